@@ -0,0 +1,71 @@
+//! Frame time of the bookmarks table as the library grows, it's expected to stay flat.
+//!
+//! Run with `cargo bench --bench render`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use fbmark::{
+    app::App,
+    models::{bookmarks::Bookmark, tags::Tag},
+    ui::components::scrolling::{column_widths, render_bookmarks_table, visible_rows},
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    widgets::{StatefulWidget, TableState},
+};
+
+const LIBRARY_SIZES: [usize; 4] = [1_000, 10_000, 50_000, 100_000];
+const TAGS: usize = 50;
+
+fn library(size: usize) -> App {
+    let tags: Vec<Tag> = (0..TAGS)
+        .map(|id| Tag::new(id as i64, format!("tag-{id}")))
+        .collect();
+    let bookmarks = (0..size)
+        .map(|id| {
+            Bookmark::new(
+                id as i64,
+                Some(format!("A bookmark number {id} with a long enough title")),
+                format!("https://example{}.com/articles/{id}", id % 100),
+                Some(vec![tags[id % TAGS].clone(), tags[(id * 7) % TAGS].clone()]),
+                "unread",
+                Some(1_700_000_000 + id as i64),
+                None,
+            )
+        })
+        .collect();
+
+    let mut app = App::new();
+    app.set_library(bookmarks, tags, vec![], vec![]);
+    // somewhere in the middle, far from the first rows
+    app.bookmarks_items.state.select(Some(size / 2));
+    app
+}
+
+fn bench_frame(c: &mut Criterion) {
+    let area = Rect::new(0, 0, 160, 50);
+    let mut group = c.benchmark_group("bookmarks_table_frame");
+
+    for size in LIBRARY_SIZES {
+        let mut app = library(size);
+        let widths = column_widths(&app.columns);
+        let mut buffer = Buffer::empty(area);
+
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            b.iter(|| {
+                let rows = visible_rows(&mut app, area);
+                let mut state = TableState::default().with_selected(
+                    app.bookmarks_items
+                        .state
+                        .selected()
+                        .and_then(|index| index.checked_sub(rows.start)),
+                );
+                render_bookmarks_table(&app, &widths, rows).render(area, &mut buffer, &mut state);
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_frame);
+criterion_main!(benches);
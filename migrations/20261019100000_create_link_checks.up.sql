@@ -0,0 +1,8 @@
+CREATE TABLE link_checks (
+    bookmark_id INTEGER PRIMARY KEY REFERENCES bookmarks(id) ON DELETE CASCADE,
+    status_code INTEGER,
+    final_url VARCHAR(255),
+    error TEXT,
+    -- unix timestamp of the check
+    checked_at INTEGER NOT NULL
+);
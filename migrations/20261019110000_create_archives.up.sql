@@ -0,0 +1,11 @@
+CREATE TABLE archives (
+    bookmark_id INTEGER PRIMARY KEY REFERENCES bookmarks(id) ON DELETE CASCADE,
+    -- where the page was downloaded from, after the redirects
+    url VARCHAR(255) NOT NULL,
+    -- gzip-compressed HTML of the page
+    content BLOB NOT NULL,
+    -- size of the HTML before compression
+    size INTEGER NOT NULL,
+    -- unix timestamp of the download
+    archived_at INTEGER NOT NULL
+);
@@ -0,0 +1,7 @@
+CREATE TABLE page_texts (
+    bookmark_id INTEGER PRIMARY KEY REFERENCES bookmarks(id) ON DELETE CASCADE,
+    -- readable text of the main content of the page
+    text TEXT NOT NULL,
+    -- unix timestamp of the extraction
+    extracted_at INTEGER NOT NULL
+);
@@ -0,0 +1,2 @@
+ALTER TABLE bookmarks DROP COLUMN created_at;
+ALTER TABLE bookmarks DROP COLUMN status;
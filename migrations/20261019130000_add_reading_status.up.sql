@@ -0,0 +1,4 @@
+-- 'unread', 'read' or 'archived', the bookmarks saved before are considered read
+ALTER TABLE bookmarks ADD COLUMN status VARCHAR(16) NOT NULL DEFAULT 'read';
+-- unix timestamp of the creation, unknown for the bookmarks saved before
+ALTER TABLE bookmarks ADD COLUMN created_at INTEGER;
@@ -0,0 +1,2 @@
+-- unix timestamp of pinning, NULL for the bookmarks that aren't pinned
+ALTER TABLE bookmarks ADD COLUMN pinned_at INTEGER;
@@ -0,0 +1 @@
+ALTER TABLE tags DROP COLUMN parent_id;
@@ -0,0 +1,30 @@
+-- tags are namespaced by slashes, e.g. 'lang/rust' is a child of 'lang'
+ALTER TABLE tags ADD COLUMN parent_id INTEGER REFERENCES tags(id) ON DELETE SET NULL;
+
+-- create the missing ancestors of the existing tags,
+-- rtrim(name, replace(name, '/', '')) cuts the last segment off and keeps the slash before it
+WITH RECURSIVE ancestors(prefix) AS (
+    SELECT rtrim(name, replace(name, '/', ''))
+    FROM tags
+    WHERE instr(name, '/') > 0
+    UNION
+    SELECT rtrim(
+        substr(prefix, 1, length(prefix) - 1),
+        replace(substr(prefix, 1, length(prefix) - 1), '/', '')
+    )
+    FROM ancestors
+    WHERE instr(substr(prefix, 1, length(prefix) - 1), '/') > 0
+)
+INSERT OR IGNORE INTO tags (name)
+SELECT substr(prefix, 1, length(prefix) - 1)
+FROM ancestors
+-- a leading slash has nothing before it
+WHERE prefix <> '/';
+
+UPDATE tags
+SET parent_id = (
+    SELECT parent.id
+    FROM tags parent
+    WHERE parent.name || '/' = rtrim(tags.name, replace(tags.name, '/', ''))
+)
+WHERE instr(name, '/') > 0;
@@ -0,0 +1,2 @@
+ALTER TABLE tags DROP COLUMN pack_id;
+DROP TABLE packs;
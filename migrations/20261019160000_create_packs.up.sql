@@ -0,0 +1,8 @@
+CREATE TABLE packs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    name VARCHAR(255) UNIQUE NOT NULL,
+    -- hue of the color in degrees, the way Tagpacker gives it
+    color INTEGER NOT NULL DEFAULT 0
+);
+
+ALTER TABLE tags ADD COLUMN pack_id INTEGER REFERENCES packs(id) ON DELETE SET NULL;
@@ -0,0 +1,4 @@
+CREATE TABLE tag_aliases (
+    name VARCHAR(255) PRIMARY KEY NOT NULL COLLATE NOCASE,
+    tag_id INTEGER NOT NULL REFERENCES tags(id) ON DELETE CASCADE
+);
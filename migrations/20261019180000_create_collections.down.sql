@@ -0,0 +1,2 @@
+ALTER TABLE bookmarks DROP COLUMN collection_id;
+DROP TABLE collections;
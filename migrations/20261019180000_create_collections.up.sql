@@ -0,0 +1,9 @@
+CREATE TABLE collections (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    parent_id INTEGER REFERENCES collections(id) ON DELETE CASCADE,
+    -- order among the siblings
+    position INTEGER NOT NULL DEFAULT 0
+);
+
+ALTER TABLE bookmarks ADD COLUMN collection_id INTEGER REFERENCES collections(id) ON DELETE SET NULL;
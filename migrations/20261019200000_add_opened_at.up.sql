@@ -0,0 +1 @@
+ALTER TABLE bookmarks ADD COLUMN opened_at INTEGER;
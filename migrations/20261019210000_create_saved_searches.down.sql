@@ -0,0 +1,2 @@
+DROP TABLE saved_search_tags;
+DROP TABLE saved_searches;
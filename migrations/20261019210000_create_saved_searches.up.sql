@@ -0,0 +1,15 @@
+CREATE TABLE saved_searches (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    name VARCHAR(255) UNIQUE NOT NULL,
+    title_query TEXT NOT NULL DEFAULT '',
+    link_query TEXT NOT NULL DEFAULT '',
+    -- column the results are sorted by, in the order of the search otherwise
+    sort_column VARCHAR(255),
+    sort_descending BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE TABLE saved_search_tags (
+    saved_search_id INTEGER NOT NULL REFERENCES saved_searches(id) ON DELETE CASCADE,
+    tag_id INTEGER NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    PRIMARY KEY (saved_search_id, tag_id)
+);
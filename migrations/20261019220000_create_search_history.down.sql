@@ -0,0 +1,2 @@
+DROP TABLE search_history_tags;
+DROP TABLE search_history;
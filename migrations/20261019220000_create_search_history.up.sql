@@ -0,0 +1,11 @@
+CREATE TABLE search_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    title_query TEXT NOT NULL DEFAULT '',
+    link_query TEXT NOT NULL DEFAULT ''
+);
+
+CREATE TABLE search_history_tags (
+    search_id INTEGER NOT NULL REFERENCES search_history(id) ON DELETE CASCADE,
+    tag_id INTEGER NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    PRIMARY KEY (search_id, tag_id)
+);
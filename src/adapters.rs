@@ -1,2 +1,9 @@
+pub mod archiver;
+pub mod browser;
+pub mod clipboard;
+pub mod http;
+pub mod linkcheck;
+pub mod metadata;
+pub mod readability;
 pub mod sqlite;
 pub mod tagpacker;
@@ -0,0 +1,271 @@
+use std::{
+    collections::HashSet,
+    io::{Read, Write},
+};
+
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::Utc;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use futures::{stream, StreamExt};
+use scraper::{Html, Selector};
+use url::Url;
+
+use crate::{
+    adapters::http::{Fetcher, Resource},
+    models::archives::Archive,
+    settings::Archives,
+};
+
+// Amount of stylesheets and images downloaded at the same time
+const RESOURCE_CONCURRENCY: usize = 4;
+
+/// Downloads the page, makes it self-contained if the settings ask for it and compresses it.
+pub async fn archive_page(
+    fetcher: &Fetcher,
+    bookmark_id: i64,
+    url: &Url,
+    settings: &Archives,
+) -> Result<Archive> {
+    let page = fetcher.fetch_html(url).await?;
+
+    let mut html = page.html;
+    if settings.inline_resources {
+        html = inline_resources(fetcher, &html, &page.url, settings.max_archive_bytes).await;
+    }
+    let html = add_base(&html, &page.url);
+
+    Ok(Archive {
+        bookmark_id,
+        url: page.url.to_string(),
+        content: compress(&html)?,
+        size: i64::try_from(html.len())?,
+        archived_at: Utc::now(),
+    })
+}
+
+/// HTML of the archived page.
+pub fn unpack(archive: &Archive) -> Result<String> {
+    let mut html = String::new();
+    GzDecoder::new(archive.content.as_slice()).read_to_string(&mut html)?;
+    Ok(html)
+}
+
+/// Writes the copy into a temporary file and opens it in the browser.
+pub fn open_archive(archive: &Archive) -> Result<()> {
+    let path = std::env::temp_dir().join(format!("fbmark-archive-{}.html", archive.bookmark_id));
+    std::fs::write(&path, unpack(archive)?)?;
+    open::that_detached(&path)?;
+    Ok(())
+}
+
+fn compress(html: &str) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(html.as_bytes())?;
+    Ok(encoder.finish()?)
+}
+
+/// Replaces the links to the stylesheets and images with `data:` URLs,
+/// in the order of the page while the copy stays within `max_bytes`.
+///
+/// The files that fail to download or don't fit keep pointing to the site, as well as
+/// whatever the stylesheets refer to, e.g. fonts.
+async fn inline_resources(fetcher: &Fetcher, html: &str, base: &Url, max_bytes: usize) -> String {
+    let inlined: Vec<(String, String)> = stream::iter(resource_links(html, base))
+        .map(|(link, url)| async move {
+            let resource = fetcher.fetch_resource(&url).await.ok()?;
+            Some((link, data_url(&resource)))
+        })
+        .buffered(RESOURCE_CONCURRENCY)
+        .filter_map(|inlined| async move { inlined })
+        .collect()
+        .await;
+
+    let mut html = html.to_string();
+    for (link, data_url) in inlined {
+        // the attribute may be written with either quotes and with escaped ampersands
+        let escaped = link.replace('&', "&amp;");
+        let mut replaced = html.clone();
+        for link in [&link, &escaped] {
+            replaced = replaced
+                .replace(&format!("\"{link}\""), &format!("\"{data_url}\""))
+                .replace(&format!("'{link}'"), &format!("'{data_url}'"));
+        }
+        // a smaller file further down may fit still
+        if replaced.len() <= max_bytes {
+            html = replaced;
+        }
+    }
+    html
+}
+
+/// Stylesheets and images of the page: the links as written and where they point to.
+fn resource_links(html: &str, base: &Url) -> Vec<(String, Url)> {
+    let document = Html::parse_document(html);
+    let select_attrs = |selector: &str, attr: &str| -> Vec<String> {
+        let Ok(selector) = Selector::parse(selector) else {
+            return vec![];
+        };
+        document
+            .select(&selector)
+            .filter_map(|element| element.value().attr(attr))
+            .map(ToString::to_string)
+            .collect()
+    };
+
+    // the page may say where its relative links lead
+    let base = select_attrs("base[href]", "href")
+        .first()
+        .and_then(|href| base.join(href).ok())
+        .unwrap_or_else(|| base.clone());
+
+    let mut seen = HashSet::new();
+    select_attrs(r#"link[rel~="stylesheet"][href]"#, "href")
+        .into_iter()
+        .chain(select_attrs("img[src]", "src"))
+        .filter(|link| !link.is_empty() && !link.starts_with("data:"))
+        .filter(|link| seen.insert(link.clone()))
+        .filter_map(|link| base.join(&link).ok().map(|url| (link, url)))
+        .collect()
+}
+
+fn data_url(resource: &Resource) -> String {
+    let content_type = resource
+        .content_type
+        .as_deref()
+        .unwrap_or("application/octet-stream")
+        .replace(' ', "");
+    format!(
+        "data:{content_type};base64,{}",
+        STANDARD.encode(&resource.bytes)
+    )
+}
+
+/// Points the relative links of the copy to the original site.
+fn add_base(html: &str, url: &Url) -> String {
+    // lowercasing ASCII keeps the offsets valid for the original
+    let lowercase = html.to_ascii_lowercase();
+    if lowercase.contains("<base") {
+        return html.to_string();
+    }
+
+    let tag = format!(r#"<base href="{url}">"#);
+    let head_end = lowercase
+        .match_indices("<head")
+        .find(|(start, _)| {
+            lowercase[start + "<head".len()..]
+                .starts_with(|c: char| c == '>' || c.is_ascii_whitespace())
+        })
+        .and_then(|(start, _)| lowercase[start..].find('>').map(|end| start + end + 1));
+
+    match head_end {
+        Some(index) => format!("{}{tag}{}", &html[..index], &html[index..]),
+        None => format!("{tag}{html}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        adapters::http::fixture::{serve, Reply},
+        settings::Http,
+    };
+
+    fn file(content_type: &'static str, body: &[u8]) -> Reply {
+        Reply {
+            content_type,
+            body: body.to_vec(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    fn fetcher() -> Fetcher {
+        Fetcher::new(&Http {
+            timeout_secs: 5,
+            max_page_bytes: 64 * 1024,
+        })
+        .unwrap()
+    }
+
+    fn settings(max_archive_bytes: usize) -> Archives {
+        Archives {
+            inline_resources: true,
+            max_archive_bytes,
+            on_create: false,
+        }
+    }
+
+    #[test]
+    fn puts_the_base_into_the_head() {
+        let url = Url::parse("https://example.com/docs/").unwrap();
+        assert_eq!(
+            add_base(r#"<html><HEAD lang="en"><title>Docs</title></HEAD>"#, &url),
+            r#"<html><HEAD lang="en"><base href="https://example.com/docs/"><title>Docs</title></HEAD>"#
+        );
+        // a header isn't the head
+        assert_eq!(
+            add_base("<header>Docs</header>", &url),
+            r#"<base href="https://example.com/docs/"><header>Docs</header>"#
+        );
+    }
+
+    #[test]
+    fn keeps_the_base_of_the_page() {
+        let url = Url::parse("https://example.com/docs/").unwrap();
+        let html = r#"<head><BASE href="https://cdn.example.com/"></head>"#;
+        assert_eq!(add_base(html, &url), html);
+    }
+
+    #[tokio::test]
+    async fn inlines_the_stylesheets_and_the_images() {
+        let page = r#"<html><head><link rel="stylesheet" href="/style.css"></head>
+<body><img src='logo.png?size=1&amp;v=2'><img src="/gone.png"></body></html>"#;
+        let root = serve(vec![
+            ("/docs/page", Reply::html(page)),
+            ("/style.css", file("text/css", b"body { margin: 0 }")),
+            ("/docs/logo.png?size=1&v=2", file("image/png", b"PNG")),
+        ])
+        .await;
+        let url = root.join("/docs/page").unwrap();
+
+        let archive = archive_page(&fetcher(), 1, &url, &settings(1024 * 1024))
+            .await
+            .unwrap();
+        let html = unpack(&archive).unwrap();
+
+        let css = format!(
+            "data:text/css;base64,{}",
+            STANDARD.encode("body { margin: 0 }")
+        );
+        assert!(html.contains(&format!(r#"href="{css}""#)), "{html}");
+        let png = format!("data:image/png;base64,{}", STANDARD.encode("PNG"));
+        assert!(html.contains(&format!("src='{png}'")), "{html}");
+        // the missing image still points to the site, through the base
+        assert!(html.contains(r#"src="/gone.png""#), "{html}");
+        assert!(html.contains(&format!(r#"<base href="{url}">"#)), "{html}");
+        assert_eq!(archive.size, html.len() as i64);
+    }
+
+    #[tokio::test]
+    async fn leaves_what_doesnt_fit_as_links() {
+        let page = r#"<img src="/photo.jpg"><link rel="stylesheet" href="/style.css">"#;
+        let root = serve(vec![
+            ("/page", Reply::html(page)),
+            ("/photo.jpg", file("image/jpeg", &[0; 4000])),
+            ("/style.css", file("text/css", b"body { margin: 0 }")),
+        ])
+        .await;
+        let url = root.join("/page").unwrap();
+
+        let archive = archive_page(&fetcher(), 1, &url, &settings(page.len() + 1000))
+            .await
+            .unwrap();
+        let html = unpack(&archive).unwrap();
+
+        assert!(html.contains(r#"src="/photo.jpg""#), "{html}");
+        assert!(html.contains("data:text/css;base64,"), "{html}");
+    }
+}
@@ -0,0 +1,152 @@
+use std::path::Path;
+
+use anyhow::Result;
+use chrono::DateTime;
+use scraper::{ElementRef, Html, Selector};
+use url::Url;
+
+use crate::models::{bookmarks::NewBookmarkParams, tags::NewTagParams};
+
+/// Reads the bookmarks a browser exported into an HTML file.
+pub async fn get_links(path: &Path) -> Result<Vec<NewBookmarkParams>> {
+    let html = tokio::fs::read_to_string(path).await?;
+    Ok(parse_bookmarks(&html))
+}
+
+/// Parses the bookmarks file in the Netscape format every browser exports,
+/// the folders the links are kept in become their collection.
+pub fn parse_bookmarks(html: &str) -> Vec<NewBookmarkParams> {
+    let document = Html::parse_document(html);
+    let Ok(links) = Selector::parse("a[href]") else {
+        return Vec::new();
+    };
+
+    document
+        .select(&links)
+        .filter_map(|link| {
+            let url = Url::parse(link.value().attr("href")?).ok()?;
+            // smart bookmarks and bookmarklets aren't pages
+            if !matches!(url.scheme(), "http" | "https") {
+                return None;
+            }
+
+            let title = link.text().collect::<String>().trim().to_string();
+            // Firefox keeps the tags along with the folders
+            let tags = link
+                .value()
+                .attr("tags")
+                .into_iter()
+                .flat_map(|tags| tags.split(','))
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(|tag| NewTagParams::from(tag.to_string()))
+                .collect();
+
+            // seconds since the epoch
+            let created_at = link
+                .value()
+                .attr("add_date")
+                .and_then(|date| date.trim().parse().ok())
+                .and_then(|seconds| DateTime::from_timestamp(seconds, 0));
+
+            Some(
+                NewBookmarkParams::new(
+                    (!title.is_empty()).then_some(title),
+                    url,
+                    tags,
+                    folders_of(link),
+                    description_of(link),
+                )
+                .created_at(created_at),
+            )
+        })
+        .collect()
+}
+
+/// The note about the link, it's kept in `DD` right after the `DT` of the link.
+fn description_of(link: ElementRef) -> Option<String> {
+    let item = link.parent()?;
+    let next = item.next_siblings().find_map(ElementRef::wrap)?;
+    if next.value().name() != "dd" {
+        return None;
+    }
+    let description = next.text().collect::<String>().trim().to_string();
+    (!description.is_empty()).then_some(description)
+}
+
+/// Names of the folders around the link, the outermost first.
+fn folders_of(link: ElementRef) -> Vec<String> {
+    // a folder is a `DT` with the name in `H3` followed by the `DL` of its contents
+    let mut folders: Vec<String> = link
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .filter(|element| element.value().name() == "dt")
+        .filter_map(|folder| {
+            folder
+                .children()
+                .filter_map(ElementRef::wrap)
+                .find(|child| child.value().name() == "h3")
+        })
+        .map(|name| name.text().collect::<String>().trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect();
+    folders.reverse();
+    folders
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    const EXPORT: &str = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<META HTTP-EQUIV="Content-Type" CONTENT="text/html; charset=UTF-8">
+<TITLE>Bookmarks</TITLE>
+<H1>Bookmarks Menu</H1>
+<DL><p>
+    <DT><A HREF="https://example.com/">Example</A>
+    <DT><H3>Dev</H3>
+    <DL><p>
+        <DT><A HREF="https://doc.rust-lang.org/book/" ADD_DATE="1696240000" TAGS="rust,docs">The Rust book</A>
+        <DD>Read it twice
+        <DT><H3>Async</H3>
+        <DL><p>
+            <DT><A HREF="https://tokio.rs/">Tokio</A>
+        </DL><p>
+        <DT><A HREF="https://go.dev/">Go</A>
+        <DT><A HREF="javascript:alert(1)">Bookmarklet</A>
+    </DL><p>
+</DL>
+"#;
+
+    #[tokio::test]
+    async fn maps_the_folders_to_the_collection() {
+        let path = env::temp_dir().join(format!("fbmark-bookmarks-{}.html", std::process::id()));
+        std::fs::write(&path, EXPORT).unwrap();
+        let links = get_links(&path).await.unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let collections: Vec<_> = links
+            .iter()
+            .map(|link| (link.url.as_str(), link.collection.join("/")))
+            .collect();
+        assert_eq!(
+            collections,
+            vec![
+                ("https://example.com/", String::new()),
+                ("https://doc.rust-lang.org/book/", "Dev".to_string()),
+                ("https://tokio.rs/", "Dev/Async".to_string()),
+                ("https://go.dev/", "Dev".to_string()),
+            ]
+        );
+
+        let book = &links[1];
+        assert_eq!(book.title.as_deref(), Some("The Rust book"));
+        assert_eq!(book.description.as_deref(), Some("Read it twice"));
+        let tags: Vec<_> = book.tags.iter().map(|tag| tag.name.as_str()).collect();
+        assert_eq!(tags, vec!["rust", "docs"]);
+        assert_eq!(book.created_at.map(|at| at.timestamp()), Some(1696240000));
+        assert!(links[0].created_at.is_none());
+    }
+}
@@ -0,0 +1,101 @@
+use std::{
+    env,
+    io::{self, Read, Write},
+    process::{Command, Stdio},
+};
+
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+/// Asks the terminal to put the text into the clipboard with an OSC 52 sequence,
+/// it reaches the clipboard of the local machine over SSH as well.
+///
+/// It's written to the same stream the interface is drawn to, so it must not run
+/// while a frame is being drawn.
+pub fn copy_to_terminal(text: &str) -> Result<()> {
+    let mut sequence = format!("\x1b]52;c;{}\x07", STANDARD.encode(text));
+    // tmux passes the sequence on only when it's wrapped
+    if env::var_os("TMUX").is_some() {
+        sequence = format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"));
+    }
+
+    let mut stderr = io::stderr();
+    stderr.write_all(sequence.as_bytes())?;
+    stderr.flush()?;
+    Ok(())
+}
+
+/// Copies the text with `wl-copy` or `xclip`, for the terminals that ignore OSC 52.
+/// Nothing happens without a display to copy to.
+pub fn copy_with_program(text: &str) -> Result<()> {
+    let Some((program, args)) = copy_program() else {
+        return Ok(());
+    };
+
+    let mut child = match Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(child) => child,
+        // OSC 52 is all there is then
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(error) => return Err(error).with_context(|| format!("Couldn't run {program}")),
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    // both of them stay in the background to serve the clipboard, so it doesn't take long
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(anyhow!("{program} failed with {status}"));
+    }
+    Ok(())
+}
+
+/// The text in the clipboard, read with `wl-paste` or `xclip` as terminals don't answer OSC 52 queries.
+pub fn paste() -> Result<String> {
+    let (program, args) = paste_program().ok_or_else(|| {
+        anyhow!("There's no display to paste from, the terminal's paste works over SSH")
+    })?;
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Couldn't run {program}, is it installed?"))?;
+    let mut text = String::new();
+    if let Some(mut stdout) = child.stdout.take() {
+        stdout.read_to_string(&mut text)?;
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(anyhow!(
+            "{program} failed with {status}, is the clipboard empty?"
+        ));
+    }
+    Ok(text)
+}
+
+fn copy_program() -> Option<(&'static str, &'static [&'static str])> {
+    if env::var_os("WAYLAND_DISPLAY").is_some() {
+        Some(("wl-copy", &[]))
+    } else if env::var_os("DISPLAY").is_some() {
+        Some(("xclip", &["-selection", "clipboard"]))
+    } else {
+        None
+    }
+}
+
+fn paste_program() -> Option<(&'static str, &'static [&'static str])> {
+    if env::var_os("WAYLAND_DISPLAY").is_some() {
+        Some(("wl-paste", &["--no-newline"]))
+    } else if env::var_os("DISPLAY").is_some() {
+        Some(("xclip", &["-selection", "clipboard", "-o"]))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,281 @@
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use reqwest::{header::CONTENT_TYPE, Client, Response};
+use url::Url;
+
+use crate::settings::{Http, Settings};
+
+/// A downloaded page, cut to the configured size limit.
+#[derive(Debug)]
+pub struct Page {
+    /// Where the request ended up after the redirects.
+    pub url: Url,
+    pub html: String,
+    /// Whether the page was larger than the limit and got cut.
+    pub truncated: bool,
+}
+
+/// A file the page refers to, e.g. a stylesheet or an image.
+#[derive(Debug)]
+pub struct Resource {
+    pub content_type: Option<String>,
+    pub bytes: Vec<u8>,
+}
+
+/// HTTP client shared by everything that downloads pages.
+#[derive(Debug, Clone)]
+pub struct Fetcher {
+    client: Client,
+    max_page_bytes: usize,
+}
+
+impl Fetcher {
+    pub fn new(settings: &Http) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(settings.timeout_secs))
+            .user_agent(concat!(
+                env!("CARGO_PKG_NAME"),
+                "/",
+                env!("CARGO_PKG_VERSION")
+            ))
+            .build()?;
+
+        Ok(Self {
+            client,
+            max_page_bytes: settings.max_page_bytes,
+        })
+    }
+
+    /// Takes the limits from the config file, falls back to the defaults if there's none.
+    pub fn from_settings() -> Result<Self> {
+        let settings = Settings::get_configuration()
+            .map(|settings| settings.http)
+            .unwrap_or_default();
+        Self::new(&settings)
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    pub async fn fetch_html(&self, url: &Url) -> Result<Page> {
+        let mut response = self
+            .client
+            .get(url.clone())
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        if !content_type.is_empty() && !content_type.contains("html") {
+            bail!("{url} is not a web page but {content_type}");
+        }
+
+        let url = response.url().clone();
+        let (body, truncated) = self.read_body(&mut response).await?;
+
+        Ok(Page {
+            url,
+            html: String::from_utf8_lossy(&body).into_owned(),
+            truncated,
+        })
+    }
+
+    /// Downloads a file as is, the ones larger than the page size limit are refused.
+    pub async fn fetch_resource(&self, url: &Url) -> Result<Resource> {
+        let mut response = self
+            .client
+            .get(url.clone())
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(ToString::to_string);
+        let (bytes, truncated) = self.read_body(&mut response).await?;
+        if truncated {
+            bail!("{url} is larger than {} bytes", self.max_page_bytes);
+        }
+
+        Ok(Resource {
+            content_type,
+            bytes,
+        })
+    }
+
+    /// Reads the body up to the size limit, tells whether it had to be cut.
+    async fn read_body(&self, response: &mut Response) -> Result<(Vec<u8>, bool)> {
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            let room = self.max_page_bytes - body.len();
+            if chunk.len() > room {
+                body.extend_from_slice(&chunk[..room]);
+                return Ok((body, true));
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        Ok((body, false))
+    }
+}
+
+/// A local HTTP server with canned responses for the tests of the downloads.
+#[cfg(test)]
+pub mod fixture {
+    use std::{collections::HashMap, sync::Arc, time::Duration};
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+    use url::Url;
+
+    #[derive(Debug, Clone)]
+    pub struct Reply {
+        pub content_type: &'static str,
+        pub body: Vec<u8>,
+        /// Pause before the response is sent.
+        pub delay: Duration,
+    }
+
+    impl Reply {
+        pub fn html(body: impl Into<String>) -> Self {
+            Self {
+                content_type: "text/html; charset=utf-8",
+                body: body.into().into_bytes(),
+                delay: Duration::ZERO,
+            }
+        }
+
+        pub fn delayed(self, delay: Duration) -> Self {
+            Self { delay, ..self }
+        }
+    }
+
+    /// Serves the replies by their paths, anything else is not found. Returns the root of the server.
+    pub async fn serve(routes: Vec<(&'static str, Reply)>) -> Url {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let routes: Arc<HashMap<&str, Reply>> = Arc::new(routes.into_iter().collect());
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let routes = routes.clone();
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buffer = [0; 1024];
+                    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                        match stream.read(&mut buffer).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(read) => request.extend_from_slice(&buffer[..read]),
+                        }
+                    }
+                    let request = String::from_utf8_lossy(&request);
+                    let path = request.split_whitespace().nth(1).unwrap_or("/");
+
+                    let (status, reply) = match routes.get(path) {
+                        Some(reply) => ("200 OK", reply.clone()),
+                        None => ("404 Not Found", Reply::html("")),
+                    };
+                    tokio::time::sleep(reply.delay).await;
+                    let head = format!(
+                        "HTTP/1.1 {status}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        reply.content_type,
+                        reply.body.len()
+                    );
+                    let _ = stream.write_all(head.as_bytes()).await;
+                    let _ = stream.write_all(&reply.body).await;
+                });
+            }
+        });
+
+        Url::parse(&format!("http://{address}/")).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        fixture::{serve, Reply},
+        *,
+    };
+
+    fn fetcher(timeout_secs: u64, max_page_bytes: usize) -> Fetcher {
+        Fetcher::new(&Http {
+            timeout_secs,
+            max_page_bytes,
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn fetches_the_page() {
+        let root = serve(vec![("/page", Reply::html("<title>Page</title>"))]).await;
+        let url = root.join("/page").unwrap();
+
+        let page = fetcher(5, 1024).fetch_html(&url).await.unwrap();
+        assert_eq!(page.url, url);
+        assert_eq!(page.html, "<title>Page</title>");
+        assert!(!page.truncated);
+    }
+
+    #[tokio::test]
+    async fn gives_up_on_a_slow_page() {
+        let slow = Reply::html("<title>Late</title>").delayed(Duration::from_secs(3));
+        let root = serve(vec![("/slow", slow)]).await;
+
+        let error = fetcher(1, 1024)
+            .fetch_html(&root.join("/slow").unwrap())
+            .await
+            .unwrap_err();
+        let error = error.downcast_ref::<reqwest::Error>().unwrap();
+        assert!(error.is_timeout(), "{error:?}");
+    }
+
+    #[tokio::test]
+    async fn cuts_the_page_at_the_size_limit() {
+        let root = serve(vec![("/large", Reply::html("a".repeat(10_000)))]).await;
+        let url = root.join("/large").unwrap();
+        let fetcher = fetcher(5, 1000);
+
+        let page = fetcher.fetch_html(&url).await.unwrap();
+        assert!(page.truncated);
+        assert_eq!(page.html.len(), 1000);
+
+        // a cut file is of no use
+        let error = fetcher.fetch_resource(&url).await.unwrap_err();
+        assert!(error.to_string().contains("larger than 1000 bytes"));
+    }
+
+    #[tokio::test]
+    async fn refuses_what_isnt_a_page() {
+        let image = Reply {
+            content_type: "image/png",
+            ..Reply::html("png")
+        };
+        let root = serve(vec![("/image.png", image)]).await;
+
+        let error = fetcher(5, 1024)
+            .fetch_html(&root.join("/image.png").unwrap())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("not a web page"));
+    }
+
+    #[tokio::test]
+    async fn reports_a_missing_page() {
+        let root = serve(vec![]).await;
+        let result = fetcher(5, 1024)
+            .fetch_html(&root.join("/missing").unwrap())
+            .await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,227 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use chrono::Utc;
+use futures::{stream, Stream, StreamExt};
+use reqwest::{Method, StatusCode};
+use tokio::time::{sleep_until, Instant};
+use url::Url;
+
+use crate::{adapters::http::Fetcher, models::link_checks::LinkCheck, settings::LinkChecks};
+
+/// Checks the links concurrently, at most `settings.concurrency` requests at once
+/// and not more often than once per `settings.per_host_interval_ms` for the same host.
+///
+/// Results come in the order the checks finish.
+pub fn check_links(
+    fetcher: Fetcher,
+    links: Vec<(i64, Url)>,
+    settings: &LinkChecks,
+) -> impl Stream<Item = LinkCheck> {
+    let interval = Duration::from_millis(settings.per_host_interval_ms);
+    let hosts: HashMap<String, Arc<HostLimit>> = links
+        .iter()
+        .map(|(_, url)| (host_key(url), Arc::new(HostLimit::new(interval))))
+        .collect();
+
+    // the turn of the host is waited for before the check takes a slot,
+    // so the checks of the other hosts aren't held up by the waiting ones
+    stream::iter(interleave_by_host(links))
+        .then(move |(bookmark_id, url)| {
+            let host = hosts[&host_key(&url)].clone();
+            async move {
+                host.wait().await;
+                (bookmark_id, url, host)
+            }
+        })
+        .map(move |(bookmark_id, url, host)| {
+            let fetcher = fetcher.clone();
+            async move { check_link(&fetcher, &host, bookmark_id, &url).await }
+        })
+        .buffer_unordered(settings.concurrency.max(1))
+}
+
+/// Takes a link of every host in turn, so the turns of the hosts come side by side
+/// rather than one host after another.
+fn interleave_by_host(links: Vec<(i64, Url)>) -> Vec<(i64, Url)> {
+    let mut taken: HashMap<String, usize> = HashMap::new();
+    let mut rounds: Vec<(usize, (i64, Url))> = links
+        .into_iter()
+        .map(|link| {
+            let count = taken.entry(host_key(&link.1)).or_default();
+            *count += 1;
+            (*count, link)
+        })
+        .collect();
+    // stable, the hosts keep their order within a round
+    rounds.sort_by_key(|(round, _)| *round);
+    rounds.into_iter().map(|(_, link)| link).collect()
+}
+
+fn host_key(url: &Url) -> String {
+    url.host_str().unwrap_or_default().to_string()
+}
+
+/// Spaces the requests to the same host by the interval.
+struct HostLimit {
+    interval: Duration,
+    next_request: Mutex<Option<Instant>>,
+}
+
+impl HostLimit {
+    fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            next_request: Mutex::new(None),
+        }
+    }
+
+    /// Takes the next free turn and sleeps until it comes,
+    /// the other requests to the host take their turns meanwhile.
+    async fn wait(&self) {
+        let turn = {
+            let mut next_request = self
+                .next_request
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let turn = next_request.map_or(Instant::now(), |next| next.max(Instant::now()));
+            *next_request = Some(turn + self.interval);
+            turn
+        };
+        sleep_until(turn).await;
+    }
+}
+
+/// Asks for the headers first, some servers don't support `HEAD` though,
+/// so the page is requested with `GET` if it fails. The turn of the host
+/// for the first request has been taken already.
+async fn check_link(fetcher: &Fetcher, host: &HostLimit, bookmark_id: i64, url: &Url) -> LinkCheck {
+    let response = match request(fetcher, Method::HEAD, url).await {
+        Ok(response) if !needs_get(response.status()) => Ok(response),
+        // the host is unreachable, no reason to try again
+        Err(error) if error.is_timeout() || error.is_connect() => Err(error),
+        _ => {
+            // the second request counts against the host as well
+            host.wait().await;
+            request(fetcher, Method::GET, url).await
+        }
+    };
+
+    let (status_code, final_url, error) = match response {
+        Ok(response) => (
+            Some(response.status().as_u16()),
+            Some(response.url().to_string()),
+            None,
+        ),
+        Err(error) => (
+            error.status().map(|status| status.as_u16()),
+            error.url().map(ToString::to_string),
+            Some(error.to_string()),
+        ),
+    };
+    let final_url = final_url.filter(|final_url| final_url != url.as_str());
+
+    LinkCheck {
+        bookmark_id,
+        status_code,
+        final_url,
+        error,
+        checked_at: Utc::now(),
+    }
+}
+
+async fn request(
+    fetcher: &Fetcher,
+    method: Method,
+    url: &Url,
+) -> reqwest::Result<reqwest::Response> {
+    fetcher.client().request(method, url.clone()).send().await
+}
+
+fn needs_get(status: StatusCode) -> bool {
+    status.is_client_error() || status.is_server_error()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        adapters::http::fixture::{serve, Reply},
+        settings::Http,
+    };
+
+    fn links(urls: &[&str]) -> Vec<(i64, Url)> {
+        (1..)
+            .zip(urls)
+            .map(|(id, url)| (id, Url::parse(url).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn takes_the_hosts_in_turn() {
+        let interleaved = interleave_by_host(links(&[
+            "https://a.com/1",
+            "https://a.com/2",
+            "https://a.com/3",
+            "https://b.com/1",
+            "https://c.com/1",
+            "https://b.com/2",
+        ]));
+        let ids: Vec<i64> = interleaved.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, vec![1, 4, 5, 2, 6, 3]);
+    }
+
+    #[tokio::test]
+    async fn checks_the_other_hosts_while_one_waits() {
+        let root = serve(vec![
+            ("/1", Reply::html("")),
+            ("/2", Reply::html("")),
+            ("/3", Reply::html("")),
+        ])
+        .await;
+        let port = root.port().unwrap();
+        let busy = |path| format!("http://127.0.0.1:{port}/{path}");
+        let links = links(&[
+            &busy(1),
+            &busy(2),
+            &busy(3),
+            &format!("http://localhost:{port}/1"),
+        ]);
+        let fetcher = Fetcher::new(&Http {
+            timeout_secs: 5,
+            max_page_bytes: 1024,
+        })
+        .unwrap();
+        let settings = LinkChecks {
+            concurrency: 1,
+            per_host_interval_ms: 300,
+        };
+
+        let start = Instant::now();
+        let checks: Vec<LinkCheck> = check_links(fetcher, links, &settings).collect().await;
+        let ids: Vec<i64> = checks.iter().map(|check| check.bookmark_id).collect();
+        // the other host goes before the second turn of the busy one
+        assert_eq!(ids, vec![1, 4, 2, 3]);
+        assert!(checks.iter().all(|check| check.status_code == Some(200)));
+        assert!(start.elapsed() >= Duration::from_millis(600));
+    }
+
+    #[tokio::test]
+    async fn spaces_the_requests_to_a_host() {
+        let interval = Duration::from_millis(200);
+        let host = HostLimit::new(interval);
+        let start = Instant::now();
+
+        // the turns are taken right away, the waits run side by side
+        tokio::join!(host.wait(), host.wait(), host.wait());
+        let elapsed = start.elapsed();
+        assert!(elapsed >= interval * 2, "{elapsed:?}");
+        assert!(elapsed < interval * 3, "{elapsed:?}");
+
+        host.wait().await;
+        assert!(start.elapsed() >= interval * 3);
+    }
+}
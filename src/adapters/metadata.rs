@@ -0,0 +1,130 @@
+use anyhow::Result;
+use scraper::{Html, Selector};
+use url::Url;
+
+use crate::adapters::http::Fetcher;
+
+/// Information about a page that helps to fill in a bookmark.
+#[derive(Debug, Default, Clone)]
+pub struct PageMetadata {
+    pub title: Option<String>,
+    pub og_title: Option<String>,
+    pub description: Option<String>,
+    pub canonical_url: Option<Url>,
+    pub favicon_url: Option<Url>,
+}
+
+impl PageMetadata {
+    /// The title that suits a bookmark best, Open Graph one is usually cleaner.
+    pub fn best_title(&self) -> Option<&str> {
+        self.og_title.as_deref().or(self.title.as_deref())
+    }
+}
+
+pub async fn fetch_metadata(fetcher: &Fetcher, url: &Url) -> Result<PageMetadata> {
+    let page = fetcher.fetch_html(url).await?;
+    Ok(parse_metadata(&page.html, &page.url))
+}
+
+/// Extracts the metadata from the page, relative links are resolved against `base`.
+pub fn parse_metadata(html: &str, base: &Url) -> PageMetadata {
+    let document = Html::parse_document(html);
+
+    let select_first = |selector: &str, attr: &str| -> Option<String> {
+        let selector = Selector::parse(selector).ok()?;
+        document
+            .select(&selector)
+            .filter_map(|element| element.value().attr(attr))
+            .map(|value| value.split_whitespace().collect::<Vec<_>>().join(" "))
+            .find(|value| !value.is_empty())
+    };
+
+    let title = Selector::parse("title").ok().and_then(|selector| {
+        document
+            .select(&selector)
+            .map(|element| element.text().collect::<Vec<_>>().join(" "))
+            .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+            .find(|text| !text.is_empty())
+    });
+
+    let og_title = select_first(r#"meta[property="og:title"]"#, "content");
+    let description = select_first(r#"meta[property="og:description"]"#, "content")
+        .or_else(|| select_first(r#"meta[name="description"]"#, "content"));
+    let canonical_url =
+        select_first(r#"link[rel~="canonical"]"#, "href").and_then(|href| base.join(&href).ok());
+    let favicon_url = select_first(r#"link[rel~="icon"]"#, "href")
+        .and_then(|href| base.join(&href).ok())
+        // browsers look there if the page doesn't say anything
+        .or_else(|| base.join("/favicon.ico").ok());
+
+    PageMetadata {
+        title,
+        og_title,
+        description,
+        canonical_url,
+        favicon_url,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        adapters::http::fixture::{serve, Reply},
+        settings::Http,
+    };
+
+    const PAGE: &str = r#"<html><head>
+        <title>  The   page
+        title </title>
+        <meta property="og:title" content="Open Graph title">
+        <meta property="og:description" content="What the page is about">
+        <meta name="description" content="The plain description">
+        <link rel="canonical" href="/articles/1">
+        <link rel="shortcut icon" href="/static/icon.png">
+    </head><body></body></html>"#;
+
+    #[tokio::test]
+    async fn fetches_the_metadata() {
+        let root = serve(vec![("/articles/1?ref=feed", Reply::html(PAGE))]).await;
+        let fetcher = Fetcher::new(&Http::default()).unwrap();
+
+        let metadata = fetch_metadata(&fetcher, &root.join("/articles/1?ref=feed").unwrap())
+            .await
+            .unwrap();
+        assert_eq!(metadata.title.as_deref(), Some("The page title"));
+        assert_eq!(metadata.og_title.as_deref(), Some("Open Graph title"));
+        assert_eq!(metadata.best_title(), Some("Open Graph title"));
+        assert_eq!(
+            metadata.description.as_deref(),
+            Some("What the page is about")
+        );
+        assert_eq!(
+            metadata.canonical_url,
+            Some(root.join("/articles/1").unwrap())
+        );
+        assert_eq!(
+            metadata.favicon_url,
+            Some(root.join("/static/icon.png").unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_the_default_icon() {
+        let page = r#"<head><title>Post</title><meta name="description" content="Plain"></head>"#;
+        let root = serve(vec![("/blog/post", Reply::html(page))]).await;
+        let fetcher = Fetcher::new(&Http::default()).unwrap();
+
+        let metadata = fetch_metadata(&fetcher, &root.join("/blog/post").unwrap())
+            .await
+            .unwrap();
+        assert_eq!(
+            metadata.favicon_url,
+            Some(root.join("/favicon.ico").unwrap())
+        );
+        assert_eq!(metadata.og_title, None);
+        assert_eq!(metadata.best_title(), Some("Post"));
+        assert_eq!(metadata.description.as_deref(), Some("Plain"));
+        assert_eq!(metadata.canonical_url, None);
+    }
+}
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+
+use scraper::{ElementRef, Html, Node, Selector};
+
+// Elements that never hold the main content
+const NOISE: [&str; 13] = [
+    "script", "style", "noscript", "template", "nav", "header", "footer", "aside", "form",
+    "button", "select", "svg", "iframe",
+];
+// Elements whose text starts on a new line
+const BLOCKS: [&str; 24] = [
+    "p",
+    "div",
+    "section",
+    "article",
+    "main",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "li",
+    "ul",
+    "ol",
+    "dl",
+    "dt",
+    "dd",
+    "pre",
+    "blockquote",
+    "table",
+    "tr",
+    "figcaption",
+    "br",
+    "hr",
+];
+// Containers with less text than that are likely to be teasers, not the content itself
+const MIN_CONTENT_CHARS: usize = 200;
+
+/// Plain text of the main content of the page, roughly what the reader mode of a browser shows.
+///
+/// Blocks of the text go on separate lines, the whitespace inside them is collapsed.
+pub fn extract_text(html: &str) -> String {
+    let document = Html::parse_document(html);
+
+    let mut text = String::new();
+    if let Some(content) = main_content(&document) {
+        collect_text(content, &mut text);
+    }
+
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The element that holds the content: the one marked up as such, or the one
+/// with the most paragraph text right inside it, or the body at worst.
+fn main_content(document: &Html) -> Option<ElementRef<'_>> {
+    let select_first = |selector: &str| -> Option<ElementRef<'_>> {
+        let selector = Selector::parse(selector).ok()?;
+        document.select(&selector).next()
+    };
+
+    let marked_up = ["article", "main", r#"[role="main"]"#]
+        .into_iter()
+        .filter_map(select_first)
+        .find(|element| text_length(*element) >= MIN_CONTENT_CHARS);
+    if marked_up.is_some() {
+        return marked_up;
+    }
+
+    let paragraphs = Selector::parse("p").ok()?;
+    let mut scores = HashMap::new();
+    for paragraph in document.select(&paragraphs) {
+        if let Some(parent) = paragraph.parent() {
+            *scores.entry(parent.id()).or_insert(0) += text_length(paragraph);
+        }
+    }
+    let best = scores
+        .into_iter()
+        .filter(|(_, score)| *score >= MIN_CONTENT_CHARS)
+        .max_by_key(|(_, score)| *score)
+        .and_then(|(id, _)| document.tree.get(id))
+        .and_then(ElementRef::wrap);
+
+    best.or_else(|| select_first("body"))
+        .or_else(|| Some(document.root_element()))
+}
+
+fn text_length(element: ElementRef<'_>) -> usize {
+    element.text().map(|text| text.trim().len()).sum()
+}
+
+fn collect_text(element: ElementRef<'_>, out: &mut String) {
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) => out.push_str(text),
+            Node::Element(child_element) => {
+                let name = child_element.name();
+                if NOISE.contains(&name) {
+                    continue;
+                }
+
+                let is_block = BLOCKS.contains(&name);
+                if is_block {
+                    out.push('\n');
+                }
+                if let Some(child) = ElementRef::wrap(child) {
+                    collect_text(child, out);
+                }
+                if is_block {
+                    out.push('\n');
+                }
+            }
+            _ => {}
+        }
+    }
+}
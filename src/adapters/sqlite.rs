@@ -1,16 +1,351 @@
-use std::{env, path::Path};
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+    path::Path,
+};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use archives::Repo as ArchivesRepo;
 use async_trait::async_trait;
 use bookmarks::Repo as BookmarkRepo;
-use sqlx::{migrate::MigrateDatabase, query, query_as, sqlite::SqlitePoolOptions, SqlitePool};
+use chrono::{DateTime, Utc};
+use collections::Repo as CollectionsRepo;
+use futures::future::BoxFuture;
+use link_checks::Repo as LinkChecksRepo;
+use packs::Repo as PacksRepo;
+use page_texts::Repo as PageTextsRepo;
+use saved_searches::Repo as SavedSearchesRepo;
+use search_history::Repo as SearchHistoryRepo;
+use sqlx::{
+    migrate::MigrateDatabase, query, query_as, sqlite::SqlitePoolOptions, SqliteConnection,
+    SqlitePool,
+};
 use tags::Repo as TagsRepo;
 
 use crate::models::{
-    bookmarks::{self, Bookmark, NewBookmarkParams, RawBookmark},
+    archives::{self, Archive},
+    bookmarks::{self, Bookmark, NewBookmarkParams, RawBookmark, ReadingStatus},
+    collections::{self, Collection, NewCollectionParams},
+    link_checks::{self, BrokenLink, LinkCheck},
+    packs::{self, NewPackParams, Pack},
+    page_texts::{self, PageText},
+    saved_searches::{self, NewSavedSearchParams, SavedSearch},
+    search_history::{self, SearchHistoryEntry, HISTORY_LIMIT},
     tags::{self, Tag},
 };
 
+/// Moves the bookmarks, the children and the aliases of the tag to the other one, then deletes it.
+async fn merge_tags(conn: &mut SqliteConnection, from: &Tag, into: &Tag) -> Result<()> {
+    query!(
+        r#"
+        INSERT OR IGNORE INTO bmarks_tags
+        (bookmark_id, tag_id)
+        SELECT bookmark_id, $1
+        FROM bmarks_tags
+        WHERE tag_id = $2
+        "#,
+        into.id,
+        from.id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    move_children(conn, from, into).await?;
+
+    query!(
+        r#"
+        UPDATE OR IGNORE tag_aliases
+        SET tag_id = $1
+        WHERE tag_id = $2
+        "#,
+        into.id,
+        from.id
+    )
+    .execute(&mut *conn)
+    .await?;
+    query!(
+        r#"
+        UPDATE tags
+        SET pack_id = coalesce(pack_id, $1)
+        WHERE id = $2
+        "#,
+        from.pack_id,
+        into.id
+    )
+    .execute(&mut *conn)
+    .await?;
+    // the searches filtered by the tag would list everything once it's deleted
+    query!(
+        r#"
+        UPDATE OR IGNORE saved_search_tags
+        SET tag_id = $1
+        WHERE tag_id = $2
+        "#,
+        into.id,
+        from.id
+    )
+    .execute(&mut *conn)
+    .await?;
+    query!(
+        r#"
+        UPDATE OR IGNORE search_history_tags
+        SET tag_id = $1
+        WHERE tag_id = $2
+        "#,
+        into.id,
+        from.id
+    )
+    .execute(&mut *conn)
+    .await?;
+    query!(
+        r#"
+        DELETE FROM tags
+        WHERE id = $1
+        "#,
+        from.id
+    )
+    .execute(&mut *conn)
+    .await?;
+    // only the names that differ from the canonical one in more than case are worth keeping
+    if !from.name.eq_ignore_ascii_case(&into.name) {
+        query!(
+            r#"
+            INSERT OR REPLACE INTO tag_aliases
+            (name, tag_id)
+            VALUES ($1, $2)
+            "#,
+            from.name,
+            into.id
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Moves the children of the tag under the other one, following the path of the new parent.
+/// A child whose new name is taken already is merged into the tag of that name, its children too.
+fn move_children<'a>(
+    conn: &'a mut SqliteConnection,
+    from: &'a Tag,
+    into: &'a Tag,
+) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+        let children = query_as!(
+            Tag,
+            r#"
+            SELECT id, name, parent_id, pack_id
+            FROM tags
+            WHERE parent_id = $1
+            "#,
+            from.id
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        for child in children {
+            let leaf = child
+                .name
+                .strip_prefix(&format!("{}/", from.name))
+                .unwrap_or(&child.name);
+            let name = format!("{}/{leaf}", into.name);
+
+            let taken = query_as!(
+                Tag,
+                r#"
+                SELECT id, name, parent_id, pack_id
+                FROM tags
+                WHERE name = $1 AND id != $2
+                "#,
+                name,
+                child.id
+            )
+            .fetch_optional(&mut *conn)
+            .await?;
+            if let Some(taken) = taken {
+                merge_tags(conn, &child, &taken).await?;
+                continue;
+            }
+
+            let moved = query_as!(
+                Tag,
+                r#"
+                UPDATE tags
+                SET name = $1, parent_id = $2
+                WHERE id = $3
+                RETURNING id AS "id!", name AS "name!", parent_id AS "parent_id?", pack_id AS "pack_id?"
+                "#,
+                name,
+                into.id,
+                child.id
+            )
+            .fetch_one(&mut *conn)
+            .await?;
+            // the grandchildren keep their parent, the path of it has changed though
+            move_children(conn, &child, &moved).await?;
+        }
+
+        Ok(())
+    })
+}
+
+/// The tag with the name or the alias, case-insensitively. The exact name wins,
+/// then the alias, then the name spelled differently.
+async fn find_tag(conn: &mut SqliteConnection, name: &str) -> Result<Option<Tag>> {
+    let tag = query_as!(
+        Tag,
+        r#"
+        SELECT id AS "id!", name AS "name!", parent_id AS "parent_id?", pack_id AS "pack_id?"
+        FROM (
+            SELECT id, name, parent_id, pack_id, CASE WHEN name = $1 THEN 0 ELSE 2 END AS rank
+            FROM tags
+            WHERE name = $1 COLLATE NOCASE
+            UNION ALL
+            SELECT t.id, t.name, t.parent_id, t.pack_id, 1 AS rank
+            FROM tag_aliases a
+            JOIN tags t ON t.id = a.tag_id
+            WHERE a.name = $1
+        )
+        ORDER BY rank, id
+        LIMIT 1
+        "#,
+        name
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    Ok(tag)
+}
+
+/// Finds the tag by its name, or creates it along with the missing ancestors.
+async fn get_or_create_tag(conn: &mut SqliteConnection, name: &str) -> Result<Tag> {
+    if let Some(tag) = find_tag(conn, name).await? {
+        return Ok(tag);
+    }
+
+    let mut parent: Option<Tag> = None;
+    for path in tags::ancestry(name) {
+        // the ancestors may be spelled differently or be aliases, the path follows them
+        let segment = path.rsplit('/').next().unwrap_or(path);
+        let name = match &parent {
+            Some(parent) => format!("{}/{segment}", parent.name),
+            None => path.to_string(),
+        };
+
+        let current = match find_tag(conn, &name).await? {
+            Some(existing) => existing,
+            None => {
+                let parent_id = parent.as_ref().map(|parent| parent.id);
+                query_as!(
+                    Tag,
+                    r#"
+                    INSERT INTO tags
+                    (name, parent_id)
+                    VALUES ($1, $2)
+                    RETURNING id, name, parent_id, pack_id
+                    "#,
+                    name,
+                    parent_id
+                )
+                .fetch_one(&mut *conn)
+                .await?
+            }
+        };
+        parent = Some(current);
+    }
+
+    parent.ok_or_else(|| anyhow!("The tag has no name"))
+}
+
+/// Finds the pack by its name, or creates it with the given color.
+async fn get_or_create_pack(conn: &mut SqliteConnection, params: NewPackParams) -> Result<Pack> {
+    let existing = query!(
+        r#"
+        SELECT id, name, color
+        FROM packs
+        WHERE name = $1
+        "#,
+        params.name
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+    if let Some(record) = existing {
+        return Ok(Pack::new(record.id, record.name, record.color));
+    }
+
+    let color = i64::from(params.color);
+    let record = query!(
+        r#"
+        INSERT INTO packs
+        (name, color)
+        VALUES ($1, $2)
+        RETURNING id, name, color
+        "#,
+        params.name,
+        color
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(Pack::new(record.id, record.name, record.color))
+}
+
+/// Finds the nested collections by their names, or creates the missing ones.
+/// It gives the innermost one, there's none for an empty path.
+async fn get_or_create_collection(
+    conn: &mut SqliteConnection,
+    path: &[String],
+) -> Result<Option<i64>> {
+    let mut parent_id: Option<i64> = None;
+    for name in path {
+        let existing = query!(
+            r#"
+            SELECT id
+            FROM collections
+            WHERE name = $1 AND parent_id IS $2
+            ORDER BY position, id
+            LIMIT 1
+            "#,
+            name,
+            parent_id
+        )
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        let id = match existing {
+            Some(record) => record.id,
+            None => insert_collection(conn, name, parent_id).await?.id,
+        };
+        parent_id = Some(id);
+    }
+
+    Ok(parent_id)
+}
+
+/// Inserts the collection after its last sibling.
+async fn insert_collection(
+    conn: &mut SqliteConnection,
+    name: &str,
+    parent_id: Option<i64>,
+) -> Result<Collection> {
+    let collection = query_as!(
+        Collection,
+        r#"
+        INSERT INTO collections
+        (name, parent_id, position)
+        VALUES ($1, $2, (SELECT coalesce(max(position) + 1, 0) FROM collections WHERE parent_id IS $2))
+        RETURNING id AS "id!", name AS "name!", parent_id, position AS "position!"
+        "#,
+        name,
+        parent_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(collection)
+}
+
 #[derive(Debug, Clone)]
 pub struct Repo {
     pub pool: SqlitePool,
@@ -18,17 +353,21 @@ pub struct Repo {
 
 impl Repo {
     pub async fn new() -> Result<Self> {
-        // Create the database
         let db_url = dotenvy::var("DATABASE_URL")?;
+        Self::open(&db_url).await
+    }
 
-        if !sqlx::Sqlite::database_exists(&db_url).await? {
-            sqlx::Sqlite::create_database(&db_url).await?;
+    /// Opens the database, creating and migrating it first if needed.
+    pub async fn open(db_url: &str) -> Result<Self> {
+        // Create the database
+        if !sqlx::Sqlite::database_exists(db_url).await? {
+            sqlx::Sqlite::create_database(db_url).await?;
         }
 
         // Connect to the database
         let pool = SqlitePoolOptions::new()
             .max_connections(5)
-            .connect(&db_url)
+            .connect(db_url)
             .await?;
 
         // Migrate the database
@@ -50,150 +389,186 @@ impl Repo {
     }
 }
 
+/// Turns the row with the tags concatenated as "id,name,id,name" into a bookmark.
+fn bookmark_from_raw(record: RawBookmark) -> Bookmark {
+    let tags = record.tags.as_ref().map(|tags| {
+        tags.split(',')
+            .collect::<Vec<&str>>()
+            .chunks(2)
+            .map(|chunk| {
+                if chunk.len() > 1 {
+                    let id = chunk[0].parse::<i64>();
+                    let name = chunk[1].to_string();
+                    match id {
+                        Ok(parsed_id) => Tag::new(parsed_id, name),
+                        Err(_) => Tag::default(),
+                    }
+                } else {
+                    Tag::default()
+                }
+            })
+            .collect::<Vec<Tag>>()
+    });
+
+    Bookmark::new(
+        record.id,
+        record.title,
+        record.url.into(),
+        tags,
+        &record.status,
+        record.created_at,
+        record.pinned_at,
+    )
+    .in_collection(record.collection_id)
+    .with_description(record.description)
+    .with_opened_at(record.opened_at)
+}
+
 #[async_trait]
 impl BookmarkRepo for Repo {
     async fn fetch_all(&self, tags_filter: Vec<Tag>) -> Result<Vec<Bookmark>> {
-        // TODO: this is dreadful code duplication, though seems like it cannot be solved w/o heavy refactoring
-        // due to sqlx that behaves kinda strange in terms of Vec<Record> type conversions
-        // !!! DO NOT USE SQLX IF YOU NEED "WHERE ... IN" request !!!
+        // a negative limit lifts it
+        BookmarkRepo::fetch_page(self, tags_filter, None, -1).await
+    }
+
+    async fn fetch_page(
+        &self,
+        tags_filter: Vec<Tag>,
+        after: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<Bookmark>> {
+        // sqlx can't bind a list for "WHERE ... IN", so the ids go as a JSON array
+        let required_tag_ids = if tags_filter.is_empty() {
+            None
+        } else {
+            // a parent tag stands for all the tags under it
+            let all_tags = TagsRepo::fetch_all(self).await?;
+            let ids = tags::with_descendants(&all_tags, tags_filter.iter().map(|tag| tag.id));
+            Some(serde_json::to_string(&ids)?)
+        };
+        let after = after.unwrap_or_default();
 
         let raw_bookmarks = query_as!(
             RawBookmark,
             r#"
-                SELECT b.id, b.title, b.url, group_concat(t.id || ',' || t.name) tags
+                SELECT b.id, b.title, b.url, group_concat(t.id || ',' || t.name) tags,
+                    b.status, b.created_at, b.pinned_at, b.collection_id, b.description,
+                    b.opened_at
                 FROM bookmarks b
                 LEFT JOIN bmarks_tags bt ON bt.bookmark_id = b.id
                 LEFT JOIN tags t ON t.id = bt.tag_id
+                WHERE b.id > $1
+                    AND ($2 IS NULL OR b.id IN (
+                        SELECT bookmark_id
+                        FROM bmarks_tags
+                        WHERE tag_id IN (SELECT value FROM json_each($2))
+                    ))
                 GROUP BY b.id
+                ORDER BY b.id
+                LIMIT $3
             "#,
+            after,
+            required_tag_ids,
+            limit,
         )
         .fetch_all(&self.pool)
         .await?;
 
-        let raw_bookmarks = if tags_filter.is_empty() {
-            raw_bookmarks
-        } else {
-            // filter bookmarks in order to left only that we're interested in
-            raw_bookmarks
-                .into_iter()
-                .filter(|record| {
-                    if let Some(tags) = &record.tags {
-                        let tags: Vec<(i64, &str)> = tags
-                            .split(',')
-                            .map(str::trim)
-                            .collect::<Vec<&str>>()
-                            .windows(2)
-                            .filter_map(|pair| {
-                                if let [number_str, text] = pair {
-                                    if let Ok(number) = number_str.parse::<i64>() {
-                                        Some((number, *text))
-                                    } else {
-                                        None
-                                    }
-                                } else {
-                                    None
-                                }
-                            })
-                            .collect();
-
-                        let bmark_tags_ids: Vec<i64> = tags.iter().map(|tag| tag.0).collect();
-
-                        let required_tag_ids =
-                            tags_filter.iter().map(|tag| tag.id).collect::<Vec<i64>>();
-
-                        for tag_id in bmark_tags_ids {
-                            if required_tag_ids.contains(&tag_id) {
-                                return true;
-                            }
-                        }
-                        false
-                    } else {
-                        false
-                    }
-                })
-                .collect::<Vec<RawBookmark>>()
-        };
+        Ok(raw_bookmarks.into_iter().map(bookmark_from_raw).collect())
+    }
 
-        let bookmarks: Vec<Bookmark> = raw_bookmarks
-            .into_iter()
-            .map(|record| {
-                let tags = record.tags.as_ref().map(|tags| {
-                    tags.split(',')
-                        .collect::<Vec<&str>>()
-                        .chunks(2)
-                        .map(|chunk| {
-                            if chunk.len() > 1 {
-                                let id = chunk[0].parse::<i64>();
-                                let name = chunk[1].to_string();
-                                match id {
-                                    Ok(parsed_id) => Tag::new(parsed_id, name),
-                                    Err(_) => Tag::default(),
-                                }
-                            } else {
-                                Tag::default()
-                            }
-                        })
-                        .collect::<Vec<Tag>>()
-                });
-
-                Bookmark::new(record.id, record.title, record.url.into(), tags)
-            })
-            .collect();
+    async fn fetch_pinned(&self) -> Result<Vec<Bookmark>> {
+        let raw_bookmarks = query_as!(
+            RawBookmark,
+            r#"
+                SELECT b.id, b.title, b.url, group_concat(t.id || ',' || t.name) tags,
+                    b.status, b.created_at, b.pinned_at, b.collection_id, b.description,
+                    b.opened_at
+                FROM bookmarks b
+                LEFT JOIN bmarks_tags bt ON bt.bookmark_id = b.id
+                LEFT JOIN tags t ON t.id = bt.tag_id
+                WHERE b.pinned_at IS NOT NULL
+                GROUP BY b.id
+                ORDER BY b.pinned_at, b.id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
 
-        Ok(bookmarks)
+        Ok(raw_bookmarks.into_iter().map(bookmark_from_raw).collect())
+    }
+
+    async fn fetch_urls(&self) -> Result<Vec<url::Url>> {
+        let records = query!(
+            r#"
+            SELECT url
+            FROM bookmarks
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        // links are validated before they get into the database, skip the broken ones anyway
+        Ok(records
+            .into_iter()
+            .filter_map(|record| url::Url::parse(&record.url).ok())
+            .collect())
     }
 
     async fn batch_create(&self, bmarks_params: Vec<NewBookmarkParams>) -> Result<Vec<Bookmark>> {
         // TODO: implement batch transactions
         // TODO: try to refactor that
         let mut tx = self.pool.begin().await?;
+        let now = Utc::now();
 
         let mut bookmarks = vec![];
         for bmark_params in bmarks_params {
             let url: String = bmark_params.url.into();
+            let created_at = bmark_params.created_at.unwrap_or(now).timestamp();
+            // imported bookmarks have been read already, as opposed to the ones saved for later
+            let status = ReadingStatus::Read.as_str();
+            let collection_id = get_or_create_collection(&mut tx, &bmark_params.collection).await?;
             let raw_bookmark = query!(
                 r#"
                 INSERT INTO bookmarks
-                (title, url)
-                VALUES ($1, $2)
-                RETURNING id, title, url
+                (title, url, status, created_at, collection_id, description)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                RETURNING id, title, url, status, created_at, pinned_at, collection_id, description
                 "#,
                 bmark_params.title,
                 url,
+                status,
+                created_at,
+                collection_id,
+                bmark_params.description,
             )
             .fetch_one(&mut *tx)
             .await?;
 
             let mut tags = vec![];
             // tags part
-            for tag_name in bmark_params.tags {
-                let tag = if let Ok(tag) = query_as!(
-                    Tag,
-                    r#"
-                    SELECT id, name
-                    FROM tags
-                    WHERE name = $1
-                    "#,
-                    tag_name
-                )
-                .fetch_one(&mut *tx)
-                .await
-                {
-                    tag
-                } else {
-                    query_as!(
-                        Tag,
+            for tag_params in bmark_params.tags {
+                let mut tag = get_or_create_tag(&mut tx, &tag_params.name).await?;
+                // names differing in the case or aliases of each other are the same tag
+                if tags.iter().any(|known: &Tag| known.id == tag.id) {
+                    continue;
+                }
+                // the pack chosen locally wins over the one at the source
+                if let (Some(pack_params), None) = (tag_params.pack, tag.pack_id) {
+                    let pack = get_or_create_pack(&mut tx, pack_params).await?;
+                    query!(
                         r#"
-                        INSERT INTO tags
-                        (name)
-                        VALUES ($1)
-                        RETURNING id, name
+                        UPDATE tags
+                        SET pack_id = $1
+                        WHERE id = $2
                         "#,
-                        tag_name
+                        pack.id,
+                        tag.id,
                     )
-                    .fetch_one(&mut *tx)
-                    .await?
-                };
+                    .execute(&mut *tx)
+                    .await?;
+                    tag.pack_id = Some(pack.id);
+                }
 
                 query!(
                     r#"
@@ -215,7 +590,12 @@ impl BookmarkRepo for Repo {
                 raw_bookmark.title,
                 raw_bookmark.url,
                 Some(tags),
-            );
+                &raw_bookmark.status,
+                raw_bookmark.created_at,
+                raw_bookmark.pinned_at,
+            )
+            .in_collection(raw_bookmark.collection_id)
+            .with_description(raw_bookmark.description);
             bookmarks.push(bmark);
         }
         tx.commit().await?;
@@ -228,19 +608,26 @@ impl BookmarkRepo for Repo {
         title: Option<String>,
         url: url::Url,
         tags: Option<Vec<Tag>>,
+        description: Option<String>,
     ) -> Result<Bookmark> {
         let mut tx = self.pool.begin().await?;
         let url = url.to_string();
+        // bookmarks are saved to be read later
+        let status = ReadingStatus::Unread.as_str();
+        let created_at = Utc::now().timestamp();
 
         let raw_bookmark = query!(
             r#"
             INSERT INTO bookmarks
-            (title, url)
-            VALUES ($1, $2)
-            RETURNING id, title, url
+            (title, url, status, created_at, description)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, title, url, status, created_at, pinned_at, description
             "#,
             title,
             url,
+            status,
+            created_at,
+            description,
         )
         .fetch_one(&mut *tx)
         .await?;
@@ -268,7 +655,110 @@ impl BookmarkRepo for Repo {
             raw_bookmark.title,
             raw_bookmark.url,
             tags,
-        ))
+            &raw_bookmark.status,
+            raw_bookmark.created_at,
+            raw_bookmark.pinned_at,
+        )
+        .with_description(raw_bookmark.description))
+    }
+
+    async fn update_url(&self, id: i64, url: url::Url) -> Result<()> {
+        let url = url.to_string();
+        query!(
+            r#"
+            UPDATE bookmarks
+            SET url = $1
+            WHERE id = $2
+            "#,
+            url,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn update_status(&self, ids: Vec<i64>, status: ReadingStatus) -> Result<()> {
+        let status = status.as_str();
+        let mut tx = self.pool.begin().await?;
+        for id in ids {
+            query!(
+                r#"
+                UPDATE bookmarks
+                SET status = $1
+                WHERE id = $2
+                "#,
+                status,
+                id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn set_pinned(&self, ids: Vec<i64>, pinned: bool) -> Result<()> {
+        let pinned_at = pinned.then(|| Utc::now().timestamp());
+        let mut tx = self.pool.begin().await?;
+        for id in ids {
+            // pinning again keeps the place in the order
+            query!(
+                r#"
+                UPDATE bookmarks
+                SET pinned_at = CASE WHEN $1 IS NULL THEN NULL ELSE coalesce(pinned_at, $1) END
+                WHERE id = $2
+                "#,
+                pinned_at,
+                id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn mark_opened(&self, id: i64) -> Result<DateTime<Utc>> {
+        let opened_at = Utc::now();
+        let timestamp = opened_at.timestamp();
+        query!(
+            r#"
+            UPDATE bookmarks
+            SET opened_at = $1
+            WHERE id = $2
+            "#,
+            timestamp,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(opened_at)
+    }
+
+    async fn add_tags(&self, id: i64, tags: Vec<String>) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for name in tags {
+            let tag = get_or_create_tag(&mut tx, &name).await?;
+            query!(
+                r#"
+                INSERT OR IGNORE INTO bmarks_tags
+                (bookmark_id, tag_id)
+                VALUES ($1, $2)
+                "#,
+                id,
+                tag.id,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
     }
 
     async fn delete(&self, id: i64) -> Result<()> {
@@ -292,7 +782,7 @@ impl TagsRepo for Repo {
         let tags = query_as!(
             Tag,
             r#"
-            SELECT id, name
+            SELECT id, name, parent_id, pack_id
             FROM tags
             "#
         )
@@ -303,18 +793,9 @@ impl TagsRepo for Repo {
     }
 
     async fn create(&self, name: String) -> Result<Tag> {
-        let tag = query_as!(
-            Tag,
-            r#"
-            INSERT INTO tags
-            (name)
-            VALUES ($1)
-            RETURNING id, name
-            "#,
-            name
-        )
-        .fetch_one(&self.pool)
-        .await?;
+        let mut tx = self.pool.begin().await?;
+        let tag = get_or_create_tag(&mut tx, &name).await?;
+        tx.commit().await?;
 
         Ok(tag)
     }
@@ -323,7 +804,7 @@ impl TagsRepo for Repo {
         let tags = query_as!(
             Tag,
             r#"
-            SELECT id, name
+            SELECT id, name, parent_id, pack_id
             FROM tags
             WHERE id = $1
             "#,
@@ -336,14 +817,21 @@ impl TagsRepo for Repo {
     }
 
     async fn get_by_name(&self, name: String) -> Result<Tag> {
+        let mut conn = self.pool.acquire().await?;
+        find_tag(&mut conn, &name)
+            .await?
+            .ok_or_else(|| anyhow!("There's no tag named {name}"))
+    }
+
+    async fn delete(&self, id: i64) -> Result<Tag> {
         let tag = query_as!(
             Tag,
             r#"
-            SELECT id, name
-            FROM tags
-            WHERE name = $1
+            DELETE FROM tags
+            WHERE id = $1
+            RETURNING id, name, parent_id, pack_id
             "#,
-            name
+            id
         )
         .fetch_one(&self.pool)
         .await?;
@@ -351,19 +839,1070 @@ impl TagsRepo for Repo {
         Ok(tag)
     }
 
-    async fn delete(&self, id: i64) -> Result<Tag> {
-        let tag = query_as!(
+    async fn merge(&self, from_id: i64, into_id: i64) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        let from = query_as!(
             Tag,
             r#"
-            DELETE FROM tags
+            SELECT id, name, parent_id, pack_id
+            FROM tags
             WHERE id = $1
-            RETURNING id, name
             "#,
-            id
+            from_id
         )
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *tx)
+        .await?;
+        let into = query_as!(
+            Tag,
+            r#"
+            SELECT id, name, parent_id, pack_id
+            FROM tags
+            WHERE id = $1
+            "#,
+            into_id
+        )
+        .fetch_one(&mut *tx)
         .await?;
 
-        Ok(tag)
+        merge_tags(&mut tx, &from, &into).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LinkChecksRepo for Repo {
+    async fn save(&self, check: &LinkCheck) -> Result<()> {
+        let status_code = check.status_code.map(i64::from);
+        let checked_at = check.checked_at.timestamp();
+        query!(
+            r#"
+            INSERT OR REPLACE INTO link_checks
+            (bookmark_id, status_code, final_url, error, checked_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            check.bookmark_id,
+            status_code,
+            check.final_url,
+            check.error,
+            checked_at,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn fetch_broken(&self) -> Result<Vec<BrokenLink>> {
+        let records = query!(
+            r#"
+            SELECT b.id, b.title, b.url, lc.status_code, lc.final_url, lc.error, lc.checked_at
+            FROM link_checks lc
+            JOIN bookmarks b ON b.id = lc.bookmark_id
+            WHERE lc.error IS NOT NULL
+                OR lc.status_code IS NULL
+                OR lc.status_code >= 400
+                OR (lc.final_url IS NOT NULL AND lc.final_url != b.url)
+            ORDER BY lc.checked_at DESC
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|record| BrokenLink {
+                bookmark_id: record.id,
+                title: record.title,
+                url: record.url,
+                check: LinkCheck::new(
+                    record.id,
+                    record.status_code,
+                    record.final_url,
+                    record.error,
+                    record.checked_at,
+                ),
+            })
+            .collect())
+    }
+
+    async fn get(&self, bookmark_id: i64) -> Result<Option<LinkCheck>> {
+        let record = query!(
+            r#"
+            SELECT bookmark_id, status_code, final_url, error, checked_at
+            FROM link_checks
+            WHERE bookmark_id = $1
+            "#,
+            bookmark_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record.map(|record| {
+            LinkCheck::new(
+                record.bookmark_id,
+                record.status_code,
+                record.final_url,
+                record.error,
+                record.checked_at,
+            )
+        }))
+    }
+
+    async fn delete(&self, bookmark_id: i64) -> Result<()> {
+        query!(
+            r#"
+            DELETE FROM link_checks
+            WHERE bookmark_id = $1
+            "#,
+            bookmark_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ArchivesRepo for Repo {
+    async fn save(&self, archive: &Archive) -> Result<()> {
+        let archived_at = archive.archived_at.timestamp();
+        query!(
+            r#"
+            INSERT OR REPLACE INTO archives
+            (bookmark_id, url, content, size, archived_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            archive.bookmark_id,
+            archive.url,
+            archive.content,
+            archive.size,
+            archived_at,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, bookmark_id: i64) -> Result<Option<Archive>> {
+        let record = query!(
+            r#"
+            SELECT bookmark_id, url, content, size, archived_at
+            FROM archives
+            WHERE bookmark_id = $1
+            "#,
+            bookmark_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record.map(|record| {
+            Archive::new(
+                record.bookmark_id,
+                record.url,
+                record.content,
+                record.size,
+                record.archived_at,
+            )
+        }))
+    }
+
+    async fn fetch_ids(&self) -> Result<HashSet<i64>> {
+        let records = query!(
+            r#"
+            SELECT bookmark_id
+            FROM archives
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|record| record.bookmark_id)
+            .collect())
+    }
+}
+
+#[async_trait]
+impl PageTextsRepo for Repo {
+    async fn save(&self, page_text: &PageText) -> Result<()> {
+        let extracted_at = page_text.extracted_at.timestamp();
+        query!(
+            r#"
+            INSERT OR REPLACE INTO page_texts
+            (bookmark_id, text, extracted_at)
+            VALUES ($1, $2, $3)
+            "#,
+            page_text.bookmark_id,
+            page_text.text,
+            extracted_at,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn fetch_all(&self) -> Result<HashMap<i64, String>> {
+        let records = query!(
+            r#"
+            SELECT bookmark_id, text
+            FROM page_texts
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|record| (record.bookmark_id, record.text))
+            .collect())
+    }
+
+    async fn fetch_ids(&self) -> Result<HashSet<i64>> {
+        let records = query!(
+            r#"
+            SELECT bookmark_id
+            FROM page_texts
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|record| record.bookmark_id)
+            .collect())
+    }
+}
+
+#[async_trait]
+impl PacksRepo for Repo {
+    async fn fetch_all(&self) -> Result<Vec<Pack>> {
+        let records = query!(
+            r#"
+            SELECT id, name, color
+            FROM packs
+            ORDER BY name
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|record| Pack::new(record.id, record.name, record.color))
+            .collect())
+    }
+
+    async fn create(&self, params: NewPackParams) -> Result<Pack> {
+        let color = i64::from(params.color);
+        let record = query!(
+            r#"
+            INSERT INTO packs
+            (name, color)
+            VALUES ($1, $2)
+            RETURNING id, name, color
+            "#,
+            params.name,
+            color
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(Pack::new(record.id, record.name, record.color))
+    }
+
+    async fn update(&self, pack: Pack) -> Result<()> {
+        let color = i64::from(pack.color);
+        query!(
+            r#"
+            UPDATE packs
+            SET name = $1, color = $2
+            WHERE id = $3
+            "#,
+            pack.name,
+            color,
+            pack.id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: i64) -> Result<()> {
+        query!(
+            r#"
+            DELETE FROM packs
+            WHERE id = $1
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn assign(&self, tag_id: i64, pack_id: Option<i64>) -> Result<()> {
+        query!(
+            r#"
+            UPDATE tags
+            SET pack_id = $1
+            WHERE id = $2
+            "#,
+            pack_id,
+            tag_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CollectionsRepo for Repo {
+    async fn fetch_all(&self) -> Result<Vec<Collection>> {
+        let collections = query_as!(
+            Collection,
+            r#"
+            SELECT id, name, parent_id, position
+            FROM collections
+            ORDER BY position, name
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(collections)
+    }
+
+    async fn create(&self, params: NewCollectionParams) -> Result<Collection> {
+        let mut conn = self.pool.acquire().await?;
+        insert_collection(&mut conn, &params.name, params.parent_id).await
+    }
+
+    async fn rename(&self, id: i64, name: String) -> Result<()> {
+        query!(
+            r#"
+            UPDATE collections
+            SET name = $1
+            WHERE id = $2
+            "#,
+            name,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: i64) -> Result<()> {
+        query!(
+            r#"
+            DELETE FROM collections
+            WHERE id = $1
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn reorder(&self, ids: Vec<i64>) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for (position, id) in ids.into_iter().enumerate() {
+            let position = i64::try_from(position)?;
+            query!(
+                r#"
+                UPDATE collections
+                SET position = $1
+                WHERE id = $2
+                "#,
+                position,
+                id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn move_bookmarks(&self, ids: Vec<i64>, collection_id: Option<i64>) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for id in ids {
+            query!(
+                r#"
+                UPDATE bookmarks
+                SET collection_id = $1
+                WHERE id = $2
+                "#,
+                collection_id,
+                id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SavedSearchesRepo for Repo {
+    async fn fetch_all(&self) -> Result<Vec<SavedSearch>> {
+        let records = query!(
+            r#"
+            SELECT id, name, title_query, link_query, sort_column, sort_descending
+            FROM saved_searches
+            ORDER BY name
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let tag_records = query!(
+            r#"
+            SELECT st.saved_search_id, t.id, t.name, t.parent_id, t.pack_id
+            FROM saved_search_tags st
+            JOIN tags t ON t.id = st.tag_id
+            ORDER BY t.name
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        let mut tags: HashMap<i64, Vec<Tag>> = HashMap::new();
+        for record in tag_records {
+            tags.entry(record.saved_search_id).or_default().push(Tag {
+                id: record.id,
+                name: record.name,
+                parent_id: record.parent_id,
+                pack_id: record.pack_id,
+            });
+        }
+
+        Ok(records
+            .into_iter()
+            .map(|record| SavedSearch {
+                id: record.id,
+                name: record.name,
+                title_query: record.title_query,
+                link_query: record.link_query,
+                tags: tags.remove(&record.id).unwrap_or_default(),
+                // a column that isn't there anymore leaves the results unsorted
+                sort_column: record.sort_column.and_then(|column| column.parse().ok()),
+                sort_descending: record.sort_descending,
+            })
+            .collect())
+    }
+
+    async fn create(&self, params: NewSavedSearchParams) -> Result<SavedSearch> {
+        let mut tx = self.pool.begin().await?;
+        let sort_column = params.sort_column.map(|column| column.as_str());
+
+        let record = query!(
+            r#"
+            INSERT INTO saved_searches
+            (name, title_query, link_query, sort_column, sort_descending)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT(name) DO UPDATE SET
+                title_query = excluded.title_query,
+                link_query = excluded.link_query,
+                sort_column = excluded.sort_column,
+                sort_descending = excluded.sort_descending
+            RETURNING id
+            "#,
+            params.name,
+            params.title_query,
+            params.link_query,
+            sort_column,
+            params.sort_descending,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // the replaced search keeps none of its tags
+        query!(
+            r#"
+            DELETE FROM saved_search_tags
+            WHERE saved_search_id = $1
+            "#,
+            record.id
+        )
+        .execute(&mut *tx)
+        .await?;
+        for tag_id in params.tag_ids {
+            query!(
+                r#"
+                INSERT INTO saved_search_tags
+                (saved_search_id, tag_id)
+                VALUES ($1, $2)
+                "#,
+                record.id,
+                tag_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        SavedSearchesRepo::fetch_all(self)
+            .await?
+            .into_iter()
+            .find(|search| search.id == record.id)
+            .ok_or_else(|| anyhow!("The saved search {} is gone", params.name))
+    }
+
+    async fn rename(&self, id: i64, name: String) -> Result<()> {
+        query!(
+            r#"
+            UPDATE saved_searches
+            SET name = $1
+            WHERE id = $2
+            "#,
+            name,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: i64) -> Result<()> {
+        query!(
+            r#"
+            DELETE FROM saved_searches
+            WHERE id = $1
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SearchHistoryRepo for Repo {
+    async fn fetch_all(&self) -> Result<Vec<SearchHistoryEntry>> {
+        let records = query!(
+            r#"
+            SELECT id, title_query, link_query
+            FROM search_history
+            ORDER BY id
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let tag_records = query!(
+            r#"
+            SELECT sht.search_id, t.id, t.name, t.parent_id, t.pack_id
+            FROM search_history_tags sht
+            JOIN tags t ON t.id = sht.tag_id
+            ORDER BY t.name
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        let mut tags: HashMap<i64, Vec<Tag>> = HashMap::new();
+        for record in tag_records {
+            tags.entry(record.search_id).or_default().push(Tag {
+                id: record.id,
+                name: record.name,
+                parent_id: record.parent_id,
+                pack_id: record.pack_id,
+            });
+        }
+
+        Ok(records
+            .into_iter()
+            .map(|record| SearchHistoryEntry {
+                title_query: record.title_query,
+                link_query: record.link_query,
+                tags: tags.remove(&record.id).unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    async fn remember(&self, entry: SearchHistoryEntry) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let tag_ids: Vec<i64> = entry.tags.iter().map(|tag| tag.id).collect();
+        let tags_count = tag_ids.len() as i64;
+        let tag_ids = serde_json::to_string(&tag_ids)?;
+
+        // the same search moves to the end of the history instead of repeating there
+        query!(
+            r#"
+            DELETE FROM search_history
+            WHERE title_query = $1
+                AND link_query = $2
+                AND (
+                    SELECT COUNT(*) FROM search_history_tags
+                    WHERE search_id = search_history.id
+                ) = $3
+                AND NOT EXISTS (
+                    SELECT 1 FROM search_history_tags
+                    WHERE search_id = search_history.id
+                        AND tag_id NOT IN (SELECT value FROM json_each($4))
+                )
+            "#,
+            entry.title_query,
+            entry.link_query,
+            tags_count,
+            tag_ids
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let record = query!(
+            r#"
+            INSERT INTO search_history
+            (title_query, link_query)
+            VALUES ($1, $2)
+            RETURNING id
+            "#,
+            entry.title_query,
+            entry.link_query
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        for tag in &entry.tags {
+            query!(
+                r#"
+                INSERT INTO search_history_tags
+                (search_id, tag_id)
+                VALUES ($1, $2)
+                "#,
+                record.id,
+                tag.id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        query!(
+            r#"
+            DELETE FROM search_history
+            WHERE id NOT IN (
+                SELECT id FROM search_history
+                ORDER BY id DESC
+                LIMIT $1
+            )
+            "#,
+            HISTORY_LIMIT
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use url::Url;
+
+    use super::*;
+    use crate::{
+        app::{App, TagsRow},
+        models::tags::NewTagParams,
+    };
+
+    /// A new database in the temp dir, every test gets its own.
+    async fn repo() -> Repo {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let path = env::temp_dir().join(format!(
+            "fbmark-test-{}-{}.db",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_file(&path);
+        Repo::open(&format!("sqlite://{}", path.display()))
+            .await
+            .unwrap()
+    }
+
+    async fn bookmark(repo: &Repo, url: &str, tags: Vec<Tag>) -> Bookmark {
+        BookmarkRepo::create(repo, None, Url::parse(url).unwrap(), Some(tags), None)
+            .await
+            .unwrap()
+    }
+
+    fn tag_names(bookmark: &Bookmark) -> Vec<String> {
+        let mut names: Vec<String> = bookmark
+            .tags
+            .iter()
+            .flatten()
+            .map(|tag| tag.name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[tokio::test]
+    async fn merges_the_descendants_with_the_same_names() {
+        let repo = repo().await;
+        let a_x = TagsRepo::create(&repo, "a/x".to_string()).await.unwrap();
+        let a_x_deep = TagsRepo::create(&repo, "a/x/deep".to_string())
+            .await
+            .unwrap();
+        let a_y = TagsRepo::create(&repo, "a/y".to_string()).await.unwrap();
+        let b_x = TagsRepo::create(&repo, "b/x".to_string()).await.unwrap();
+        let a = TagsRepo::get_by_name(&repo, "a".to_string()).await.unwrap();
+        let b = TagsRepo::get_by_name(&repo, "b".to_string()).await.unwrap();
+
+        let first = bookmark(&repo, "https://one.example.com/", vec![a_x.clone()]).await;
+        let second = bookmark(&repo, "https://two.example.com/", vec![b_x.clone()]).await;
+        let third = bookmark(&repo, "https://three.example.com/", vec![a_x_deep, a_y]).await;
+
+        TagsRepo::merge(&repo, a.id, b.id).await.unwrap();
+
+        let mut names: Vec<(String, Option<i64>)> = TagsRepo::fetch_all(&repo)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|tag| (tag.name, tag.parent_id))
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                ("b".to_string(), None),
+                ("b/x".to_string(), Some(b.id)),
+                ("b/x/deep".to_string(), Some(b_x.id)),
+                ("b/y".to_string(), Some(b.id)),
+            ]
+        );
+
+        let bookmarks = BookmarkRepo::fetch_all(&repo, vec![]).await.unwrap();
+        let tags_of =
+            |id: i64| tag_names(bookmarks.iter().find(|bookmark| bookmark.id == id).unwrap());
+        assert_eq!(tags_of(first.id), vec!["b/x"]);
+        assert_eq!(tags_of(second.id), vec!["b/x"]);
+        assert_eq!(tags_of(third.id), vec!["b/x/deep", "b/y"]);
+
+        // the old names still find the tags they've become
+        let merged = TagsRepo::get_by_name(&repo, "a/x".to_string())
+            .await
+            .unwrap();
+        assert_eq!(merged.id, b_x.id);
+        let merged = TagsRepo::get_by_name(&repo, "a".to_string()).await.unwrap();
+        assert_eq!(merged.id, b.id);
+    }
+
+    #[tokio::test]
+    async fn imports_the_names_of_one_tag_once() {
+        let repo = repo().await;
+        let js = TagsRepo::create(&repo, "js".to_string()).await.unwrap();
+        let javascript = TagsRepo::create(&repo, "javascript".to_string())
+            .await
+            .unwrap();
+        TagsRepo::merge(&repo, js.id, javascript.id).await.unwrap();
+
+        let link = NewBookmarkParams::new(
+            Some("MDN".to_string()),
+            Url::parse("https://developer.mozilla.org/").unwrap(),
+            ["rust", "Rust", "js", "javascript"]
+                .into_iter()
+                .map(|name| NewTagParams::from(name.to_string()))
+                .collect(),
+            vec![],
+            None,
+        );
+        let created = BookmarkRepo::batch_create(&repo, vec![link]).await.unwrap();
+        assert_eq!(tag_names(&created[0]), vec!["javascript", "rust"]);
+
+        let bookmarks = BookmarkRepo::fetch_all(&repo, vec![]).await.unwrap();
+        assert_eq!(tag_names(&bookmarks[0]), vec!["javascript", "rust"]);
+    }
+
+    #[tokio::test]
+    async fn keeps_the_searches_filtered_by_the_merged_tag() {
+        let repo = repo().await;
+        let js = TagsRepo::create(&repo, "js".to_string()).await.unwrap();
+        let javascript = TagsRepo::create(&repo, "javascript".to_string())
+            .await
+            .unwrap();
+        let web = TagsRepo::create(&repo, "web".to_string()).await.unwrap();
+
+        SavedSearchesRepo::create(
+            &repo,
+            NewSavedSearchParams {
+                name: "scripts".to_string(),
+                title_query: String::new(),
+                link_query: String::new(),
+                tag_ids: vec![js.id],
+                sort_column: None,
+                sort_descending: false,
+            },
+        )
+        .await
+        .unwrap();
+        SavedSearchesRepo::create(
+            &repo,
+            NewSavedSearchParams {
+                name: "both".to_string(),
+                title_query: String::new(),
+                link_query: String::new(),
+                tag_ids: vec![js.id, javascript.id, web.id],
+                sort_column: None,
+                sort_descending: false,
+            },
+        )
+        .await
+        .unwrap();
+        SearchHistoryRepo::remember(
+            &repo,
+            SearchHistoryEntry {
+                title_query: "async".to_string(),
+                link_query: String::new(),
+                tags: vec![js.clone()],
+            },
+        )
+        .await
+        .unwrap();
+
+        TagsRepo::merge(&repo, js.id, javascript.id).await.unwrap();
+
+        let searches = SavedSearchesRepo::fetch_all(&repo).await.unwrap();
+        let tags_of = |name: &str| -> Vec<String> {
+            let search = searches.iter().find(|search| search.name == name).unwrap();
+            search.tags.iter().map(|tag| tag.name.clone()).collect()
+        };
+        assert_eq!(tags_of("scripts"), vec!["javascript"]);
+        assert_eq!(tags_of("both"), vec!["javascript", "web"]);
+
+        let history = SearchHistoryRepo::fetch_all(&repo).await.unwrap();
+        let tags: Vec<_> = history[0].tags.iter().map(|tag| tag.id).collect();
+        assert_eq!(tags, vec![javascript.id]);
+    }
+
+    #[tokio::test]
+    async fn keeps_the_time_the_link_was_saved_at_the_source() {
+        let repo = repo().await;
+        let saved_at = DateTime::from_timestamp(1696240000, 0);
+        let link = |url: &str| {
+            NewBookmarkParams::new(None, Url::parse(url).unwrap(), vec![], vec![], None)
+        };
+
+        let before = Utc::now().timestamp();
+        let created = BookmarkRepo::batch_create(
+            &repo,
+            vec![
+                link("https://old.example.com/").created_at(saved_at),
+                link("https://new.example.com/"),
+            ],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(created[0].created_at, saved_at);
+        let imported_at = created[1].created_at.unwrap().timestamp();
+        assert!(imported_at >= before);
+    }
+
+    /// The ids of the bookmarks page by page, the way a refresh loads them.
+    async fn pages(repo: &Repo, tags_filter: Vec<Tag>, limit: i64) -> Vec<Vec<i64>> {
+        let mut pages = vec![];
+        let mut after = None;
+        loop {
+            let page = BookmarkRepo::fetch_page(repo, tags_filter.clone(), after, limit)
+                .await
+                .unwrap();
+            let Some(last) = page.last() else {
+                return pages;
+            };
+            after = Some(last.id);
+            pages.push(page.iter().map(|bookmark| bookmark.id).collect());
+        }
+    }
+
+    #[tokio::test]
+    async fn loads_the_library_in_pages() {
+        let repo = repo().await;
+        let rust = TagsRepo::create(&repo, "rust".to_string()).await.unwrap();
+        let rust_async = TagsRepo::create(&repo, "rust/async".to_string())
+            .await
+            .unwrap();
+        let go = TagsRepo::create(&repo, "go".to_string()).await.unwrap();
+
+        let mut ids = vec![];
+        for number in 1..=7 {
+            let tags = match number {
+                5 => vec![rust_async.clone()],
+                number if number % 2 == 1 => vec![rust.clone(), go.clone()],
+                _ => vec![go.clone()],
+            };
+            let url = format!("https://example.com/{number}");
+            ids.push(bookmark(&repo, &url, tags).await.id);
+        }
+
+        let all = pages(&repo, vec![], 2).await;
+        assert_eq!(
+            all,
+            vec![
+                vec![ids[0], ids[1]],
+                vec![ids[2], ids[3]],
+                vec![ids[4], ids[5]],
+                vec![ids[6]],
+            ]
+        );
+
+        // a parent tag takes in the tags under it on every page
+        let tagged = pages(&repo, vec![rust.clone()], 2).await;
+        assert_eq!(tagged, vec![vec![ids[0], ids[2]], vec![ids[4], ids[6]]]);
+
+        // the limit cuts the bookmarks, not the rows of their tags
+        let first = BookmarkRepo::fetch_page(&repo, vec![go], None, 1)
+            .await
+            .unwrap();
+        assert_eq!(tag_names(&first[0]), vec!["go", "rust"]);
+
+        let everything = BookmarkRepo::fetch_all(&repo, vec![]).await.unwrap();
+        assert_eq!(everything.len(), 7);
+    }
+
+    #[tokio::test]
+    async fn filters_by_the_tag_with_its_descendants() {
+        let repo = repo().await;
+        let mut tags_by_name = HashMap::new();
+        for name in ["lang", "lang/rust", "lang/rust/async", "language", "go"] {
+            let tag = TagsRepo::create(&repo, name.to_string()).await.unwrap();
+            let url = format!("https://example.com/{}", name.replace('/', "-"));
+            bookmark(&repo, &url, vec![tag.clone()]).await;
+            tags_by_name.insert(name, tag);
+        }
+
+        let found = |filter: &str| {
+            let filter = vec![tags_by_name[filter].clone()];
+            let repo = &repo;
+            async move {
+                let mut urls: Vec<String> = BookmarkRepo::fetch_all(repo, filter)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|bookmark| bookmark.url.inner.path().to_string())
+                    .collect();
+                urls.sort();
+                urls
+            }
+        };
+        // the names sharing a prefix aren't descendants
+        assert_eq!(
+            found("lang").await,
+            vec!["/lang", "/lang-rust", "/lang-rust-async"]
+        );
+        assert_eq!(
+            found("lang/rust").await,
+            vec!["/lang-rust", "/lang-rust-async"]
+        );
+        assert_eq!(found("lang/rust/async").await, vec!["/lang-rust-async"]);
+        assert_eq!(found("language").await, vec!["/language"]);
+    }
+
+    #[tokio::test]
+    async fn backfills_the_parents_of_the_existing_tags() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let dir = env::temp_dir().join(format!(
+            "fbmark-test-migrations-{}-{}",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("migrations")).unwrap();
+        // the migrations that were there before the tags got their parents
+        let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("migrations");
+        for entry in std::fs::read_dir(&crate_dir).unwrap() {
+            let name = entry.unwrap().file_name();
+            if name.to_string_lossy().as_ref() < "20261019150000" {
+                std::fs::copy(crate_dir.join(&name), dir.join("migrations").join(&name)).unwrap();
+            }
+        }
+
+        let db_url = format!("sqlite://{}", dir.join("fbmark.db").display());
+        sqlx::Sqlite::create_database(&db_url).await.unwrap();
+        let pool = SqlitePool::connect(&db_url).await.unwrap();
+        sqlx::migrate::Migrator::new(dir.join("migrations"))
+            .await
+            .unwrap()
+            .run(&pool)
+            .await
+            .unwrap();
+        for name in [
+            "web/css/grid",
+            "lang/rust",
+            "lang/go",
+            "lang",
+            "async",
+            "/odd",
+        ] {
+            sqlx::query("INSERT INTO tags (name) VALUES ($1)")
+                .bind(name)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        pool.close().await;
+
+        let repo = Repo::open(&db_url).await.unwrap();
+        let tags = TagsRepo::fetch_all(&repo).await.unwrap();
+        let parent_of = |name: &str| {
+            let tag = tags.iter().find(|tag| tag.name == name).unwrap();
+            tag.parent_id
+                .map(|id| tags.iter().find(|tag| tag.id == id).unwrap().name.as_str())
+        };
+        assert_eq!(parent_of("lang/rust"), Some("lang"));
+        assert_eq!(parent_of("lang"), None);
+        // the missing ancestors are created
+        assert_eq!(parent_of("web/css/grid"), Some("web/css"));
+        assert_eq!(parent_of("web/css"), Some("web"));
+        assert_eq!(parent_of("/odd"), None);
+
+        // the tree goes depth first, the siblings in the alphabetical order
+        let mut app = App::new();
+        app.expanded_tags = tags.iter().map(|tag| tag.id).collect();
+        app.set_library(vec![], tags, vec![], vec![]);
+        let rows: Vec<&str> = app
+            .tags_items
+            .items
+            .iter()
+            .map(|row| match row {
+                TagsRow::Tag(tag) => tag.name.as_str(),
+                TagsRow::Pack(_) => panic!("no packs"),
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                "/odd",
+                "async",
+                "lang",
+                "lang/go",
+                "lang/rust",
+                "web",
+                "web/css",
+                "web/css/grid",
+            ]
+        );
     }
 }
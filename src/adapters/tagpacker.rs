@@ -3,7 +3,10 @@ use chrono::{DateTime, NaiveDate, TimeZone, Utc};
 use serde::{Deserialize, Deserializer};
 use url::Url;
 
-use crate::{models::bookmarks::NewBookmarkParams, settings::Settings};
+use crate::{
+    models::{bookmarks::NewBookmarkParams, packs::NewPackParams, tags::NewTagParams},
+    settings::Settings,
+};
 
 #[derive(Deserialize, Debug)]
 pub struct Link {
@@ -64,6 +67,18 @@ pub struct Pack {
     pub color: u16,
 }
 
+impl From<Tag> for NewTagParams {
+    fn from(tag: Tag) -> Self {
+        Self {
+            name: tag.name,
+            pack: tag.pack.map(|pack| NewPackParams {
+                name: pack.name,
+                color: pack.color,
+            }),
+        }
+    }
+}
+
 pub async fn get_links() -> Result<Vec<NewBookmarkParams>> {
     let settings = Settings::get_configuration()?;
 
@@ -81,8 +96,13 @@ pub async fn get_links() -> Result<Vec<NewBookmarkParams>> {
             NewBookmarkParams::new(
                 Some(link.title),
                 link.source_url,
-                link.tags.into_iter().map(|tag| tag.name).collect(),
+                link.tags.into_iter().map(NewTagParams::from).collect(),
+                // Tagpacker has no folders, only tags
+                Vec::new(),
+                link.description
+                    .filter(|description| !description.trim().is_empty()),
             )
+            .created_at(Some(link.created_at))
         })
         .collect::<Vec<NewBookmarkParams>>())
 }
@@ -1,32 +1,104 @@
-use std::fmt::Debug;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    time::{Duration, Instant},
+};
 
 use crate::{
-    adapters::{
-        sqlite::Repo,
-        tagpacker::{self},
+    adapters::metadata::PageMetadata,
+    fulltext::Snippet,
+    models::{
+        bookmarks::{Bookmark, ReadingStatus},
+        collections::Collection,
+        link_checks::{BrokenLink, LinkCheck},
+        packs::Pack,
+        saved_searches::{NewSavedSearchParams, SavedSearch},
+        search_history::{SearchHistoryEntry, HISTORY_LIMIT},
+        tags::{MergeCandidate, Tag},
     },
-    models::{bookmarks::Bookmark, tags::Tag},
+    notifications::Level,
+    notifications::Notifications,
+    palette::{self, Entry},
+    settings::{BookmarkList, Column, ListColumn},
+    suggestions::{suggest, TagStats},
+    tagging::TagChange,
+    tasks::Tasks,
+    validation::{canonical_url as canonical_url_of, validate_link, LinkValidation},
 };
 use anyhow::Result;
-use crossterm::event::{Event, KeyEvent};
-use ratatui::widgets::ListState;
+use chrono::{DateTime, Utc};
+use ratatui::{layout::Rect, widgets::ListState};
 use simsearch::{SearchOptions, SimSearch};
-use tui_input::{backend::crossterm::EventHandler, Input};
+use tui_input::Input;
 use url::Url;
 
+/// The whole state of the application.
+///
+/// It doesn't perform any I/O by itself, all the side effects are requested
+/// from [`crate::update::update`] as [`crate::command::Command`]s.
+#[derive(Default)]
 pub struct App {
     pub bookmarks: Vec<Bookmark>,
     pub bookmarks_items: StatefulList<Bookmark>,
+    /// The search the listed bookmarks are the results of, `None` when the whole library is listed.
+    pub results_query: Option<ResultsQuery>,
+    /// Columns of the bookmarks table, in the order they are shown.
+    pub columns: Vec<ListColumn>,
+    /// The order of the listed bookmarks, the order of the search results otherwise.
+    pub sort: Option<Sort>,
+    /// Pinned bookmarks, numbered from 1 in this order.
+    pub pinned: Vec<Bookmark>,
     pub tags: Vec<Tag>,
-    pub tags_items: StatefulList<Tag>,
+    pub packs: Vec<Pack>,
+    /// The visible rows of the tags tree, grouped by packs if there are any.
+    pub tags_items: StatefulList<TagsRow>,
+    pub expanded_tags: HashSet<i64>,
+    /// Packs whose tags are hidden, `None` stands for the tags without a pack.
+    pub collapsed_packs: HashSet<Option<i64>>,
+    pub collections: Vec<Collection>,
+    /// The visible rows of the collections sidebar.
+    pub collections_items: StatefulList<CollectionsRow>,
+    pub collapsed_collections: HashSet<i64>,
+    pub saved_searches: Vec<SavedSearch>,
+    /// Submitted searches, the latest one last.
+    pub search_history: Vec<SearchHistoryEntry>,
     pub mode: Mode,
     pub tags_filter: Vec<Tag>,
+    /// The collection the library is narrowed down to.
+    pub collection_filter: Option<i64>,
     pub render_help: bool,
+    /// The command palette, if it's open.
+    pub palette: Option<PaletteParams>,
+    pub areas: Areas,
+    pub tasks: Tasks,
+    pub tick: usize,
+    pub notifications: Notifications,
+    /// Bookmarks picked for the actions that work on several of them at once.
+    pub marked: HashSet<i64>,
+    /// Bookmarks that have an archived copy of their page.
+    pub archived: HashSet<i64>,
+    pub archive_on_create: bool,
+    /// Texts of the indexed pages by the ids of their bookmarks.
+    pub page_texts: HashMap<i64, String>,
+    pub show_details: bool,
+    /// What's stored about the highlighted bookmark apart from the bookmark itself.
+    pub details: Option<BookmarkDetails>,
+    /// The bookmark the details have been asked for.
+    details_for: Option<i64>,
+    last_click: Option<(Instant, u16, u16)>,
     should_quit: bool,
-    repo: Repo,
 }
 
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+
 impl App {
+    pub fn new() -> Self {
+        Self {
+            columns: BookmarkList::default().columns,
+            ..Default::default()
+        }
+    }
+
     pub fn is_should_quit(&self) -> bool {
         self.should_quit
     }
@@ -35,27 +107,16 @@ impl App {
         self.should_quit = true;
     }
 
-    pub async fn new() -> Result<Self> {
-        let repo = Repo::new().await?;
-        let (bookmarks, bookmarks_items, tags, tags_items) =
-            Self::refresh_state(&repo, vec![]).await?;
-
-        Ok(Self {
-            bookmarks,
-            bookmarks_items,
-            tags,
-            tags_items,
-            tags_filter: Vec::new(),
-            mode: Mode::default(),
-            should_quit: false,
-            render_help: false,
-            repo,
-        })
-    }
-
     pub fn toggle_mode(&mut self) {
         self.mode = match &mut self.mode {
-            Mode::Search(_params) => Mode::Scrolling,
+            Mode::Search(_)
+            | Mode::BrokenLinks(_)
+            | Mode::ReadingList(_)
+            | Mode::Packs(_)
+            | Mode::TagMerges(_)
+            | Mode::AutoTagging(_)
+            | Mode::Collections(_)
+            | Mode::SavedSearches(_) => Mode::Scrolling,
             Mode::Create(ref mut params) => Mode::Create(params.clone()),
             Mode::Scrolling => Mode::Search(SearchingParams::default()),
         }
@@ -65,22 +126,211 @@ impl App {
         if let Mode::Create(_params) = &self.mode {
             self.mode = Mode::Scrolling;
         } else {
-            let params = CreationParams {
-                tags_items: StatefulList::with_items(self.tags.clone()),
+            let mut params = CreationParams {
+                tags: self.tags.clone(),
                 ..Default::default()
             };
-            self.mode = Mode::Create(params);
+            params.validate();
+            params.suggest_tags();
+            self.mode = Mode::Create(Box::new(params));
+        }
+    }
+
+    pub fn toggle_broken_links(&mut self) {
+        self.mode = match self.mode {
+            Mode::BrokenLinks(_) => Mode::Scrolling,
+            _ => Mode::BrokenLinks(BrokenLinksParams::default()),
+        }
+    }
+
+    pub fn toggle_reading_list(&mut self) {
+        self.mode = match self.mode {
+            Mode::ReadingList(_) => Mode::Scrolling,
+            _ => {
+                let mut params = ReadingListParams::default();
+                params.set_items(&self.bookmarks);
+                Mode::ReadingList(params)
+            }
+        }
+    }
+
+    pub fn toggle_packs(&mut self) {
+        self.mode = match self.mode {
+            Mode::Packs(_) => Mode::Scrolling,
+            _ => {
+                let mut params = PacksParams::default();
+                params.items.replace_items(self.packs.clone());
+                Mode::Packs(params)
+            }
+        }
+    }
+
+    pub fn toggle_saved_searches(&mut self) {
+        self.mode = match self.mode {
+            Mode::SavedSearches(_) => Mode::Scrolling,
+            _ => {
+                let mut params = SavedSearchesParams::default();
+                params.items.replace_items(self.saved_searches.clone());
+                Mode::SavedSearches(params)
+            }
+        }
+    }
+
+    /// Replaces the saved searches, in the picker as well if it's open.
+    pub fn set_saved_searches(&mut self, searches: Vec<SavedSearch>) {
+        self.saved_searches = searches;
+        if let Mode::SavedSearches(params) = &mut self.mode {
+            params.items.replace_items(self.saved_searches.clone());
+        }
+    }
+
+    /// The typed queries along with the tags filter.
+    pub fn current_search(&self) -> SearchHistoryEntry {
+        let (title_query, link_query) = match &self.mode {
+            Mode::Search(params) => (
+                params.title.value().trim().to_string(),
+                params.link.value().trim().to_string(),
+            ),
+            _ => (String::new(), String::new()),
+        };
+        SearchHistoryEntry {
+            title_query,
+            link_query,
+            tags: self.tags_filter.clone(),
+        }
+    }
+
+    /// The search to be added to the history, unless it's empty or the latest one already.
+    pub fn search_to_remember(&self) -> Option<SearchHistoryEntry> {
+        if !matches!(self.mode, Mode::Search(_)) {
+            return None;
+        }
+        let search = self.current_search();
+        let is_latest = self
+            .search_history
+            .last()
+            .is_some_and(|latest| latest.is_same(&search));
+        (!search.is_empty() && !is_latest).then_some(search)
+    }
+
+    pub fn set_search_history(&mut self, history: Vec<SearchHistoryEntry>) {
+        let skipped = history.len().saturating_sub(HISTORY_LIMIT as usize);
+        self.search_history = history.into_iter().skip(skipped).collect();
+        // the positions in the history may point elsewhere now
+        if let Mode::Search(params) = &mut self.mode {
+            params.recalled = None;
+            if let Some(reverse_search) = &mut params.reverse_search {
+                reverse_search.find(&self.search_history, false);
+            }
+        }
+    }
+
+    /// Steps through the history from the search in the inputs, returns the search to be put there.
+    ///
+    /// Going past the latest search brings back what has been typed before the history was recalled.
+    pub fn recall_search(&mut self, older: bool) -> Option<SearchHistoryEntry> {
+        let current = self.current_search();
+        let Mode::Search(params) = &mut self.mode else {
+            return None;
+        };
+        let latest = self.search_history.len().checked_sub(1)?;
+
+        let position = match (params.recalled, older) {
+            (None, true) => {
+                params.draft = Some(current);
+                0
+            }
+            (Some(position), true) if position < latest => position + 1,
+            (Some(_), true) | (None, false) => return None,
+            (Some(0), false) => {
+                params.recalled = None;
+                return Some(params.draft.take().unwrap_or_default());
+            }
+            (Some(position), false) => position - 1,
+        };
+        params.recalled = Some(position);
+        self.search_history.get(latest - position).cloned()
+    }
+
+    /// The queries, the tags filter and the sort order to be saved as a search, unless they're all empty.
+    pub fn search_to_save(&self) -> Option<NewSavedSearchParams> {
+        let SearchHistoryEntry {
+            title_query,
+            link_query,
+            ..
+        } = self.current_search();
+        if title_query.is_empty() && link_query.is_empty() && self.tags_filter.is_empty() {
+            return None;
+        }
+
+        // the name tells what the search is, it can be changed in the picker
+        let mut parts = vec![];
+        if !title_query.is_empty() {
+            parts.push(format!("\"{title_query}\""));
+        }
+        if !link_query.is_empty() {
+            parts.push(format!("link \"{link_query}\""));
+        }
+        parts.extend(self.tags_filter.iter().map(|tag| format!("#{}", tag.name)));
+
+        Some(NewSavedSearchParams {
+            name: parts.join(" "),
+            title_query,
+            link_query,
+            tag_ids: self.tags_filter.iter().map(|tag| tag.id).collect(),
+            sort_column: self.sort.map(|sort| sort.column),
+            sort_descending: self.sort.is_some_and(|sort| sort.descending),
+        })
+    }
+
+    /// Puts the queries, the tags and the sort order of the saved search in place,
+    /// the library is to be refreshed for the tags to apply.
+    pub fn apply_saved_search(&mut self, search: &SavedSearch) {
+        self.tags_filter = search.tags.clone();
+        self.collection_filter = None;
+        self.sort = search.sort_column.map(|column| Sort {
+            column,
+            descending: search.sort_descending,
+        });
+        self.mode = Mode::Search(SearchingParams {
+            active_window: if search.title_query.is_empty() && !search.link_query.is_empty() {
+                ActiveWindow::Link
+            } else {
+                ActiveWindow::Title
+            },
+            title: Input::new(search.title_query.clone()),
+            link: Input::new(search.link_query.clone()),
+            ..Default::default()
+        });
+    }
+
+    pub fn toggle_collections(&mut self) {
+        self.mode = match self.mode {
+            Mode::Collections(_) => Mode::Scrolling,
+            _ => Mode::Collections(CollectionsParams::default()),
         }
     }
 
-    pub async fn reset(&mut self) -> Result<()> {
-        let (_, bookmarks_items, _, tags_items, ..) =
-            Self::refresh_state(&self.repo, Vec::new()).await?;
-        self.bookmarks_items = bookmarks_items;
-        self.tags_items = tags_items;
-        self.tags_filter = Vec::new();
+    /// Opens the sidebar to pick the collection for the bookmarks.
+    pub fn start_moving(&mut self, ids: Vec<i64>) {
+        self.mode = Mode::Collections(CollectionsParams {
+            moving: ids,
+            ..Default::default()
+        });
+    }
 
-        Ok(())
+    pub fn toggle_tag_merges(&mut self) {
+        self.mode = match self.mode {
+            Mode::TagMerges(_) => Mode::Scrolling,
+            _ => Mode::TagMerges(TagMergesParams::default()),
+        }
+    }
+
+    pub fn toggle_auto_tagging(&mut self) {
+        self.mode = match self.mode {
+            Mode::AutoTagging(_) => Mode::Scrolling,
+            _ => Mode::AutoTagging(AutoTaggingParams::default()),
+        }
     }
 
     pub fn change_active_window(&mut self) {
@@ -96,154 +346,670 @@ impl App {
                 ActiveWindow::Title => params.active_window = ActiveWindow::Tags,
                 ActiveWindow::Tags => params.active_window = ActiveWindow::Link,
             },
-            Mode::Scrolling => {}
+            Mode::Scrolling
+            | Mode::BrokenLinks(_)
+            | Mode::ReadingList(_)
+            | Mode::Packs(_)
+            | Mode::TagMerges(_)
+            | Mode::AutoTagging(_)
+            | Mode::Collections(_)
+            | Mode::SavedSearches(_) => {}
+        }
+    }
+
+    pub fn activate_window(&mut self, window: ActiveWindow) {
+        match self.mode {
+            Mode::Search(ref mut params) => params.active_window = window,
+            Mode::Create(ref mut params) => params.active_window = window,
+            Mode::Scrolling
+            | Mode::BrokenLinks(_)
+            | Mode::ReadingList(_)
+            | Mode::Packs(_)
+            | Mode::TagMerges(_)
+            | Mode::AutoTagging(_)
+            | Mode::Collections(_)
+            | Mode::SavedSearches(_) => {
+                self.mode = Mode::Search(SearchingParams {
+                    active_window: window,
+                    ..Default::default()
+                })
+            }
         }
     }
 
-    pub async fn sync_bmarks(&mut self) -> Result<()> {
-        let tagpacker_links = tagpacker::get_links().await?;
-        self.bookmarks = Bookmark::batch_create(&self.repo, tagpacker_links).await?;
-        self.bookmarks_items = StatefulList::with_items(self.bookmarks.clone());
-        self.tags = Tag::fetch_all(&self.repo).await?;
-        self.tags_items = StatefulList::with_items(self.tags.clone());
+    /// Remembers a click and tells whether it completes a double-click on the same cell.
+    pub fn register_click(&mut self, column: u16, row: u16, at: Instant) -> bool {
+        let is_double = matches!(
+            self.last_click,
+            Some((last, c, r)) if c == column && r == row && at.duration_since(last) <= DOUBLE_CLICK_INTERVAL
+        );
+        // a double-click shouldn't turn into another one with the third click
+        self.last_click = if is_double {
+            None
+        } else {
+            Some((at, column, row))
+        };
 
-        Ok(())
+        is_double
     }
 
-    pub async fn resolve_enter(&mut self) -> Result<()> {
+    pub fn on_tick(&mut self) {
+        self.tick = self.tick.wrapping_add(1);
+    }
+
+    /// Replaces the loaded library, keeping the tag and the collection under the cursor where possible.
+    pub fn set_library(
+        &mut self,
+        bookmarks: Vec<Bookmark>,
+        tags: Vec<Tag>,
+        packs: Vec<Pack>,
+        collections: Vec<Collection>,
+    ) {
+        self.bookmarks = bookmarks;
+        // the search results are found again by the caller
+        self.relist(self.bookmarks.clone());
+
+        self.tags = tags;
+        self.packs = packs;
+        self.rebuild_tags_tree();
+
+        // the checks or the archived copy may have changed along with the library
+        self.details_for = None;
+
+        self.collections = collections;
+        // the filter goes away along with the deleted collection
+        if self
+            .collection_filter
+            .is_some_and(|id| !self.collections.iter().any(|c| c.id == id))
+        {
+            self.collection_filter = None;
+        }
+        self.rebuild_collections_tree();
+
         match &mut self.mode {
-            Mode::Search(_) => {
-                if let Some(tag_index) = self.tags_items.state.selected() {
-                    // TODO: add deselection for a tag if it was already selected
-                    let selected_tag = self.tags_items.items[tag_index].clone();
-                    self.tags_filter.push(selected_tag);
-
-                    let (bookmarks, items, ..) =
-                        Self::refresh_state(&self.repo, self.tags_filter.clone()).await?;
-                    self.bookmarks = bookmarks;
-                    self.bookmarks_items = items;
-                }
+            Mode::ReadingList(params) => params.set_items(&self.bookmarks),
+            Mode::Packs(params) => params.items.replace_items(self.packs.clone()),
+            _ => {}
+        }
+    }
 
-                Ok(())
-            }
-            Mode::Create(params) => {
-                match params.active_window {
-                    ActiveWindow::Link | ActiveWindow::Title => {
-                        let title = Some(params.title.to_string());
-                        let url = Url::parse(&params.link.to_string())?;
-                        // TODO: add tags
-                        let _bookmark = Bookmark::create(
-                            &self.repo,
-                            title,
-                            url,
-                            Some(params.selected_tags.clone()),
-                        )
-                        .await?;
-                        let (bookmarks, bookmarks_items, tags, tags_items) =
-                            Self::refresh_state(&self.repo, self.tags_filter.clone()).await?;
-
-                        self.bookmarks = bookmarks;
-                        self.bookmarks_items = bookmarks_items;
-                        self.tags = tags;
-                        self.tags_items = tags_items;
-                        self.mode = Mode::Scrolling;
-                        Ok(())
-                    }
-                    ActiveWindow::Tags => {
-                        // TODO: add deselection for a tag if it was already selected
-                        if let Some(tag_index) = params.tags_items.state.selected() {
-                            let selected_tag = params.tags_items.items[tag_index].clone();
-                            params.selected_tags.push(selected_tag);
-                        }
-                        Ok(())
-                    }
-                }
-            }
-            Mode::Scrolling => {
-                if let Some(url_index) = self.bookmarks_items.state.selected() {
-                    let url: String = self.bookmarks_items.items[url_index].url.clone().into();
-                    open::that(url)?;
-                }
-                Ok(())
-            }
+    /// Adds the next page of the library that is being refreshed.
+    pub fn append_bookmarks(&mut self, bookmarks: Vec<Bookmark>) {
+        // the search results don't grow, the search runs over the whole library again instead
+        if !self.lists_results() {
+            self.bookmarks_items.items.extend(bookmarks.iter().cloned());
+            self.sort_items();
         }
+        self.bookmarks.extend(bookmarks);
+
+        if let Mode::ReadingList(params) = &mut self.mode {
+            params.set_items(&self.bookmarks);
+        }
+    }
+
+    /// Lists the search results in place of the library, no query lists the whole library.
+    pub fn list_results(&mut self, results: Vec<Bookmark>, query: Option<ResultsQuery>) {
+        self.bookmarks_items.unselect();
+        self.bookmarks_items.items = results;
+        self.results_query = query;
+        self.sort_items();
+    }
+
+    pub fn lists_results(&self) -> bool {
+        self.results_query.is_some()
+    }
+
+    /// Replaces the listed bookmarks, keeping the cursor and the scroll on the same bookmark.
+    pub fn relist(&mut self, bookmarks: Vec<Bookmark>) {
+        let selected_id = self.selected_bookmark().map(|bookmark| bookmark.id);
+        self.bookmarks_items.replace_items(bookmarks);
+        self.sort_items();
+
+        let index = selected_id.and_then(|id| {
+            self.bookmarks_items
+                .items
+                .iter()
+                .position(|bookmark| bookmark.id == id)
+        });
+        if index.is_some() {
+            self.bookmarks_items.state.select(index);
+        }
+    }
+
+    /// Adds the saved bookmark without reloading the library.
+    pub fn insert_bookmark(&mut self, bookmark: Bookmark) {
+        self.append_bookmarks(vec![bookmark]);
+    }
+
+    /// Drops the deleted bookmark without reloading the library.
+    pub fn remove_bookmark(&mut self, id: i64) {
+        self.bookmarks.retain(|bookmark| bookmark.id != id);
+        self.pinned.retain(|bookmark| bookmark.id != id);
+        self.marked.remove(&id);
+        self.archived.remove(&id);
+
+        let items = std::mem::take(&mut self.bookmarks_items.items)
+            .into_iter()
+            .filter(|bookmark| bookmark.id != id)
+            .collect();
+        self.bookmarks_items.replace_items(items);
+
+        if let Mode::ReadingList(params) = &mut self.mode {
+            params.set_items(&self.bookmarks);
+        }
+    }
+
+    /// The pack the tag belongs to.
+    pub fn pack_of(&self, tag: &Tag) -> Option<&Pack> {
+        let pack_id = tag.pack_id?;
+        self.packs.iter().find(|pack| pack.id == pack_id)
+    }
+
+    /// The tag under the cursor in the tags panel, unless it's a pack.
+    pub fn selected_tag(&self) -> Option<&Tag> {
+        match self
+            .tags_items
+            .state
+            .selected()
+            .and_then(|index| self.tags_items.items.get(index))
+        {
+            Some(TagsRow::Tag(tag)) => Some(tag),
+            _ => None,
+        }
+    }
+
+    /// The row under the cursor in the collections sidebar.
+    pub fn selected_collection_row(&self) -> Option<&CollectionsRow> {
+        self.collections_items
+            .state
+            .selected()
+            .and_then(|index| self.collections_items.items.get(index))
+    }
+
+    /// Expands the selected collection, or collapses it back.
+    pub fn toggle_selected_collection(&mut self) {
+        let Some(id) = self
+            .selected_collection_row()
+            .and_then(|row| row.collection.as_ref())
+            .map(|collection| collection.id)
+        else {
+            return;
+        };
+        if !self.collapsed_collections.remove(&id) {
+            self.collapsed_collections.insert(id);
+        }
+        self.rebuild_collections_tree();
+    }
+
+    /// Ids of the selected collection and its siblings in the order they'd go
+    /// after the collection is moved up or down, `None` if it can't go there.
+    pub fn reordered_siblings(&self, up: bool) -> Option<Vec<i64>> {
+        let selected = self.selected_collection_row()?.collection.as_ref()?;
+        let mut ids: Vec<i64> = self
+            .collections
+            .iter()
+            .filter(|collection| collection.parent_id == selected.parent_id)
+            .map(|collection| collection.id)
+            .collect();
+        let index = ids.iter().position(|id| *id == selected.id)?;
+        let other = if up { index.checked_sub(1)? } else { index + 1 };
+        if other >= ids.len() {
+            return None;
+        }
+        ids.swap(index, other);
+        Some(ids)
+    }
+
+    /// Lays the collections out as a tree, keeping the cursor on the same row where possible.
+    fn rebuild_collections_tree(&mut self) {
+        let selected_id = self
+            .selected_collection_row()
+            .map(|row| row.collection.as_ref().map(|collection| collection.id));
+
+        let items = collections_rows(&self.collections, &self.collapsed_collections);
+        let selected = selected_id.and_then(|id| {
+            items
+                .iter()
+                .position(|row| row.collection.as_ref().map(|collection| collection.id) == id)
+        });
+        self.collections_items = StatefulList::with_items(items);
+        self.collections_items.state.select(selected);
     }
 
-    pub async fn on_delete(&mut self) -> Result<()> {
+    /// The bookmark under the cursor in the list that is shown.
+    pub fn highlighted_bookmark(&self) -> Option<&Bookmark> {
         match &self.mode {
-            Mode::Search(_) | Mode::Create(_) => Ok(()),
-            Mode::Scrolling => {
-                if let Some(index) = self.bookmarks_items.state.selected() {
-                    let id = self.bookmarks_items.items[index].id;
-                    Bookmark::delete(&self.repo, id).await?;
-
-                    let (bookmarks, items, ..) =
-                        Self::refresh_state(&self.repo, self.tags_filter.clone()).await?;
-                    self.bookmarks = bookmarks;
-                    self.bookmarks_items = items;
-                }
-                Ok(())
+            Mode::ReadingList(params) => params.selected(),
+            Mode::Scrolling | Mode::Search(_) | Mode::Collections(_) => self.selected_bookmark(),
+            Mode::BrokenLinks(params) => params.selected().and_then(|broken_link| {
+                self.bookmarks
+                    .iter()
+                    .find(|bookmark| bookmark.id == broken_link.bookmark_id)
+            }),
+            Mode::Create(_)
+            | Mode::Packs(_)
+            | Mode::TagMerges(_)
+            | Mode::AutoTagging(_)
+            | Mode::SavedSearches(_) => None,
+        }
+    }
+
+    pub fn toggle_details(&mut self) {
+        self.show_details = !self.show_details;
+        self.details_for = None;
+    }
+
+    /// The bookmark whose details are to be loaded, once the pane shows another one.
+    pub fn request_details(&mut self) -> Option<i64> {
+        if !self.show_details {
+            return None;
+        }
+        let id = self.highlighted_bookmark().map(|bookmark| bookmark.id);
+        if id == self.details_for {
+            return None;
+        }
+        self.details_for = id;
+        id
+    }
+
+    /// Names of the collection and the ones it's nested into, the outermost first.
+    pub fn collection_path(&self, id: i64) -> Vec<&str> {
+        let mut path = Vec::new();
+        let mut next = Some(id);
+        while let Some(collection) =
+            next.and_then(|id| self.collections.iter().find(|c| c.id == id))
+        {
+            path.push(collection.name.as_str());
+            next = collection.parent_id;
+            // a broken tree shouldn't hang the app
+            if path.len() > self.collections.len() {
+                break;
             }
         }
+        path.reverse();
+        path
     }
 
-    pub async fn refresh_state(
-        repo: &Repo,
-        filter_tags: Vec<Tag>,
-    ) -> Result<(
-        Vec<Bookmark>,
-        StatefulList<Bookmark>,
-        Vec<Tag>,
-        StatefulList<Tag>,
-    )> {
-        let bookmarks = Bookmark::fetch_all(repo, filter_tags).await?;
-        let bookmark_items = StatefulList::with_items(bookmarks.clone());
-        let tags = Tag::fetch_all(repo).await?;
-        let tags_items = StatefulList::with_items(tags.clone());
+    pub fn selected_bookmark(&self) -> Option<&Bookmark> {
+        self.bookmarks_items
+            .state
+            .selected()
+            .and_then(|index| self.bookmarks_items.items.get(index))
+    }
 
-        Ok((bookmarks, bookmark_items, tags, tags_items))
+    /// Sorts by the column, the same column again flips the direction.
+    pub fn sort_by(&mut self, column: Column) {
+        self.sort = Some(Sort {
+            column,
+            descending: self
+                .sort
+                .is_some_and(|sort| sort.column == column && !sort.descending),
+        });
+        self.sort_items();
     }
 
-    pub fn add_char(&mut self, key_event: KeyEvent) -> Result<()> {
-        match self.mode {
-            Mode::Search(ref mut params) => match params.active_window {
-                // TODO: remove code duplication
-                ActiveWindow::Link => {
-                    params.link.handle_event(&Event::Key(key_event));
+    /// Goes through the shown columns one by one, and back to the unsorted list.
+    pub fn cycle_sort(&mut self) {
+        let next = match self.sort {
+            None => self.columns.first(),
+            Some(sort) => self
+                .columns
+                .iter()
+                .position(|shown| shown.column == sort.column)
+                .and_then(|index| self.columns.get(index + 1)),
+        };
+        self.sort = next.map(|shown| Sort {
+            column: shown.column,
+            descending: false,
+        });
+        self.sort_items();
+    }
 
-                    let new_state = search(self.bookmarks.clone(), params.link.to_string(), false)?;
-                    self.bookmarks_items.unselect();
-                    self.bookmarks_items.items = new_state;
-                }
-                ActiveWindow::Title => {
-                    params.title.handle_event(&Event::Key(key_event));
+    pub fn reverse_sort(&mut self) {
+        if let Some(sort) = &mut self.sort {
+            sort.descending = !sort.descending;
+        }
+        self.sort_items();
+    }
 
-                    let new_state = search(self.bookmarks.clone(), params.title.to_string(), true)?;
-                    self.bookmarks_items.unselect();
-                    self.bookmarks_items.items = new_state;
-                }
-                ActiveWindow::Tags => {}
-            },
-            Mode::Create(ref mut params) => match params.active_window {
-                ActiveWindow::Link => {
-                    params.link.handle_event(&Event::Key(key_event));
-                }
-                ActiveWindow::Title => {
-                    params.title.handle_event(&Event::Key(key_event));
+    /// Puts the listed bookmarks in the chosen order, keeping the cursor on the same bookmark.
+    pub fn sort_items(&mut self) {
+        let Some(sort) = self.sort else {
+            return;
+        };
+        let selected_id = self.selected_bookmark().map(|bookmark| bookmark.id);
+
+        self.bookmarks_items
+            .items
+            .sort_by(|a, b| sort.compare(a, b));
+
+        if let Some(id) = selected_id {
+            let index = self
+                .bookmarks_items
+                .items
+                .iter()
+                .position(|bookmark| bookmark.id == id);
+            self.bookmarks_items.state.select(index);
+        }
+    }
+
+    /// Remembers the time the bookmark has been opened at wherever it's listed.
+    pub fn set_opened(&mut self, id: i64, at: DateTime<Utc>) {
+        for bookmark in self
+            .bookmarks
+            .iter_mut()
+            .chain(self.bookmarks_items.items.iter_mut())
+            .chain(self.pinned.iter_mut())
+            .filter(|bookmark| bookmark.id == id)
+        {
+            bookmark.opened_at = Some(at);
+        }
+    }
+
+    pub fn toggle_mark(&mut self, id: i64) {
+        if !self.marked.remove(&id) {
+            self.marked.insert(id);
+        }
+    }
+
+    /// The marked bookmarks, or the selected one if nothing is marked.
+    pub fn targets(&self) -> Vec<i64> {
+        if self.marked.is_empty() {
+            self.selected_bookmark()
+                .map(|bookmark| bookmark.id)
+                .into_iter()
+                .collect()
+        } else {
+            self.marked.iter().copied().collect()
+        }
+    }
+
+    /// Expands the selected tag or pack, or collapses it back if there's anything under it.
+    pub fn toggle_selected_tag(&mut self) {
+        let Some(row) = self
+            .tags_items
+            .state
+            .selected()
+            .and_then(|index| self.tags_items.items.get(index))
+        else {
+            return;
+        };
+        let tag = match row {
+            TagsRow::Tag(tag) => tag,
+            TagsRow::Pack(pack) => {
+                let id = pack.as_ref().map(|pack| pack.id);
+                if !self.collapsed_packs.remove(&id) {
+                    self.collapsed_packs.insert(id);
                 }
-                ActiveWindow::Tags => {}
-            },
-            Mode::Scrolling => {}
+                self.rebuild_tags_tree();
+                return;
+            }
+        };
+        if !self
+            .tags
+            .iter()
+            .any(|other| other.parent_id == Some(tag.id))
+        {
+            return;
         }
-        Ok(())
+
+        let id = tag.id;
+        if !self.expanded_tags.remove(&id) {
+            self.expanded_tags.insert(id);
+        }
+        self.rebuild_tags_tree();
+    }
+
+    /// Lays the tags out as a tree, keeping the cursor on the same row where possible.
+    fn rebuild_tags_tree(&mut self) {
+        let selected_key = self
+            .tags_items
+            .state
+            .selected()
+            .and_then(|index| self.tags_items.items.get(index))
+            .map(TagsRow::key);
+
+        let items = tags_rows(
+            &self.tags,
+            &self.packs,
+            &self.expanded_tags,
+            &self.collapsed_packs,
+        );
+        let selected = selected_key.and_then(|key| items.iter().position(|row| row.key() == key));
+        self.tags_items = StatefulList::with_items(items);
+        self.tags_items.state.select(selected);
     }
 
     pub fn toggle_help_render(&mut self) {
         self.render_help = !self.render_help;
     }
+
+    /// Opens the command palette with the actions of the current mode, or closes it.
+    pub fn toggle_palette(&mut self) {
+        self.palette = match self.palette {
+            Some(_) => None,
+            None => Some(PaletteParams::new(palette::entries(self))),
+        };
+    }
 }
 
-fn search<T: AsRef<str>>(
+/// The command palette, narrowed down by the query being typed.
+#[derive(Debug, Default)]
+pub struct PaletteParams {
+    pub query: Input,
+    entries: Vec<Entry>,
+    pub items: StatefulList<Entry>,
+}
+
+impl PaletteParams {
+    pub fn new(entries: Vec<Entry>) -> Self {
+        let mut params = Self {
+            entries,
+            ..Default::default()
+        };
+        params.filter();
+        params
+    }
+
+    /// Shows the entries that match the query, starting from the best one.
+    pub fn filter(&mut self) {
+        self.items = StatefulList::with_items(palette::filter(&self.entries, self.query.value()));
+        if !self.items.items.is_empty() {
+            self.items.state.select(Some(0));
+        }
+    }
+
+    pub fn selected(&self) -> Option<&Entry> {
+        self.items
+            .state
+            .selected()
+            .and_then(|index| self.items.items.get(index))
+    }
+}
+
+/// A row of the tags panel.
+#[derive(Debug, Clone)]
+pub enum TagsRow {
+    /// The header of a pack, `None` heads the tags without a pack.
+    Pack(Option<Pack>),
+    Tag(Tag),
+}
+
+impl TagsRow {
+    /// Tells the rows apart between rebuilds of the panel.
+    fn key(&self) -> (bool, Option<i64>) {
+        match self {
+            Self::Pack(pack) => (true, pack.as_ref().map(|pack| pack.id)),
+            Self::Tag(tag) => (false, Some(tag.id)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sort {
+    pub column: Column,
+    pub descending: bool,
+}
+
+impl Sort {
+    fn compare(&self, a: &Bookmark, b: &Bookmark) -> std::cmp::Ordering {
+        let tags = |bookmark: &Bookmark| {
+            bookmark
+                .tags
+                .iter()
+                .flatten()
+                .map(|tag| tag.name.to_lowercase())
+                .collect::<Vec<_>>()
+        };
+        let ordering = match self.column {
+            Column::Title => a
+                .title
+                .as_deref()
+                .unwrap_or_default()
+                .to_lowercase()
+                .cmp(&b.title.as_deref().unwrap_or_default().to_lowercase()),
+            Column::Domain => a.url.inner.host_str().cmp(&b.url.inner.host_str()),
+            Column::Url => a.url.inner.as_str().cmp(b.url.inner.as_str()),
+            Column::Tags => tags(a).cmp(&tags(b)),
+            Column::Added => a.created_at.cmp(&b.created_at),
+            Column::Opened => a.opened_at.cmp(&b.opened_at),
+        };
+        if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+/// The last check and the archived copy of a bookmark.
+#[derive(Debug, Clone)]
+pub struct BookmarkDetails {
+    pub bookmark_id: i64,
+    pub check: Option<LinkCheck>,
+    pub archived_at: Option<DateTime<Utc>>,
+    /// Size of the archived page before compression.
+    pub archive_size: Option<i64>,
+}
+
+/// A row of the collections sidebar.
+#[derive(Debug, Clone)]
+pub struct CollectionsRow {
+    /// `None` stands for the whole library, or for no collection when the bookmarks are moved.
+    pub collection: Option<Collection>,
+    pub depth: usize,
+    pub has_children: bool,
+}
+
+/// The collections tree in the order of the siblings, without the children of the collapsed ones.
+fn collections_rows(collections: &[Collection], collapsed: &HashSet<i64>) -> Vec<CollectionsRow> {
+    let ids: HashSet<i64> = collections.iter().map(|c| c.id).collect();
+    let mut children: HashMap<Option<i64>, Vec<&Collection>> = HashMap::new();
+    for collection in collections {
+        // the collections with unknown parents go to the top level
+        let parent_id = collection.parent_id.filter(|id| ids.contains(id));
+        children.entry(parent_id).or_default().push(collection);
+    }
+
+    let mut rows = vec![CollectionsRow {
+        collection: None,
+        depth: 0,
+        has_children: false,
+    }];
+    // reversed, so they come off the stack in their order
+    let mut stack: Vec<(&Collection, usize)> = children
+        .get(&None)
+        .into_iter()
+        .flatten()
+        .rev()
+        .map(|collection| (*collection, 0))
+        .collect();
+    while let Some((collection, depth)) = stack.pop() {
+        let nested = children.get(&Some(collection.id));
+        rows.push(CollectionsRow {
+            collection: Some(collection.clone()),
+            depth,
+            has_children: nested.is_some(),
+        });
+        if !collapsed.contains(&collection.id) {
+            stack.extend(
+                nested
+                    .into_iter()
+                    .flatten()
+                    .rev()
+                    .map(|child| (*child, depth + 1)),
+            );
+        }
+    }
+    rows
+}
+
+/// The tags tree split by packs, the headers are left out if there are no packs at all.
+fn tags_rows(
+    tags: &[Tag],
+    packs: &[Pack],
+    expanded: &HashSet<i64>,
+    collapsed_packs: &HashSet<Option<i64>>,
+) -> Vec<TagsRow> {
+    if packs.is_empty() {
+        return visible_tags(tags, expanded)
+            .into_iter()
+            .map(TagsRow::Tag)
+            .collect();
+    }
+
+    let pack_ids: HashSet<i64> = packs.iter().map(|pack| pack.id).collect();
+    let mut rows = Vec::new();
+    for pack in packs.iter().map(Some).chain(std::iter::once(None)) {
+        let pack_id = pack.map(|pack| pack.id);
+        let members: Vec<Tag> = tags
+            .iter()
+            .filter(|tag| tag.pack_id.filter(|id| pack_ids.contains(id)) == pack_id)
+            .cloned()
+            .collect();
+        // empty packs are shown, so the new ones can be filled
+        if pack.is_none() && members.is_empty() {
+            continue;
+        }
+
+        rows.push(TagsRow::Pack(pack.cloned()));
+        if !collapsed_packs.contains(&pack_id) {
+            rows.extend(
+                visible_tags(&members, expanded)
+                    .into_iter()
+                    .map(TagsRow::Tag),
+            );
+        }
+    }
+    rows
+}
+
+/// Tags in the order of the tree, without the children of the collapsed ones.
+fn visible_tags(tags: &[Tag], expanded: &HashSet<i64>) -> Vec<Tag> {
+    let ids: HashSet<i64> = tags.iter().map(|tag| tag.id).collect();
+    let mut children: HashMap<Option<i64>, Vec<&Tag>> = HashMap::new();
+    for tag in tags {
+        // the tags with unknown parents go to the top level
+        let parent_id = tag.parent_id.filter(|parent_id| ids.contains(parent_id));
+        children.entry(parent_id).or_default().push(tag);
+    }
+    for siblings in children.values_mut() {
+        // reversed, so they come off the stack in the alphabetical order
+        siblings.sort_by(|a, b| b.name.cmp(&a.name));
+    }
+
+    let mut visible = Vec::new();
+    let mut stack = children.get(&None).cloned().unwrap_or_default();
+    while let Some(tag) = stack.pop() {
+        visible.push(tag.clone());
+        if expanded.contains(&tag.id) {
+            stack.extend(children.get(&Some(tag.id)).into_iter().flatten());
+        }
+    }
+    visible
+}
+
+pub fn search<T: AsRef<str>>(
     bookmarks: Vec<Bookmark>,
     search_string: T,
     for_titles: bool,
@@ -278,18 +1044,369 @@ fn search<T: AsRef<str>>(
 #[derive(Debug, Default)]
 pub enum Mode {
     Search(SearchingParams),
-    Create(CreationParams),
+    Create(Box<CreationParams>),
+    BrokenLinks(BrokenLinksParams),
+    ReadingList(ReadingListParams),
+    Packs(PacksParams),
+    TagMerges(TagMergesParams),
+    AutoTagging(AutoTaggingParams),
+    Collections(CollectionsParams),
+    SavedSearches(SavedSearchesParams),
     #[default]
     Scrolling,
 }
 
+/// The collections sidebar is focused, to browse the library or to move the bookmarks.
+#[derive(Debug, Default)]
+pub struct CollectionsParams {
+    pub editing: Option<CollectionDraft>,
+    /// The bookmarks waiting for the collection to be picked.
+    pub moving: Vec<i64>,
+}
+
+#[derive(Debug, Default)]
+pub struct CollectionDraft {
+    /// The collection being renamed, `None` for a new one.
+    pub id: Option<i64>,
+    /// Where a new collection goes.
+    pub parent_id: Option<i64>,
+    pub name: Input,
+}
+
+#[derive(Debug, Default)]
+pub struct BrokenLinksParams {
+    pub items: StatefulList<BrokenLink>,
+}
+
+impl BrokenLinksParams {
+    pub fn selected(&self) -> Option<&BrokenLink> {
+        self.items
+            .state
+            .selected()
+            .and_then(|index| self.items.items.get(index))
+    }
+
+    pub fn set_items(&mut self, items: Vec<BrokenLink>) {
+        self.items.replace_items(items);
+    }
+}
+
+/// The list of the tag packs, with the name of a new or renamed one being typed.
+#[derive(Debug, Default)]
+pub struct PacksParams {
+    pub items: StatefulList<Pack>,
+    pub editing: Option<PackDraft>,
+}
+
+#[derive(Debug, Default)]
+pub struct PackDraft {
+    /// The pack being renamed, `None` for a new one.
+    pub id: Option<i64>,
+    pub name: Input,
+}
+
+impl PacksParams {
+    pub fn selected(&self) -> Option<&Pack> {
+        self.items
+            .state
+            .selected()
+            .and_then(|index| self.items.items.get(index))
+    }
+}
+
+/// The saved searches, with the new name of the selected one being typed.
+#[derive(Debug, Default)]
+pub struct SavedSearchesParams {
+    pub items: StatefulList<SavedSearch>,
+    pub renaming: Option<Input>,
+}
+
+impl SavedSearchesParams {
+    pub fn selected(&self) -> Option<&SavedSearch> {
+        self.items
+            .state
+            .selected()
+            .and_then(|index| self.items.items.get(index))
+    }
+}
+
+/// Near-duplicate tags proposed to be merged.
+#[derive(Debug, Default)]
+pub struct TagMergesParams {
+    pub items: StatefulList<MergeCandidate>,
+}
+
+impl TagMergesParams {
+    pub fn selected(&self) -> Option<&MergeCandidate> {
+        self.items
+            .state
+            .selected()
+            .and_then(|index| self.items.items.get(index))
+    }
+}
+
+/// The tags the rules would add to the library, waiting to be applied.
+#[derive(Debug, Default)]
+pub struct AutoTaggingParams {
+    /// Descriptions of the rules by their indexes.
+    pub rules: Vec<String>,
+    pub changes: Vec<TagChange>,
+    /// The rule whose changes are shown, all of them are if there's none.
+    pub shown_rule: Option<usize>,
+    pub items: StatefulList<TagChange>,
+}
+
+impl AutoTaggingParams {
+    pub fn set_changes(&mut self, rules: Vec<String>, changes: Vec<TagChange>) {
+        if self.shown_rule.is_some_and(|rule| rule >= rules.len()) {
+            self.shown_rule = None;
+        }
+        self.rules = rules;
+        self.changes = changes;
+        self.show_changes();
+    }
+
+    /// Goes through the rules one by one, and back to all of them.
+    pub fn cycle_rule(&mut self) {
+        self.shown_rule = match self.shown_rule {
+            None if !self.rules.is_empty() => Some(0),
+            Some(rule) if rule + 1 < self.rules.len() => Some(rule + 1),
+            _ => None,
+        };
+        self.items.unselect();
+        self.show_changes();
+    }
+
+    /// Leaves the selected change out of the ones to be applied.
+    pub fn dismiss_selected(&mut self) {
+        let Some(change) = self
+            .items
+            .state
+            .selected()
+            .and_then(|index| self.items.items.get(index))
+        else {
+            return;
+        };
+        let (bookmark_id, rule) = (change.bookmark_id, change.rule);
+        self.changes
+            .retain(|change| change.bookmark_id != bookmark_id || change.rule != rule);
+        self.show_changes();
+    }
+
+    fn show_changes(&mut self) {
+        let items = self
+            .changes
+            .iter()
+            .filter(|change| self.shown_rule.map_or(true, |rule| change.rule == rule))
+            .cloned()
+            .collect();
+        self.items.replace_items(items);
+    }
+}
+
+/// The read-later queue, the latest bookmarks go first.
+#[derive(Debug)]
+pub struct ReadingListParams {
+    pub items: StatefulList<Bookmark>,
+    /// Status of the listed bookmarks.
+    pub shown: ReadingStatus,
+}
+
+impl Default for ReadingListParams {
+    fn default() -> Self {
+        Self {
+            items: StatefulList::default(),
+            shown: ReadingStatus::Unread,
+        }
+    }
+}
+
+impl ReadingListParams {
+    pub fn selected(&self) -> Option<&Bookmark> {
+        self.items
+            .state
+            .selected()
+            .and_then(|index| self.items.items.get(index))
+    }
+
+    /// Picks the bookmarks with the shown status out of the library.
+    pub fn set_items(&mut self, bookmarks: &[Bookmark]) {
+        let mut items: Vec<Bookmark> = bookmarks
+            .iter()
+            .filter(|bookmark| bookmark.status == self.shown)
+            .cloned()
+            .collect();
+        // the bookmarks without the date are older than any with it
+        items.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+        self.items.replace_items(items);
+    }
+
+    /// Switches between unread, read and archived bookmarks.
+    pub fn cycle_shown(&mut self, bookmarks: &[Bookmark]) {
+        self.shown = match self.shown {
+            ReadingStatus::Unread => ReadingStatus::Read,
+            ReadingStatus::Read => ReadingStatus::Archived,
+            ReadingStatus::Archived => ReadingStatus::Unread,
+        };
+        self.items.unselect();
+        self.set_items(bookmarks);
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct CreationParams {
     pub active_window: ActiveWindow,
     pub title: Input,
     pub link: Input,
+    /// The suggested tags go first, followed by all the others.
     pub tags_items: StatefulList<Tag>,
+    /// Amount of the suggested tags at the head of `tags_items`.
+    pub suggested: usize,
+    /// Name of the tag to look for or to create.
+    pub tag_input: Input,
     pub selected_tags: Vec<Tag>,
+    /// All the tags of the library.
+    pub tags: Vec<Tag>,
+    pub tag_stats: TagStats,
+    pub known_urls: HashSet<String>,
+    pub validation: LinkValidation,
+    /// Metadata of the page, along with the link it was fetched for.
+    pub metadata: Option<(Url, PageMetadata)>,
+}
+
+impl CreationParams {
+    pub fn validate(&mut self) {
+        self.validation = validate_link(self.link.value(), &self.known_urls);
+
+        if let Some(canonical_url) = self.metadata().and_then(|m| m.canonical_url.as_ref()) {
+            if self.validation.url.as_ref() != Some(canonical_url)
+                && self.known_urls.contains(&canonical_url_of(canonical_url))
+            {
+                self.validation.notes.push((
+                    Level::Warning,
+                    format!("The canonical page {canonical_url} is already bookmarked"),
+                ));
+            }
+        }
+    }
+
+    /// Puts the tags that fit the typed link and title best on top of the list,
+    /// or lists the tags that complete the typed name if there's one.
+    pub fn suggest_tags(&mut self) {
+        let typed = self.tag_input.value().trim().to_lowercase();
+        if !typed.is_empty() {
+            let mut items: Vec<Tag> = self
+                .tags
+                .iter()
+                .filter(|tag| tag.name.to_lowercase().contains(&typed))
+                .cloned()
+                .collect();
+            // the names that start with the typed text go first
+            items.sort_by_key(|tag| !tag.name.to_lowercase().starts_with(&typed));
+            self.suggested = 0;
+            self.tags_items.replace_items(items);
+            return;
+        }
+
+        let suggestions = suggest(
+            &self.tag_stats,
+            &self.tags,
+            self.validation.url.as_ref(),
+            self.title.value(),
+            &self.selected_tags,
+        );
+        let suggested_ids: HashSet<i64> = suggestions.iter().map(|tag| tag.id).collect();
+
+        self.suggested = suggestions.len();
+        let mut items = suggestions;
+        items.extend(
+            self.tags
+                .iter()
+                .filter(|tag| !suggested_ids.contains(&tag.id))
+                .cloned(),
+        );
+        self.tags_items.replace_items(items);
+    }
+
+    /// Adds the suggested tag by its number, starting from 1.
+    pub fn pick_suggestion(&mut self, number: usize) {
+        let Some(tag) = number
+            .checked_sub(1)
+            .filter(|index| *index < self.suggested)
+            .and_then(|index| self.tags_items.items.get(index))
+        else {
+            return;
+        };
+        self.selected_tags.push(tag.clone());
+        self.suggest_tags();
+    }
+
+    /// Selects the tag, or deselects it if it's been selected already.
+    pub fn toggle_tag(&mut self, tag: Tag) {
+        if let Some(index) = self
+            .selected_tags
+            .iter()
+            .position(|selected| selected.id == tag.id)
+        {
+            self.selected_tags.remove(index);
+        } else {
+            self.selected_tags.push(tag);
+        }
+        self.suggest_tags();
+    }
+
+    /// Selects the tag that has just been created or found by the typed name.
+    pub fn add_typed_tag(&mut self, tag: Tag) {
+        if !self.tags.iter().any(|known| known.id == tag.id) {
+            self.tags.push(tag.clone());
+        }
+        if !self
+            .selected_tags
+            .iter()
+            .any(|selected| selected.id == tag.id)
+        {
+            self.selected_tags.push(tag);
+        }
+        self.tag_input.reset();
+        self.tags_items.unselect();
+        self.suggest_tags();
+    }
+
+    /// Metadata of the page the link currently points to, if it has been fetched.
+    pub fn metadata(&self) -> Option<&PageMetadata> {
+        match (&self.metadata, &self.validation.url) {
+            (Some((fetched_for, metadata)), Some(url)) if fetched_for == url => Some(metadata),
+            _ => None,
+        }
+    }
+
+    /// Fills the empty title in with the one from the page.
+    pub fn apply_metadata(&mut self, url: Url, metadata: PageMetadata) {
+        if self.title.value().trim().is_empty() {
+            if let Some(title) = metadata.best_title() {
+                self.title = Input::new(title.to_string());
+            }
+        }
+        self.metadata = Some((url, metadata));
+        self.validate();
+        self.suggest_tags();
+    }
+
+    /// Replaces the typed link with the one that is going to be saved, e.g. with the added scheme.
+    pub fn complete_link(&mut self) {
+        if let Some(url) = &self.validation.url {
+            if url.as_str() != self.link.value() {
+                self.link = Input::new(url.to_string());
+            }
+        }
+    }
+}
+
+/// What the listed search results have been found by.
+#[derive(Debug, Clone)]
+pub enum ResultsQuery {
+    Titles { query: String, in_content: bool },
+    Links(String),
 }
 
 #[derive(Debug, Default)]
@@ -298,9 +1415,52 @@ pub struct SearchingParams {
     pub title: Input,
     pub link: Input,
     pub tags: Option<Vec<Tag>>,
+    /// Whether the title search looks into the text of the pages as well.
+    pub in_content: bool,
+    /// Matches in the page texts by the ids of the found bookmarks.
+    pub snippets: HashMap<i64, Snippet>,
+    /// How far back in the search history the search in the inputs is, 0 is the latest one.
+    pub recalled: Option<usize>,
+    /// What has been typed before the history was recalled.
+    pub draft: Option<SearchHistoryEntry>,
+    /// Looking for a query through the search history, started with Ctrl+R.
+    pub reverse_search: Option<ReverseSearch>,
 }
 
-impl Clone for StatefulList<Tag> {
+impl SearchingParams {
+    /// Edited queries aren't the recalled search anymore, the history is recalled from the latest one again.
+    pub fn forget_recalled(&mut self) {
+        self.recalled = None;
+        self.draft = None;
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ReverseSearch {
+    pub query: Input,
+    /// Index of the latest matching search in the history.
+    pub matched: Option<usize>,
+}
+
+impl ReverseSearch {
+    /// Looks for the query from the latest search, or further back from the match if it's the older one.
+    /// The match stays when there's nothing older.
+    pub fn find(&mut self, history: &[SearchHistoryEntry], older: bool) {
+        let query = self.query.value();
+        let until = match (older, self.matched) {
+            (true, Some(matched)) => matched,
+            _ => history.len(),
+        };
+        let found = history
+            .get(..until)
+            .and_then(|entries| entries.iter().rposition(|entry| entry.matches(query)));
+        if found.is_some() || !older {
+            self.matched = found;
+        }
+    }
+}
+
+impl<T: Clone> Clone for StatefulList<T> {
     fn clone(&self) -> Self {
         Self {
             state: self.state.clone(),
@@ -309,7 +1469,7 @@ impl Clone for StatefulList<Tag> {
     }
 }
 
-impl Default for StatefulList<Tag> {
+impl<T> Default for StatefulList<T> {
     fn default() -> Self {
         Self {
             state: ListState::default(),
@@ -318,7 +1478,7 @@ impl Default for StatefulList<Tag> {
     }
 }
 
-impl Debug for StatefulList<Tag> {
+impl<T: Debug> Debug for StatefulList<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("StatefulList")
             .field("state", &self.state)
@@ -327,6 +1487,34 @@ impl Debug for StatefulList<Tag> {
     }
 }
 
+/// Screen areas of the last rendered frame, used to resolve mouse events.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Areas {
+    /// The whole panel above the search inputs.
+    pub results: Rect,
+    /// The bookmarks table, along with its header.
+    pub bookmarks: Rect,
+    /// The section of the pinned bookmarks, empty if there are none.
+    pub pinned: Rect,
+    pub title_search: Rect,
+    pub link_search: Rect,
+    pub tags: Rect,
+    pub selected_tags: Rect,
+    /// The collections sidebar, empty if it's hidden.
+    pub collections: Rect,
+    pub creation: Option<CreationAreas>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CreationAreas {
+    pub popup: Rect,
+    pub title: Rect,
+    pub link: Rect,
+    pub tag_input: Rect,
+    pub tags: Rect,
+    pub selected_tags: Rect,
+}
+
 #[derive(Debug, Default, Clone)]
 pub enum ActiveWindow {
     #[default]
@@ -387,4 +1575,15 @@ impl<T> StatefulList<T> {
     pub fn unselect(&mut self) {
         self.state.select(None);
     }
+
+    /// Replaces the items, keeping the cursor at the same position where possible.
+    pub fn replace_items(&mut self, items: Vec<T>) {
+        let selected = self
+            .state
+            .selected()
+            .map(|index| index.min(items.len().saturating_sub(1)))
+            .filter(|_| !items.is_empty());
+        self.items = items;
+        self.state.select(selected);
+    }
 }
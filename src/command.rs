@@ -0,0 +1,852 @@
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+};
+
+use anyhow::{anyhow, Result};
+use futures::{stream, StreamExt};
+use tokio::{sync::mpsc::UnboundedSender, task::JoinHandle};
+use url::Url;
+
+use crate::{
+    adapters::{
+        archiver::{archive_page, open_archive, unpack},
+        browser, clipboard,
+        http::Fetcher,
+        linkcheck::check_links,
+        metadata::fetch_metadata,
+        readability::extract_text,
+        sqlite::Repo,
+        tagpacker,
+    },
+    app::BookmarkDetails,
+    event::Event,
+    message::Message,
+    models::{
+        archives::Archive,
+        bookmarks::{Bookmark, NewBookmarkParams, ReadingStatus},
+        collections::{self, Collection, NewCollectionParams},
+        link_checks::LinkCheck,
+        packs::{NewPackParams, Pack},
+        page_texts::PageText,
+        saved_searches::{NewSavedSearchParams, SavedSearch},
+        search_history::SearchHistoryEntry,
+        tags::{merge_candidates, NewTagParams, Tag},
+    },
+    settings::Settings,
+    suggestions::TagStats,
+    tagging::{self, load_rules, tags_for, TagChange},
+    tasks::Progress,
+    validation::canonical_url,
+};
+
+// Amount of bookmarks saved per transaction while syncing
+const SYNC_CHUNK_SIZE: usize = 50;
+// Amount of pages downloaded at the same time while indexing
+const INDEX_CONCURRENCY: usize = 4;
+// Amount of bookmarks loaded per query while refreshing
+const REFRESH_PAGE_SIZE: i64 = 1000;
+
+/// Side effects requested by [`crate::update::update`].
+#[derive(Debug)]
+pub enum Command {
+    OpenUrl(String),
+    /// Puts the text into the clipboard, `what` names it in the notification.
+    Copy {
+        text: String,
+        what: String,
+    },
+    /// Reads the clipboard to paste it into the link of the new bookmark.
+    Paste,
+    /// Remembers when the bookmark has been opened.
+    MarkOpened(i64),
+    Refresh {
+        tags_filter: Vec<Tag>,
+        /// Only the bookmarks of the collection and the nested ones are loaded.
+        collection_filter: Option<i64>,
+    },
+    CreateBookmark {
+        title: Option<String>,
+        url: Url,
+        tags: Vec<Tag>,
+        description: Option<String>,
+    },
+    DeleteBookmark(i64),
+    SetReadingStatus {
+        ids: Vec<i64>,
+        status: ReadingStatus,
+    },
+    SetPinned {
+        ids: Vec<i64>,
+        pinned: bool,
+    },
+    /// Points the bookmark to another link, e.g. where the old one redirects to.
+    RetargetBookmark {
+        id: i64,
+        url: Url,
+    },
+    /// Loads canonical forms of the saved links to warn about duplicates.
+    LoadKnownUrls,
+    /// Loads how the tags are used, to suggest them for a new bookmark.
+    LoadTagStats,
+    /// Loads the last check and the archived copy of the bookmark.
+    LoadDetails(i64),
+    CreateTag(String),
+    FetchMetadata {
+        task_id: u64,
+        url: Url,
+    },
+    Sync {
+        task_id: u64,
+    },
+    /// Checks the given bookmarks or all of them if there are no ids.
+    CheckLinks {
+        task_id: u64,
+        ids: Option<Vec<i64>>,
+    },
+    LoadBrokenLinks,
+    /// Downloads the pages of the bookmarks and keeps them in the database.
+    ArchiveBookmarks {
+        task_id: u64,
+        ids: Vec<i64>,
+    },
+    OpenArchive(i64),
+    /// Extracts the text of the pages that haven't been indexed yet.
+    IndexPages {
+        task_id: u64,
+    },
+    LoadPageTexts,
+    /// Finds the tags the rules would add to the library.
+    PreviewTagRules,
+    ApplyTagChanges(Vec<TagChange>),
+    /// Looks for the near-duplicate tags.
+    LoadTagMerges,
+    MergeTags {
+        from: Tag,
+        into: Tag,
+    },
+    CreatePack(NewPackParams),
+    /// Saves the new name and color of the pack.
+    UpdatePack(Pack),
+    DeletePack(i64),
+    /// Moves the tag into the pack, or out of any pack.
+    AssignPack {
+        tag: Tag,
+        pack: Option<Pack>,
+    },
+    CreateCollection(NewCollectionParams),
+    RenameCollection {
+        id: i64,
+        name: String,
+    },
+    DeleteCollection(i64),
+    /// Puts the sibling collections in the given order.
+    ReorderCollections(Vec<i64>),
+    /// Moves the bookmarks into the collection, or out of any collection.
+    MoveBookmarks {
+        ids: Vec<i64>,
+        collection: Option<Collection>,
+    },
+    LoadSavedSearches,
+    SaveSearch(NewSavedSearchParams),
+    RenameSavedSearch {
+        id: i64,
+        name: String,
+    },
+    DeleteSavedSearch(i64),
+    LoadSearchHistory,
+    /// Adds the submitted search to the history.
+    RememberSearch(SearchHistoryEntry),
+    Cancel(Vec<u64>),
+}
+
+/// Performs the commands against the database and the network.
+///
+/// Every command runs in its own tokio task, the outcome is posted back
+/// into the main loop as a [`Message`].
+pub struct Executor {
+    repo: Repo,
+    fetcher: Fetcher,
+    sender: UnboundedSender<Event>,
+    tasks: HashMap<u64, JoinHandle<()>>,
+    /// The refresh that is still loading pages, a newer one replaces it.
+    refresh: Option<JoinHandle<()>>,
+}
+
+impl Executor {
+    pub fn new(repo: Repo, fetcher: Fetcher, sender: UnboundedSender<Event>) -> Self {
+        Self {
+            repo,
+            fetcher,
+            sender,
+            tasks: HashMap::new(),
+            refresh: None,
+        }
+    }
+
+    pub fn execute(&mut self, command: Command) {
+        let repo = self.repo.clone();
+
+        match command {
+            Command::OpenUrl(url) => {
+                if let Err(error) = open::that_detached(url) {
+                    self.post(Message::Failed(format!("{error:#}")));
+                }
+            }
+            Command::Copy { text, what } => {
+                // it's run between the frames, so the sequence doesn't break into one
+                if let Err(error) = clipboard::copy_to_terminal(&text) {
+                    self.post(Message::Failed(format!("{error:#}")));
+                }
+                self.spawn(async move {
+                    tokio::task::spawn_blocking(move || clipboard::copy_with_program(&text))
+                        .await??;
+                    Ok(Message::Copied(what))
+                });
+            }
+            Command::Paste => {
+                self.spawn(async move {
+                    let text = tokio::task::spawn_blocking(clipboard::paste).await??;
+                    Ok(Message::Pasted(text))
+                });
+            }
+            Command::MarkOpened(id) => {
+                self.spawn(async move {
+                    let at = Bookmark::mark_opened(&repo, id).await?;
+                    Ok(Message::BookmarkOpened { id, at })
+                });
+            }
+            Command::Refresh {
+                tags_filter,
+                collection_filter,
+            } => {
+                // the pages the older refresh has sent are queued before the new library
+                if let Some(refresh) = self.refresh.take() {
+                    refresh.abort();
+                }
+                let sender = self.sender.clone();
+                self.refresh = Some(self.spawn(async move {
+                    let tags = Tag::fetch_all(&repo).await?;
+                    let pinned = Bookmark::fetch_pinned(&repo).await?;
+                    let packs = Pack::fetch_all(&repo).await?;
+                    let collections = Collection::fetch_all(&repo).await?;
+                    let archived = Archive::fetch_ids(&repo).await?;
+                    // a collection holds the bookmarks of the nested ones as well
+                    let collection_ids = collection_filter
+                        .map(|id| collections::with_descendants(&collections, [id]));
+                    let in_collection = |bookmark: &Bookmark| {
+                        collection_ids.as_ref().map_or(true, |ids| {
+                            bookmark
+                                .collection_id
+                                .is_some_and(|collection_id| ids.contains(&collection_id))
+                        })
+                    };
+
+                    let mut bookmarks =
+                        Bookmark::fetch_page(&repo, tags_filter.clone(), None, REFRESH_PAGE_SIZE)
+                            .await?;
+                    let mut last = bookmarks.last().map(|bookmark| bookmark.id);
+                    let mut is_full = bookmarks.len() as i64 == REFRESH_PAGE_SIZE;
+                    bookmarks.retain(in_collection);
+                    let mut message = Message::Refreshed {
+                        bookmarks,
+                        pinned,
+                        tags,
+                        packs,
+                        collections,
+                        archived,
+                    };
+
+                    while is_full {
+                        let _ = sender.send(Event::Message(Box::new(message)));
+                        let mut page = Bookmark::fetch_page(
+                            &repo,
+                            tags_filter.clone(),
+                            last,
+                            REFRESH_PAGE_SIZE,
+                        )
+                        .await?;
+                        last = page.last().map(|bookmark| bookmark.id);
+                        is_full = page.len() as i64 == REFRESH_PAGE_SIZE;
+                        page.retain(in_collection);
+                        message = Message::BookmarksLoaded(page);
+                    }
+                    Ok(message)
+                }));
+            }
+            Command::CreateBookmark {
+                title,
+                url,
+                tags,
+                description,
+            } => {
+                self.spawn(async move {
+                    // broken rules are reported by the preview, they don't stop saving
+                    let rules = load_rules().unwrap_or_default();
+                    let auto_tags: Vec<String> = tags_for(&rules, title.as_deref(), &url)
+                        .into_iter()
+                        .filter(|name| !tags.iter().any(|tag| tag.name.eq_ignore_ascii_case(name)))
+                        .collect();
+
+                    let bookmark =
+                        Bookmark::create(&repo, title, url, Some(tags), description).await?;
+                    if !auto_tags.is_empty() {
+                        Bookmark::add_tags(&repo, bookmark.id, auto_tags.clone()).await?;
+                    }
+                    Ok(Message::BookmarkCreated {
+                        bookmark,
+                        auto_tags,
+                    })
+                });
+            }
+            Command::DeleteBookmark(id) => {
+                self.spawn(async move {
+                    Bookmark::delete(&repo, id).await?;
+                    Ok(Message::BookmarkDeleted(id))
+                });
+            }
+            Command::SetReadingStatus { ids, status } => {
+                self.spawn(async move {
+                    let count = ids.len();
+                    Bookmark::update_status(&repo, ids, status).await?;
+                    Ok(Message::ReadingStatusChanged { count, status })
+                });
+            }
+            Command::SetPinned { ids, pinned } => {
+                self.spawn(async move {
+                    let count = ids.len();
+                    Bookmark::set_pinned(&repo, ids, pinned).await?;
+                    Ok(Message::PinnedChanged { count, pinned })
+                });
+            }
+            Command::RetargetBookmark { id, url } => {
+                self.spawn(async move {
+                    Bookmark::update_url(&repo, id, url).await?;
+                    // the old check is about the old link
+                    LinkCheck::delete(&repo, id).await?;
+                    Ok(Message::BookmarkRetargeted(id))
+                });
+            }
+            Command::LoadKnownUrls => {
+                self.spawn(async move {
+                    let urls = Bookmark::fetch_urls(&repo).await?;
+                    Ok(Message::KnownUrls(urls.iter().map(canonical_url).collect()))
+                });
+            }
+            Command::CreateTag(name) => {
+                self.spawn(async move {
+                    let tag = Tag::create(&repo, name).await?;
+                    Ok(Message::TagCreated(tag))
+                });
+            }
+            Command::LoadTagStats => {
+                self.spawn(async move {
+                    let bookmarks = Bookmark::fetch_all(&repo, vec![]).await?;
+                    Ok(Message::TagStatsLoaded(Box::new(TagStats::new(&bookmarks))))
+                });
+            }
+            Command::LoadDetails(bookmark_id) => {
+                self.spawn(async move {
+                    let check = LinkCheck::get(&repo, bookmark_id).await?;
+                    let archive = Archive::get(&repo, bookmark_id).await?;
+                    Ok(Message::DetailsLoaded(Box::new(BookmarkDetails {
+                        bookmark_id,
+                        check,
+                        archived_at: archive.as_ref().map(|archive| archive.archived_at),
+                        archive_size: archive.map(|archive| archive.size),
+                    })))
+                });
+            }
+            Command::FetchMetadata { task_id, url } => {
+                let fetcher = self.fetcher.clone();
+                self.spawn_task(task_id, async move {
+                    let metadata = fetch_metadata(&fetcher, &url).await?;
+                    Ok(Message::MetadataFetched {
+                        url,
+                        metadata: Box::new(metadata),
+                    })
+                });
+            }
+            Command::Sync { task_id } => {
+                let progress = Progress::new(task_id, self.sender.clone());
+                self.spawn_task(task_id, sync(repo, progress));
+            }
+            Command::CheckLinks { task_id, ids } => {
+                let fetcher = self.fetcher.clone();
+                let progress = Progress::new(task_id, self.sender.clone());
+                self.spawn_task(task_id, check(repo, fetcher, ids, progress));
+            }
+            Command::LoadBrokenLinks => {
+                self.spawn(async move {
+                    let broken_links = LinkCheck::fetch_broken(&repo).await?;
+                    Ok(Message::BrokenLinksLoaded(broken_links))
+                });
+            }
+            Command::ArchiveBookmarks { task_id, ids } => {
+                let fetcher = self.fetcher.clone();
+                let progress = Progress::new(task_id, self.sender.clone());
+                self.spawn_task(task_id, archive(repo, fetcher, ids, progress));
+            }
+            Command::OpenArchive(id) => {
+                self.spawn(async move {
+                    let archive = Archive::get(&repo, id)
+                        .await?
+                        .ok_or_else(|| anyhow!("The page hasn't been archived yet"))?;
+                    open_archive(&archive)?;
+                    Ok(Message::ArchiveOpened {
+                        url: archive.url,
+                        archived_at: archive.archived_at,
+                    })
+                });
+            }
+            Command::IndexPages { task_id } => {
+                let fetcher = self.fetcher.clone();
+                let progress = Progress::new(task_id, self.sender.clone());
+                self.spawn_task(task_id, index(repo, fetcher, progress));
+            }
+            Command::LoadPageTexts => {
+                self.spawn(async move {
+                    let texts = PageText::fetch_all(&repo).await?;
+                    Ok(Message::PageTextsLoaded(texts))
+                });
+            }
+            Command::PreviewTagRules => {
+                self.spawn(async move {
+                    let mut rules = load_rules()?;
+                    // the rules may name a tag by its alias or in another case
+                    for rule in &mut rules {
+                        if let Ok(tag) = Tag::get_by_name(&repo, rule.tag.clone()).await {
+                            rule.tag = tag.name;
+                        }
+                    }
+                    let bookmarks = Bookmark::fetch_all(&repo, vec![]).await?;
+                    Ok(Message::TagRulesPreviewed {
+                        rules: rules.iter().map(tagging::Rule::describe).collect(),
+                        changes: tagging::preview(&rules, &bookmarks),
+                    })
+                });
+            }
+            Command::ApplyTagChanges(changes) => {
+                self.spawn(async move {
+                    let mut tags_by_bookmark: HashMap<i64, Vec<String>> = HashMap::new();
+                    for change in &changes {
+                        tags_by_bookmark
+                            .entry(change.bookmark_id)
+                            .or_default()
+                            .push(change.tag.clone());
+                    }
+                    let bookmarks = tags_by_bookmark.len();
+                    for (id, tags) in tags_by_bookmark {
+                        Bookmark::add_tags(&repo, id, tags).await?;
+                    }
+                    Ok(Message::TagChangesApplied {
+                        bookmarks,
+                        tags: changes.len(),
+                    })
+                });
+            }
+            Command::LoadTagMerges => {
+                self.spawn(async move {
+                    let tags = Tag::fetch_all(&repo).await?;
+                    let mut usage = HashMap::new();
+                    for bookmark in Bookmark::fetch_all(&repo, vec![]).await? {
+                        for tag in bookmark.tags.unwrap_or_default() {
+                            *usage.entry(tag.id).or_insert(0) += 1;
+                        }
+                    }
+                    Ok(Message::TagMergesLoaded(merge_candidates(&tags, &usage)))
+                });
+            }
+            Command::MergeTags { from, into } => {
+                self.spawn(async move {
+                    Tag::merge(&repo, from.id, into.id).await?;
+                    Ok(Message::TagsMerged { from, into })
+                });
+            }
+            Command::CreatePack(params) => {
+                self.spawn(async move {
+                    let pack = Pack::create(&repo, params).await?;
+                    Ok(Message::PackSaved(pack))
+                });
+            }
+            Command::UpdatePack(pack) => {
+                self.spawn(async move {
+                    Pack::update(&repo, pack.clone()).await?;
+                    Ok(Message::PackSaved(pack))
+                });
+            }
+            Command::DeletePack(id) => {
+                self.spawn(async move {
+                    Pack::delete(&repo, id).await?;
+                    Ok(Message::PackDeleted(id))
+                });
+            }
+            Command::AssignPack { tag, pack } => {
+                self.spawn(async move {
+                    Pack::assign(&repo, tag.id, pack.as_ref().map(|pack| pack.id)).await?;
+                    Ok(Message::PackAssigned {
+                        tag: tag.name,
+                        pack: pack.map(|pack| pack.name),
+                    })
+                });
+            }
+            Command::CreateCollection(params) => {
+                self.spawn(async move {
+                    let collection = Collection::create(&repo, params).await?;
+                    Ok(Message::CollectionSaved(collection.name))
+                });
+            }
+            Command::RenameCollection { id, name } => {
+                self.spawn(async move {
+                    Collection::rename(&repo, id, name.clone()).await?;
+                    Ok(Message::CollectionSaved(name))
+                });
+            }
+            Command::DeleteCollection(id) => {
+                self.spawn(async move {
+                    Collection::delete(&repo, id).await?;
+                    Ok(Message::CollectionDeleted(id))
+                });
+            }
+            Command::ReorderCollections(ids) => {
+                self.spawn(async move {
+                    Collection::reorder(&repo, ids).await?;
+                    Ok(Message::CollectionsReordered)
+                });
+            }
+            Command::MoveBookmarks { ids, collection } => {
+                self.spawn(async move {
+                    let count = ids.len();
+                    Collection::move_bookmarks(&repo, ids, collection.as_ref().map(|c| c.id))
+                        .await?;
+                    Ok(Message::BookmarksMoved {
+                        count,
+                        collection: collection.map(|collection| collection.name),
+                    })
+                });
+            }
+            Command::LoadSavedSearches => {
+                self.spawn(async move {
+                    let searches = SavedSearch::fetch_all(&repo).await?;
+                    Ok(Message::SavedSearchesLoaded(searches))
+                });
+            }
+            Command::SaveSearch(params) => {
+                self.spawn(async move {
+                    let search = SavedSearch::create(&repo, params).await?;
+                    Ok(Message::SearchSaved(search.name))
+                });
+            }
+            Command::RenameSavedSearch { id, name } => {
+                self.spawn(async move {
+                    SavedSearch::rename(&repo, id, name.clone()).await?;
+                    Ok(Message::SearchSaved(name))
+                });
+            }
+            Command::DeleteSavedSearch(id) => {
+                self.spawn(async move {
+                    SavedSearch::delete(&repo, id).await?;
+                    Ok(Message::SavedSearchDeleted(id))
+                });
+            }
+            Command::LoadSearchHistory => {
+                self.spawn(async move {
+                    let history = SearchHistoryEntry::fetch_all(&repo).await?;
+                    Ok(Message::SearchHistoryLoaded(history))
+                });
+            }
+            Command::RememberSearch(entry) => {
+                self.spawn(async move {
+                    SearchHistoryEntry::remember(&repo, entry).await?;
+                    let history = SearchHistoryEntry::fetch_all(&repo).await?;
+                    Ok(Message::SearchHistoryLoaded(history))
+                });
+            }
+            Command::Cancel(ids) => {
+                for id in ids {
+                    if let Some(handle) = self.tasks.remove(&id) {
+                        handle.abort();
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn cancel_all(&mut self) {
+        for (_, handle) in self.tasks.drain() {
+            handle.abort();
+        }
+    }
+
+    fn post(&self, message: Message) {
+        // the main loop is gone if sending fails, nobody is interested in the result
+        let _ = self.sender.send(Event::Message(Box::new(message)));
+    }
+
+    fn spawn<F>(&self, job: F) -> JoinHandle<()>
+    where
+        F: Future<Output = Result<Message>> + Send + 'static,
+    {
+        let sender = self.sender.clone();
+        tokio::spawn(async move {
+            let message = job
+                .await
+                .unwrap_or_else(|error| Message::Failed(format!("{error:#}")));
+            let _ = sender.send(Event::Message(Box::new(message)));
+        })
+    }
+
+    /// Spawns a cancellable job, its outcome is wrapped into [`Message::TaskFinished`].
+    fn spawn_task<F>(&mut self, id: u64, job: F)
+    where
+        F: Future<Output = Result<Message>> + Send + 'static,
+    {
+        self.tasks.retain(|_, handle| !handle.is_finished());
+
+        let handle = self.spawn(async move {
+            let outcome = job
+                .await
+                .unwrap_or_else(|error| Message::Failed(format!("{error:#}")));
+            Ok(Message::TaskFinished {
+                id,
+                outcome: Box::new(outcome),
+            })
+        });
+        self.tasks.insert(id, handle);
+    }
+}
+
+async fn sync(repo: Repo, progress: Progress) -> Result<Message> {
+    let bookmarks_file = Settings::get_configuration()?.browser.bookmarks_file;
+    // one source being down doesn't keep the other one from importing
+    let (tagpacker_links, browser_links) = tokio::join!(tagpacker::get_links(), async {
+        match &bookmarks_file {
+            Some(path) => browser::get_links(path).await,
+            None => Ok(vec![]),
+        }
+    });
+    let mut imported_links = vec![];
+    let mut failures = vec![];
+    for (source, links) in [("Tagpacker", tagpacker_links), ("Browser", browser_links)] {
+        match links {
+            Ok(links) => imported_links.extend(links),
+            Err(error) => failures.push(format!("{source}: {error:#}")),
+        }
+    }
+    // broken rules are reported by the preview, they don't stop importing
+    let rules = load_rules().unwrap_or_default();
+    for link in &mut imported_links {
+        // the names the link has already, in another case or as aliases, are dropped when it's saved
+        let names = tags_for(&rules, link.title.as_deref(), &link.url);
+        link.tags.extend(names.into_iter().map(NewTagParams::from));
+    }
+    let count = import(&repo, imported_links, &progress).await?;
+
+    Ok(Message::Synced { count, failures })
+}
+
+/// Saves the links that aren't in the library yet, returns how many of them there were.
+async fn import(repo: &Repo, links: Vec<NewBookmarkParams>, progress: &Progress) -> Result<usize> {
+    // the sources are imported whole every time, and may share the links
+    let mut known: HashSet<String> = Bookmark::fetch_urls(repo)
+        .await?
+        .iter()
+        .map(canonical_url)
+        .collect();
+    let new_links: Vec<_> = links
+        .into_iter()
+        .filter(|link| known.insert(canonical_url(&link.url)))
+        .collect();
+    let total = new_links.len();
+    progress.report(0, total);
+
+    // save by chunks, so the progress is visible and cancelling keeps what's been saved
+    let mut links = new_links.into_iter();
+    let mut done = 0;
+    loop {
+        let chunk: Vec<_> = links.by_ref().take(SYNC_CHUNK_SIZE).collect();
+        if chunk.is_empty() {
+            break;
+        }
+        done += chunk.len();
+        Bookmark::batch_create(repo, chunk).await?;
+        progress.report(done, total);
+    }
+
+    Ok(total)
+}
+
+async fn check(
+    repo: Repo,
+    fetcher: Fetcher,
+    ids: Option<Vec<i64>>,
+    progress: Progress,
+) -> Result<Message> {
+    let settings = Settings::get_configuration()
+        .map(|settings| settings.link_checks)
+        .unwrap_or_default();
+
+    let links: Vec<_> = Bookmark::fetch_all(&repo, vec![])
+        .await?
+        .into_iter()
+        .filter(|bookmark| ids.as_ref().map_or(true, |ids| ids.contains(&bookmark.id)))
+        .map(|bookmark| (bookmark.id, bookmark.url.inner))
+        .collect();
+    let total = links.len();
+    progress.report(0, total);
+
+    // save the checks as they come, so cancelling keeps what's been checked
+    let mut checks = std::pin::pin!(check_links(fetcher, links, &settings));
+    let (mut done, mut broken) = (0, 0);
+    while let Some(check) = checks.next().await {
+        if check.is_broken() {
+            broken += 1;
+        }
+        LinkCheck::save(&repo, &check).await?;
+        done += 1;
+        progress.report(done, total);
+    }
+
+    Ok(Message::LinksChecked {
+        checked: total,
+        broken,
+    })
+}
+
+async fn archive(
+    repo: Repo,
+    fetcher: Fetcher,
+    ids: Vec<i64>,
+    progress: Progress,
+) -> Result<Message> {
+    let settings = Settings::get_configuration()
+        .map(|settings| settings.archives)
+        .unwrap_or_default();
+
+    let bookmarks: Vec<_> = Bookmark::fetch_all(&repo, vec![])
+        .await?
+        .into_iter()
+        .filter(|bookmark| ids.contains(&bookmark.id))
+        .collect();
+    let total = bookmarks.len();
+    progress.report(0, total);
+
+    // one page at a time, the resources of every page are downloaded concurrently anyway
+    let mut archived = 0;
+    let mut failed = Vec::new();
+    for (done, bookmark) in bookmarks.into_iter().enumerate() {
+        let url = bookmark.url.inner;
+        match archive_page(&fetcher, bookmark.id, &url, &settings).await {
+            Ok(archive) => {
+                Archive::save(&repo, &archive).await?;
+                let text = extract_text(&unpack(&archive)?);
+                PageText::save(&repo, &PageText::new(bookmark.id, text)).await?;
+                archived += 1;
+            }
+            Err(error) => failed.push(format!("{url}: {error:#}")),
+        }
+        progress.report(done + 1, total);
+    }
+
+    Ok(Message::Archived { archived, failed })
+}
+
+async fn index(repo: Repo, fetcher: Fetcher, progress: Progress) -> Result<Message> {
+    let indexed_ids = PageText::fetch_ids(&repo).await?;
+    let bookmarks: Vec<_> = Bookmark::fetch_all(&repo, vec![])
+        .await?
+        .into_iter()
+        .filter(|bookmark| !indexed_ids.contains(&bookmark.id))
+        .collect();
+    let total = bookmarks.len();
+    progress.report(0, total);
+
+    let mut texts = std::pin::pin!(stream::iter(bookmarks)
+        .map(|bookmark| page_text(&repo, &fetcher, bookmark))
+        .buffer_unordered(INDEX_CONCURRENCY));
+    let (mut done, mut indexed) = (0, 0);
+    let mut failed = Vec::new();
+    while let Some((url, text)) = texts.next().await {
+        match text {
+            Ok(text) => {
+                PageText::save(&repo, &text).await?;
+                indexed += 1;
+            }
+            Err(error) => failed.push(format!("{url}: {error:#}")),
+        }
+        done += 1;
+        progress.report(done, total);
+    }
+
+    Ok(Message::PagesIndexed { indexed, failed })
+}
+
+/// Text of the bookmarked page, the archived copy is used if there's one.
+async fn page_text(repo: &Repo, fetcher: &Fetcher, bookmark: Bookmark) -> (Url, Result<PageText>) {
+    let url = bookmark.url.inner;
+    let html = async {
+        match Archive::get(repo, bookmark.id).await? {
+            Some(archive) => unpack(&archive),
+            None => Ok(fetcher.fetch_html(&url).await?.html),
+        }
+    };
+    let text = html
+        .await
+        .map(|html| PageText::new(bookmark.id, extract_text(&html)));
+
+    (url, text)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use tokio::sync::mpsc::unbounded_channel;
+
+    use super::*;
+
+    const EXPORT: &str = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<DL><p>
+    <DT><A HREF="https://doc.rust-lang.org/book/">The Rust book</A>
+    <DT><H3>Go</H3>
+    <DL><p>
+        <DT><A HREF="https://gobyexample.com/">Go by example</A>
+        <DT><A HREF="http://www.gobyexample.com/#top">Go by example again</A>
+    </DL><p>
+</DL>
+"#;
+
+    #[tokio::test]
+    async fn imports_the_same_file_once() {
+        let dir = env::temp_dir();
+        let db = dir.join(format!("fbmark-sync-{}.db", process::id()));
+        let export = dir.join(format!("fbmark-sync-{}.html", process::id()));
+        let _ = fs::remove_file(&db);
+        fs::write(&export, EXPORT).unwrap();
+        let repo = Repo::open(&format!("sqlite://{}", db.display()))
+            .await
+            .unwrap();
+        let (sender, _receiver) = unbounded_channel();
+        let progress = Progress::new(1, sender);
+
+        let links = browser::get_links(&export).await.unwrap();
+        assert_eq!(import(&repo, links, &progress).await.unwrap(), 2);
+        let links = browser::get_links(&export).await.unwrap();
+        assert_eq!(import(&repo, links, &progress).await.unwrap(), 0);
+        let _ = fs::remove_file(&export);
+
+        let urls: Vec<_> = Bookmark::fetch_urls(&repo)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://doc.rust-lang.org/book/",
+                "https://gobyexample.com/"
+            ]
+        );
+    }
+}
@@ -1,53 +1,55 @@
-use std::{
-    sync::mpsc,
-    thread,
-    time::{Duration, Instant},
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use crossterm::event::{Event as CrosstermEvent, EventStream, KeyEvent, KeyEventKind, MouseEvent};
+use futures::StreamExt;
+use tokio::{
+    sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+    task::JoinHandle,
 };
 
-use anyhow::Result;
-use crossterm::event::{self, Event as CrosstermEvent, KeyEvent, MouseEvent};
+use crate::message::Message;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Debug)]
 pub enum Event {
     Tick,
     Key(KeyEvent),
     Mouse(MouseEvent),
     Resize(u16, u16),
+    Message(Box<Message>),
 }
 
 #[derive(Debug)]
 pub struct EventHandler {
-    sender: mpsc::Sender<Event>,
-    receiver: mpsc::Receiver<Event>,
-    handler: thread::JoinHandle<()>,
+    sender: UnboundedSender<Event>,
+    receiver: UnboundedReceiver<Event>,
+    handler: JoinHandle<()>,
 }
 
 impl EventHandler {
     pub fn new(tick_rate: u64) -> Self {
         let tick_rate = Duration::from_millis(tick_rate);
-        let (sender, receiver) = mpsc::channel();
+        let (sender, receiver) = mpsc::unbounded_channel();
         let handler = {
             let sender = sender.clone();
-            thread::spawn(move || {
-                let mut last_tick = Instant::now();
+            tokio::spawn(async move {
+                let mut reader = EventStream::new();
+                let mut tick = tokio::time::interval(tick_rate);
                 loop {
-                    let timeout = tick_rate
-                        .checked_sub(last_tick.elapsed())
-                        .unwrap_or(tick_rate);
-
-                    if event::poll(timeout).expect("no events available") {
-                        match event::read().expect("unable to read event") {
-                            CrosstermEvent::Key(e) => sender.send(Event::Key(e)),
-                            CrosstermEvent::Mouse(e) => sender.send(Event::Mouse(e)),
-                            CrosstermEvent::Resize(w, h) => sender.send(Event::Resize(w, h)),
-                            _ => unimplemented!(),
-                        }
-                        .expect("failed to send terminal event");
-                    }
+                    let event = tokio::select! {
+                        _ = sender.closed() => break,
+                        _ = tick.tick() => Event::Tick,
+                        Some(Ok(event)) = reader.next() => match event {
+                            // crossterm reports key releases on some platforms, skip them
+                            CrosstermEvent::Key(e) if e.kind == KeyEventKind::Press => Event::Key(e),
+                            CrosstermEvent::Mouse(e) => Event::Mouse(e),
+                            CrosstermEvent::Resize(w, h) => Event::Resize(w, h),
+                            _ => continue,
+                        },
+                    };
 
-                    if last_tick.elapsed() >= tick_rate {
-                        sender.send(Event::Tick).expect("failed to send tick event");
-                        last_tick = Instant::now();
+                    if sender.send(event).is_err() {
+                        break;
                     }
                 }
             })
@@ -59,11 +61,23 @@ impl EventHandler {
         }
     }
 
-    /// Receive the next event from the handler thread.
+    /// A sender that the command executor uses to post messages into the main loop.
+    pub fn sender(&self) -> UnboundedSender<Event> {
+        self.sender.clone()
+    }
+
+    /// Receive the next event from the handler task.
     ///
-    /// This function will always block the current thread if
-    /// there is no data available and it's possible for more data to be sent.
-    pub fn next(&self) -> Result<Event> {
-        Ok(self.receiver.recv()?)
+    /// Waits without blocking the runtime if there is no event available yet.
+    pub async fn next(&mut self) -> Result<Event> {
+        self.receiver
+            .recv()
+            .await
+            .ok_or_else(|| anyhow!("event channel has been closed"))
+    }
+
+    /// Stops reading the terminal events.
+    pub fn stop(&self) {
+        self.handler.abort();
     }
 }
@@ -0,0 +1,171 @@
+use std::{collections::HashMap, ops::Range};
+
+// Bytes of the page text shown around the first match
+const SNIPPET_BEFORE: usize = 60;
+const SNIPPET_AFTER: usize = 140;
+const ELLIPSIS: &str = "…";
+
+/// A piece of the page text around the matched words.
+#[derive(Debug, Clone)]
+pub struct Snippet {
+    pub text: String,
+    /// Byte ranges of the matched words within `text`.
+    pub highlights: Vec<Range<usize>>,
+}
+
+/// Bookmarks whose page text contains every word of the query, case-insensitively.
+///
+/// The pages with more matches go first.
+pub fn search_texts(texts: &HashMap<i64, String>, query: &str) -> Vec<(i64, Snippet)> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| fold(term).text)
+        .collect();
+    if terms.is_empty() {
+        return vec![];
+    }
+
+    let mut found: Vec<(usize, i64, Snippet)> = texts
+        .iter()
+        .filter_map(|(id, text)| {
+            let matches = find_terms(text, &terms)?;
+            Some((matches.len(), *id, snippet(text, &matches)))
+        })
+        .collect();
+
+    found.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    found
+        .into_iter()
+        .map(|(_, id, snippet)| (id, snippet))
+        .collect()
+}
+
+/// Byte ranges of all the occurrences of the terms, `None` if any of them is missing.
+fn find_terms(text: &str, terms: &[String]) -> Option<Vec<Range<usize>>> {
+    let folded = fold(text);
+    let mut matches = Vec::new();
+    for term in terms {
+        let before = matches.len();
+        matches.extend(
+            folded
+                .text
+                .match_indices(term.as_str())
+                .map(|(start, _)| folded.origins[start]..folded.origins[start + term.len()]),
+        );
+        if matches.len() == before {
+            return None;
+        }
+    }
+    matches.sort_by_key(|range| range.start);
+
+    Some(matches)
+}
+
+/// Lowercased text along with the offsets of its bytes in the original one,
+/// lowercasing may change the length of a character.
+struct Folded {
+    text: String,
+    origins: Vec<usize>,
+}
+
+fn fold(text: &str) -> Folded {
+    let mut folded = String::with_capacity(text.len());
+    let mut origins = Vec::with_capacity(text.len() + 1);
+    for (index, c) in text.char_indices() {
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        origins.extend(std::iter::repeat(index).take(lower.len_utf8()));
+        folded.push(lower);
+    }
+    origins.push(text.len());
+
+    Folded {
+        text: folded,
+        origins,
+    }
+}
+
+fn snippet(text: &str, matches: &[Range<usize>]) -> Snippet {
+    let first = matches.first().map_or(0, |range| range.start);
+    let start = (0..=first.saturating_sub(SNIPPET_BEFORE))
+        .rev()
+        .find(|index| text.is_char_boundary(*index))
+        .unwrap_or(0);
+    let end = ((first + SNIPPET_AFTER).min(text.len())..=text.len())
+        .find(|index| text.is_char_boundary(*index))
+        .unwrap_or(text.len());
+
+    let prefix = if start > 0 { ELLIPSIS } else { "" };
+    let suffix = if end < text.len() { ELLIPSIS } else { "" };
+    let shift = |index: usize| index - start + prefix.len();
+    let highlights = matches
+        .iter()
+        .filter(|range| range.start >= start && range.start < end)
+        .map(|range| shift(range.start)..shift(range.end.min(end)))
+        .collect();
+
+    Snippet {
+        // the lines are blocks of the page, in a snippet they read better as one
+        text: format!("{prefix}{}{suffix}", text[start..end].replace('\n', " ")),
+        highlights,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search(text: &str, query: &str) -> Snippet {
+        let texts = HashMap::from([(1, text.to_string())]);
+        let mut found = search_texts(&texts, query);
+        assert_eq!(found.len(), 1, "{query} isn't found in {text}");
+        found.remove(0).1
+    }
+
+    fn highlighted(snippet: &Snippet) -> Vec<&str> {
+        snippet
+            .highlights
+            .iter()
+            .map(|range| &snippet.text[range.clone()])
+            .collect()
+    }
+
+    #[test]
+    fn maps_the_folded_bytes_back() {
+        // the lowercase is shorter
+        let folded = fold("İst");
+        assert_eq!(folded.text, "ist");
+        assert_eq!(folded.origins, vec![0, 2, 3, 4]);
+
+        // the lowercase is shorter by a byte still
+        let folded = fold("ẞa");
+        assert_eq!(folded.text, "ßa");
+        assert_eq!(folded.origins, vec![0, 0, 3, 4]);
+    }
+
+    #[test]
+    fn highlights_the_original_words() {
+        let snippet = search("Über die Straße", "STRAẞE über");
+        assert_eq!(snippet.text, "Über die Straße");
+        assert_eq!(highlighted(&snippet), vec!["Über", "Straße"]);
+
+        let snippet = search("Das İst gut", "İST");
+        assert_eq!(highlighted(&snippet), vec!["İst"]);
+    }
+
+    #[test]
+    fn cuts_the_snippet_between_the_characters() {
+        let text = format!("{}xNeedle{}", "ж".repeat(100), "ж".repeat(200));
+        let snippet = search(&text, "needle");
+
+        assert!(snippet.text.starts_with("…ж"), "{}", snippet.text);
+        assert!(snippet.text.ends_with("ж…"), "{}", snippet.text);
+        assert_eq!(highlighted(&snippet), vec!["Needle"]);
+    }
+
+    #[test]
+    fn needs_every_word() {
+        let texts = HashMap::from([(1, "Über die Straße".to_string())]);
+        assert!(search_texts(&texts, "straße auto").is_empty());
+        assert!(search_texts(&texts, "  ").is_empty());
+    }
+}
@@ -0,0 +1,17 @@
+pub mod adapters;
+pub mod app;
+pub mod command;
+pub mod event;
+pub mod fulltext;
+pub mod message;
+pub mod models;
+pub mod notifications;
+pub mod palette;
+pub mod settings;
+pub mod suggestions;
+pub mod tagging;
+pub mod tasks;
+pub mod tui;
+pub mod ui;
+pub mod update;
+pub mod validation;
@@ -1,39 +1,62 @@
-pub mod adapters;
-pub mod app;
-pub mod event;
-pub mod models;
-pub mod settings;
-pub mod tui;
-pub mod ui;
-pub mod update;
-
 use anyhow::Result;
-use app::App;
-use event::{Event, EventHandler};
+use fbmark::{
+    adapters::{http::Fetcher, sqlite::Repo},
+    app::App,
+    command::{Command, Executor},
+    event::EventHandler,
+    message::Message,
+    settings::Settings,
+    tui::Tui,
+    update::update,
+};
 use ratatui::{backend::CrosstermBackend, Terminal};
-use tui::Tui;
-use update::update;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     initialize_panic_handler();
-    let mut app = App::new().await?;
+    let repo = Repo::new().await?;
+    let fetcher = Fetcher::from_settings()?;
+
+    let events = EventHandler::new(250);
+    let mut executor = Executor::new(repo, fetcher, events.sender());
+    let mut app = App::new();
+    let settings = Settings::get_configuration().ok();
+    app.archive_on_create = settings
+        .as_ref()
+        .is_some_and(|settings| settings.archives.on_create);
+    if let Some(columns) = settings
+        .map(|settings| settings.list.columns)
+        .filter(|columns| !columns.is_empty())
+    {
+        app.columns = columns;
+    }
+    executor.execute(Command::Refresh {
+        tags_filter: Vec::new(),
+        collection_filter: None,
+    });
+    executor.execute(Command::LoadPageTexts);
+    executor.execute(Command::LoadSavedSearches);
+    executor.execute(Command::LoadSearchHistory);
 
     let backend = CrosstermBackend::new(std::io::stderr());
     let terminal = Terminal::new(backend)?;
-    let events = EventHandler::new(250);
     let mut tui = Tui::new(terminal, events);
     tui.init()?;
 
     while !app.is_should_quit() {
         tui.draw(&mut app)?;
 
-        match tui.events.next()? {
-            Event::Tick | Event::Mouse(_) | Event::Resize(_, _) => {}
-            Event::Key(key_event) => update(&mut app, key_event).await?,
-        };
+        if let Some(message) = Message::from_event(tui.events.next().await?) {
+            let (next, commands) = update(app, message);
+            app = next;
+            for command in commands {
+                executor.execute(command);
+            }
+        }
     }
 
+    executor.cancel_all();
+    tui.events.stop();
     tui.exit()?;
     Ok(())
 }
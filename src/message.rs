@@ -0,0 +1,287 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::Instant,
+};
+
+use chrono::{DateTime, Utc};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEventKind};
+
+use url::Url;
+
+use crate::{
+    adapters::metadata::PageMetadata,
+    app::BookmarkDetails,
+    event::Event,
+    models::{
+        bookmarks::{Bookmark, ReadingStatus},
+        collections::Collection,
+        link_checks::BrokenLink,
+        packs::Pack,
+        saved_searches::SavedSearch,
+        search_history::SearchHistoryEntry,
+        tags::{MergeCandidate, Tag},
+    },
+    suggestions::TagStats,
+    tagging::TagChange,
+};
+
+/// Everything that can happen to the app: decoded user input and results of the commands.
+#[derive(Debug)]
+pub enum Message {
+    Tick,
+    Quit,
+    /// Closes the message log or cancels the background jobs if there are any, quits otherwise.
+    Escape,
+    ToggleHelp,
+    ToggleLog,
+    ToggleMode,
+    ToggleCreationMode,
+    ToggleBrokenLinks,
+    ToggleReadingList,
+    TogglePacks,
+    ToggleTagMerges,
+    ToggleAutoTagging,
+    /// Focuses the collections sidebar.
+    ToggleCollections,
+    /// Shows or hides the details of the highlighted bookmark.
+    ToggleDetails,
+    /// Makes the title search look into the text of the pages as well.
+    ToggleContentSearch,
+    /// Opens the command palette or closes it.
+    TogglePalette,
+    ToggleSavedSearches,
+    /// Saves the current queries, tags filter and sort order under a generated name.
+    SaveSearch,
+    /// Narrows the library down to the tag, e.g. picked in the command palette.
+    FilterByTag(Tag),
+    OpenBookmark(i64),
+    RunSavedSearch(i64),
+    /// Pastes the clipboard into the link of the new bookmark.
+    Paste,
+    /// Looks for a query through the search history, the next older match if it's already looking.
+    ReverseSearch,
+    CheckLinks,
+    /// Opens the pinned bookmark by its number, starting from 1.
+    OpenPinned(usize),
+    IndexPages,
+    NextWindow,
+    Sync,
+    Reset,
+    Next,
+    Previous,
+    /// Unselects the focused list, the key goes to the focused input if there's no list.
+    Unselect(KeyEvent),
+    Enter,
+    Delete,
+    /// A key that is meant to be typed into the focused input.
+    Input(KeyEvent),
+    Click {
+        column: u16,
+        row: u16,
+        at: Instant,
+    },
+    ScrollDown {
+        column: u16,
+        row: u16,
+    },
+    ScrollUp {
+        column: u16,
+        row: u16,
+    },
+
+    /// The library along with the first page of the bookmarks, the rest follow as
+    /// [`Message::BookmarksLoaded`].
+    Refreshed {
+        bookmarks: Vec<Bookmark>,
+        /// Pinned bookmarks of the whole library, the tags filter doesn't apply to them.
+        pinned: Vec<Bookmark>,
+        tags: Vec<Tag>,
+        packs: Vec<Pack>,
+        collections: Vec<Collection>,
+        /// Ids of the bookmarks that have an archived copy.
+        archived: HashSet<i64>,
+    },
+    /// The next page of the bookmarks that are being refreshed.
+    BookmarksLoaded(Vec<Bookmark>),
+    BookmarkCreated {
+        bookmark: Bookmark,
+        /// Tags added by the tagging rules.
+        auto_tags: Vec<String>,
+    },
+    BookmarkDeleted(i64),
+    /// What has been put into the clipboard, e.g. "the link".
+    Copied(String),
+    Pasted(String),
+    BookmarkOpened {
+        id: i64,
+        at: DateTime<Utc>,
+    },
+    BookmarkRetargeted(i64),
+    PinnedChanged {
+        count: usize,
+        pinned: bool,
+    },
+    ReadingStatusChanged {
+        count: usize,
+        status: ReadingStatus,
+    },
+    PackSaved(Pack),
+    PackDeleted(i64),
+    PackAssigned {
+        tag: String,
+        pack: Option<String>,
+    },
+    CollectionSaved(String),
+    CollectionDeleted(i64),
+    CollectionsReordered,
+    SavedSearchesLoaded(Vec<SavedSearch>),
+    SearchSaved(String),
+    SavedSearchDeleted(i64),
+    /// The remembered searches, the latest one last.
+    SearchHistoryLoaded(Vec<SearchHistoryEntry>),
+    BookmarksMoved {
+        count: usize,
+        collection: Option<String>,
+    },
+    TagRulesPreviewed {
+        /// Descriptions of the rules by their indexes.
+        rules: Vec<String>,
+        changes: Vec<TagChange>,
+    },
+    TagChangesApplied {
+        bookmarks: usize,
+        tags: usize,
+    },
+    TagMergesLoaded(Vec<MergeCandidate>),
+    TagsMerged {
+        from: Tag,
+        into: Tag,
+    },
+    BrokenLinksLoaded(Vec<BrokenLink>),
+    LinksChecked {
+        checked: usize,
+        broken: usize,
+    },
+    Archived {
+        archived: usize,
+        /// Links that couldn't be archived along with the reasons.
+        failed: Vec<String>,
+    },
+    PagesIndexed {
+        indexed: usize,
+        /// Links that couldn't be indexed along with the reasons.
+        failed: Vec<String>,
+    },
+    /// Texts of the indexed pages by the ids of their bookmarks.
+    PageTextsLoaded(HashMap<i64, String>),
+    ArchiveOpened {
+        url: String,
+        archived_at: DateTime<Utc>,
+    },
+    /// Canonical forms of all the saved links.
+    KnownUrls(HashSet<String>),
+    TagStatsLoaded(Box<TagStats>),
+    DetailsLoaded(Box<BookmarkDetails>),
+    /// The tag typed in the creation popup, it may have existed under an alias.
+    TagCreated(Tag),
+    MetadataFetched {
+        url: Url,
+        metadata: Box<PageMetadata>,
+    },
+    /// Amount of the new bookmarks that came from Tagpacker and the browser,
+    /// along with the sources that couldn't be read.
+    Synced {
+        count: usize,
+        failures: Vec<String>,
+    },
+    TaskProgress {
+        id: u64,
+        done: usize,
+        total: usize,
+    },
+    TaskFinished {
+        id: u64,
+        outcome: Box<Message>,
+    },
+    Failed(String),
+}
+
+impl Message {
+    pub fn from_event(event: Event) -> Option<Self> {
+        match event {
+            Event::Tick => Some(Self::Tick),
+            Event::Resize(_, _) => None,
+            Event::Key(key_event) => Some(Self::from_key(key_event)),
+            Event::Mouse(mouse_event) => {
+                let (column, row) = (mouse_event.column, mouse_event.row);
+                match mouse_event.kind {
+                    MouseEventKind::Down(MouseButton::Left) => Some(Self::Click {
+                        column,
+                        row,
+                        at: Instant::now(),
+                    }),
+                    MouseEventKind::ScrollDown => Some(Self::ScrollDown { column, row }),
+                    MouseEventKind::ScrollUp => Some(Self::ScrollUp { column, row }),
+                    _ => None,
+                }
+            }
+            Event::Message(message) => Some(*message),
+        }
+    }
+
+    pub fn from_key(key_event: KeyEvent) -> Self {
+        match key_event.code {
+            KeyCode::Esc => Self::Escape,
+            KeyCode::Char('c') if key_event.modifiers == KeyModifiers::CONTROL => Self::Quit,
+            KeyCode::Char('f') if key_event.modifiers == KeyModifiers::CONTROL => {
+                Self::ToggleContentSearch
+            }
+            KeyCode::Char('o') if key_event.modifiers == KeyModifiers::CONTROL => {
+                Self::ToggleCollections
+            }
+            KeyCode::Char('d') if key_event.modifiers == KeyModifiers::CONTROL => {
+                Self::ToggleDetails
+            }
+            KeyCode::Char('p') if key_event.modifiers == KeyModifiers::CONTROL => {
+                Self::TogglePalette
+            }
+            KeyCode::Char('s') if key_event.modifiers == KeyModifiers::CONTROL => Self::SaveSearch,
+            KeyCode::Char('l') if key_event.modifiers == KeyModifiers::CONTROL => {
+                Self::ToggleSavedSearches
+            }
+            KeyCode::Char('r') if key_event.modifiers == KeyModifiers::CONTROL => {
+                Self::ReverseSearch
+            }
+            KeyCode::Char('v') if key_event.modifiers == KeyModifiers::CONTROL => Self::Paste,
+            KeyCode::Char(digit @ '1'..='9') if key_event.modifiers == KeyModifiers::ALT => {
+                Self::OpenPinned(key_number(digit))
+            }
+            KeyCode::F(1) => Self::ToggleHelp,
+            KeyCode::F(2) => Self::ToggleLog,
+            KeyCode::F(3) => Self::ToggleCreationMode,
+            KeyCode::F(4) => Self::ToggleReadingList,
+            KeyCode::F(5) => Self::Sync,
+            KeyCode::F(6) => Self::ToggleBrokenLinks,
+            KeyCode::F(7) => Self::CheckLinks,
+            KeyCode::F(8) => Self::IndexPages,
+            KeyCode::F(9) => Self::TogglePacks,
+            KeyCode::F(10) => Self::ToggleTagMerges,
+            KeyCode::F(11) => Self::ToggleAutoTagging,
+            KeyCode::F(12) => Self::Reset,
+            KeyCode::Left => Self::Unselect(key_event),
+            KeyCode::Down => Self::Next,
+            KeyCode::Up => Self::Previous,
+            KeyCode::Enter => Self::Enter,
+            KeyCode::Delete => Self::Delete,
+            KeyCode::Char('`') => Self::ToggleMode,
+            KeyCode::Tab => Self::NextWindow,
+            _ => Self::Input(key_event),
+        }
+    }
+}
+
+/// Number the digit key stands for, e.g. of a pinned bookmark.
+pub fn key_number(digit: char) -> usize {
+    digit.to_digit(10).map_or(0, |digit| digit as usize)
+}
@@ -1,2 +1,9 @@
+pub mod archives;
 pub mod bookmarks;
+pub mod collections;
+pub mod link_checks;
+pub mod packs;
+pub mod page_texts;
+pub mod saved_searches;
+pub mod search_history;
 pub mod tags;
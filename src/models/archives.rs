@@ -0,0 +1,60 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+
+/// Copy of a bookmarked page kept in case the page disappears.
+#[derive(Debug, Clone)]
+pub struct Archive {
+    pub bookmark_id: i64,
+    /// Where the page was downloaded from, after the redirects.
+    pub url: String,
+    /// Compressed HTML, see [`crate::adapters::archiver`].
+    pub content: Vec<u8>,
+    /// Size of the HTML before compression.
+    pub size: i64,
+    pub archived_at: DateTime<Utc>,
+}
+
+impl Archive {
+    pub fn new(
+        bookmark_id: i64,
+        url: String,
+        content: Vec<u8>,
+        size: i64,
+        archived_at: i64,
+    ) -> Self {
+        Self {
+            bookmark_id,
+            url,
+            content,
+            size,
+            archived_at: Utc
+                .timestamp_opt(archived_at, 0)
+                .single()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Replaces the previous copy of the page if there's one.
+    pub async fn save(repo: &impl Repo, archive: &Archive) -> Result<()> {
+        repo.save(archive).await
+    }
+
+    pub async fn get(repo: &impl Repo, bookmark_id: i64) -> Result<Option<Archive>> {
+        repo.get(bookmark_id).await
+    }
+
+    /// Ids of the bookmarks that have an archived copy.
+    pub async fn fetch_ids(repo: &impl Repo) -> Result<HashSet<i64>> {
+        repo.fetch_ids().await
+    }
+}
+
+#[async_trait]
+pub trait Repo {
+    async fn save(&self, archive: &Archive) -> Result<()>;
+    async fn get(&self, bookmark_id: i64) -> Result<Option<Archive>>;
+    async fn fetch_ids(&self) -> Result<HashSet<i64>>;
+}
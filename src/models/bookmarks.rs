@@ -1,9 +1,10 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use super::tags::Tag;
+use super::tags::{NewTagParams, Tag};
 
 // That struct only need to be able to operate with the bookmarks and thier tags from the database
 // since query_as cannot be used due to sqlite limitations (no ARRAY_AGG function)
@@ -12,6 +13,12 @@ pub struct RawBookmark {
     pub title: Option<String>,
     pub url: BookmarkUrl,
     pub tags: Option<String>,
+    pub status: String,
+    pub created_at: Option<i64>,
+    pub pinned_at: Option<i64>,
+    pub collection_id: Option<i64>,
+    pub description: Option<String>,
+    pub opened_at: Option<i64>,
 }
 
 #[derive(Debug)]
@@ -19,12 +26,34 @@ pub struct NewBookmarkParams {
     pub title: Option<String>,
     // TODO: change to just Url if possible
     pub url: Url,
-    pub tags: Vec<String>,
+    pub tags: Vec<NewTagParams>,
+    /// Names of the nested folders the bookmark is kept in at the source, the outermost first.
+    pub collection: Vec<String>,
+    pub description: Option<String>,
+    /// When the bookmark was saved at the source, the time of the import if it's unknown.
+    pub created_at: Option<DateTime<Utc>>,
 }
 
 impl NewBookmarkParams {
-    pub fn new(title: Option<String>, url: Url, tags: Vec<String>) -> Self {
-        Self { title, url, tags }
+    pub fn new(
+        title: Option<String>,
+        url: Url,
+        tags: Vec<NewTagParams>,
+        collection: Vec<String>,
+        description: Option<String>,
+    ) -> Self {
+        Self {
+            title,
+            url,
+            tags,
+            collection,
+            description,
+            created_at: None,
+        }
+    }
+
+    pub fn created_at(self, created_at: Option<DateTime<Utc>>) -> Self {
+        Self { created_at, ..self }
     }
 }
 
@@ -36,6 +65,46 @@ pub struct Bookmark {
     // TODO: change to Url if possible
     pub url: BookmarkUrl,
     pub tags: Option<Vec<Tag>>,
+    pub status: ReadingStatus,
+    /// Unknown for the bookmarks saved before the date started to be recorded.
+    pub created_at: Option<DateTime<Utc>>,
+    /// Pinned bookmarks are listed in the order they were pinned in.
+    pub pinned_at: Option<DateTime<Utc>>,
+    pub collection_id: Option<i64>,
+    /// What the page says about itself, or the note from the source it was imported from.
+    pub description: Option<String>,
+    /// When the bookmark was opened from the app last time.
+    pub opened_at: Option<DateTime<Utc>>,
+}
+
+/// Where the bookmark is in the read-later workflow.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReadingStatus {
+    Unread,
+    #[default]
+    Read,
+    /// Read and not needed in the reading list anymore.
+    Archived,
+}
+
+impl ReadingStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Unread => "unread",
+            Self::Read => "read",
+            Self::Archived => "archived",
+        }
+    }
+}
+
+impl From<&str> for ReadingStatus {
+    fn from(value: &str) -> Self {
+        match value {
+            "unread" => Self::Unread,
+            "archived" => Self::Archived,
+            _ => Self::Read,
+        }
+    }
 }
 
 // TODO: get rid of this struct completely
@@ -59,26 +128,106 @@ impl From<String> for BookmarkUrl {
 }
 
 impl Bookmark {
-    pub fn new(id: i64, title: Option<String>, url: String, tags: Option<Vec<Tag>>) -> Self {
+    pub fn new(
+        id: i64,
+        title: Option<String>,
+        url: String,
+        tags: Option<Vec<Tag>>,
+        status: &str,
+        created_at: Option<i64>,
+        pinned_at: Option<i64>,
+    ) -> Self {
+        let to_date = |timestamp: i64| Utc.timestamp_opt(timestamp, 0).single();
         Self {
             id,
             title,
             url: url.into(),
             tags,
+            status: status.into(),
+            created_at: created_at.and_then(to_date),
+            pinned_at: pinned_at.and_then(to_date),
+            collection_id: None,
+            description: None,
+            opened_at: None,
+        }
+    }
+
+    pub fn in_collection(self, collection_id: Option<i64>) -> Self {
+        Self {
+            collection_id,
+            ..self
+        }
+    }
+
+    pub fn with_description(self, description: Option<String>) -> Self {
+        Self {
+            description,
+            ..self
+        }
+    }
+
+    pub fn with_opened_at(self, opened_at: Option<i64>) -> Self {
+        Self {
+            opened_at: opened_at.and_then(|timestamp| Utc.timestamp_opt(timestamp, 0).single()),
+            ..self
         }
     }
 
+    pub fn is_pinned(&self) -> bool {
+        self.pinned_at.is_some()
+    }
+
+    /// The title, or the link for the bookmarks without one.
+    pub fn name(&self) -> String {
+        self.title
+            .clone()
+            .unwrap_or_else(|| self.url.inner.to_string())
+    }
+
+    /// The bookmark as a Markdown link, `[title](url)`.
+    pub fn markdown_link(&self) -> String {
+        let title = self.name().replace('[', "\\[").replace(']', "\\]");
+        // unbalanced parentheses would end the link early
+        let url = self
+            .url
+            .inner
+            .as_str()
+            .replace('(', "%28")
+            .replace(')', "%29");
+        format!("[{title}]({url})")
+    }
+
     pub async fn fetch_all(repo: &impl Repo, tags_filter: Vec<Tag>) -> Result<Vec<Self>> {
         repo.fetch_all(tags_filter).await
     }
 
+    /// Up to `limit` bookmarks following the one with the `after` id, in the order they were added in.
+    pub async fn fetch_page(
+        repo: &impl Repo,
+        tags_filter: Vec<Tag>,
+        after: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<Self>> {
+        repo.fetch_page(tags_filter, after, limit).await
+    }
+
+    /// All the pinned bookmarks regardless of any filters, in the order they were pinned in.
+    pub async fn fetch_pinned(repo: &impl Repo) -> Result<Vec<Self>> {
+        repo.fetch_pinned().await
+    }
+
+    pub async fn fetch_urls(repo: &impl Repo) -> Result<Vec<Url>> {
+        repo.fetch_urls().await
+    }
+
     pub async fn create(
         repo: &impl Repo,
         title: Option<String>,
         url: Url,
         tags: Option<Vec<Tag>>,
+        description: Option<String>,
     ) -> Result<Self> {
-        repo.create(title, url, tags).await
+        repo.create(title, url, tags, description).await
     }
 
     pub async fn batch_create(
@@ -88,6 +237,32 @@ impl Bookmark {
         repo.batch_create(params).await
     }
 
+    pub async fn update_url(repo: &impl Repo, id: i64, url: Url) -> Result<()> {
+        repo.update_url(id, url).await
+    }
+
+    pub async fn update_status(
+        repo: &impl Repo,
+        ids: Vec<i64>,
+        status: ReadingStatus,
+    ) -> Result<()> {
+        repo.update_status(ids, status).await
+    }
+
+    pub async fn set_pinned(repo: &impl Repo, ids: Vec<i64>, pinned: bool) -> Result<()> {
+        repo.set_pinned(ids, pinned).await
+    }
+
+    /// Remembers that the bookmark has just been opened, returns the time it's been opened at.
+    pub async fn mark_opened(repo: &impl Repo, id: i64) -> Result<DateTime<Utc>> {
+        repo.mark_opened(id).await
+    }
+
+    /// Adds the tags by their names, creating the missing ones.
+    pub async fn add_tags(repo: &impl Repo, id: i64, tags: Vec<String>) -> Result<()> {
+        repo.add_tags(id, tags).await
+    }
+
     pub async fn delete(repo: &impl Repo, id: i64) -> Result<()> {
         repo.delete(id).await
     }
@@ -96,12 +271,26 @@ impl Bookmark {
 #[async_trait]
 pub trait Repo {
     async fn fetch_all(&self, tags_filter: Vec<Tag>) -> Result<Vec<Bookmark>>;
+    async fn fetch_page(
+        &self,
+        tags_filter: Vec<Tag>,
+        after: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<Bookmark>>;
+    async fn fetch_pinned(&self) -> Result<Vec<Bookmark>>;
+    async fn fetch_urls(&self) -> Result<Vec<Url>>;
     async fn create(
         &self,
         title: Option<String>,
         url: Url,
         tags: Option<Vec<Tag>>,
+        description: Option<String>,
     ) -> Result<Bookmark>;
     async fn batch_create(&self, params: Vec<NewBookmarkParams>) -> Result<Vec<Bookmark>>;
+    async fn update_url(&self, id: i64, url: Url) -> Result<()>;
+    async fn update_status(&self, ids: Vec<i64>, status: ReadingStatus) -> Result<()>;
+    async fn set_pinned(&self, ids: Vec<i64>, pinned: bool) -> Result<()>;
+    async fn mark_opened(&self, id: i64) -> Result<DateTime<Utc>>;
+    async fn add_tags(&self, id: i64, tags: Vec<String>) -> Result<()>;
     async fn delete(&self, id: i64) -> Result<()>;
 }
@@ -0,0 +1,89 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// A folder of bookmarks, e.g. the one they were kept in by a browser.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Collection {
+    pub id: i64,
+    pub name: String,
+    pub parent_id: Option<i64>,
+    /// Order among the siblings.
+    pub position: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewCollectionParams {
+    pub name: String,
+    pub parent_id: Option<i64>,
+}
+
+impl Collection {
+    pub async fn fetch_all(repo: &impl Repo) -> Result<Vec<Self>> {
+        repo.fetch_all().await
+    }
+
+    pub async fn create(repo: &impl Repo, params: NewCollectionParams) -> Result<Self> {
+        repo.create(params).await
+    }
+
+    pub async fn rename(repo: &impl Repo, id: i64, name: String) -> Result<()> {
+        repo.rename(id, name).await
+    }
+
+    /// Deletes the collection with the nested ones, their bookmarks are left without a collection.
+    pub async fn delete(repo: &impl Repo, id: i64) -> Result<()> {
+        repo.delete(id).await
+    }
+
+    /// Puts the sibling collections in the given order.
+    pub async fn reorder(repo: &impl Repo, ids: Vec<i64>) -> Result<()> {
+        repo.reorder(ids).await
+    }
+
+    /// Moves the bookmarks into the collection, or out of any collection.
+    pub async fn move_bookmarks(
+        repo: &impl Repo,
+        ids: Vec<i64>,
+        collection_id: Option<i64>,
+    ) -> Result<()> {
+        repo.move_bookmarks(ids, collection_id).await
+    }
+}
+
+/// Ids of the collections along with all the ones nested into them.
+pub fn with_descendants(
+    collections: &[Collection],
+    ids: impl IntoIterator<Item = i64>,
+) -> HashSet<i64> {
+    let mut found: HashSet<i64> = ids.into_iter().collect();
+    // every pass goes at least one level deeper
+    loop {
+        let before = found.len();
+        for collection in collections {
+            if collection
+                .parent_id
+                .is_some_and(|parent_id| found.contains(&parent_id))
+            {
+                found.insert(collection.id);
+            }
+        }
+        if found.len() == before {
+            return found;
+        }
+    }
+}
+
+#[async_trait]
+pub trait Repo {
+    /// All the collections, the siblings go in their order.
+    async fn fetch_all(&self) -> Result<Vec<Collection>>;
+    /// Creates the collection after its last sibling.
+    async fn create(&self, params: NewCollectionParams) -> Result<Collection>;
+    async fn rename(&self, id: i64, name: String) -> Result<()>;
+    async fn delete(&self, id: i64) -> Result<()>;
+    async fn reorder(&self, ids: Vec<i64>) -> Result<()>;
+    async fn move_bookmarks(&self, ids: Vec<i64>, collection_id: Option<i64>) -> Result<()>;
+}
@@ -0,0 +1,74 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+
+/// Outcome of the last request to a bookmarked page.
+#[derive(Debug, Clone)]
+pub struct LinkCheck {
+    pub bookmark_id: i64,
+    pub status_code: Option<u16>,
+    /// Where the redirects ended up, if anywhere but the bookmarked link.
+    pub final_url: Option<String>,
+    pub error: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// A bookmark that either doesn't respond properly or redirects elsewhere.
+#[derive(Debug, Clone)]
+pub struct BrokenLink {
+    pub bookmark_id: i64,
+    pub title: Option<String>,
+    pub url: String,
+    pub check: LinkCheck,
+}
+
+impl LinkCheck {
+    pub fn new(
+        bookmark_id: i64,
+        status_code: Option<i64>,
+        final_url: Option<String>,
+        error: Option<String>,
+        checked_at: i64,
+    ) -> Self {
+        Self {
+            bookmark_id,
+            status_code: status_code.and_then(|code| u16::try_from(code).ok()),
+            final_url,
+            error,
+            checked_at: Utc
+                .timestamp_opt(checked_at, 0)
+                .single()
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn is_broken(&self) -> bool {
+        self.error.is_some() || self.status_code.map_or(true, |code| code >= 400)
+    }
+
+    pub async fn save(repo: &impl Repo, check: &LinkCheck) -> Result<()> {
+        repo.save(check).await
+    }
+
+    pub async fn fetch_broken(repo: &impl Repo) -> Result<Vec<BrokenLink>> {
+        repo.fetch_broken().await
+    }
+
+    /// The last check of the bookmark, if it's been checked.
+    pub async fn get(repo: &impl Repo, bookmark_id: i64) -> Result<Option<LinkCheck>> {
+        repo.get(bookmark_id).await
+    }
+
+    pub async fn delete(repo: &impl Repo, bookmark_id: i64) -> Result<()> {
+        repo.delete(bookmark_id).await
+    }
+}
+
+#[async_trait]
+pub trait Repo {
+    async fn save(&self, check: &LinkCheck) -> Result<()>;
+    /// Bookmarks that failed the last check or got redirected to another link.
+    async fn fetch_broken(&self) -> Result<Vec<BrokenLink>>;
+    async fn get(&self, bookmark_id: i64) -> Result<Option<LinkCheck>>;
+    async fn delete(&self, bookmark_id: i64) -> Result<()>;
+}
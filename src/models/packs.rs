@@ -0,0 +1,58 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// A named group of tags, like the tag packs of Tagpacker.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Pack {
+    pub id: i64,
+    pub name: String,
+    /// Hue of the color in degrees.
+    pub color: u16,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewPackParams {
+    pub name: String,
+    pub color: u16,
+}
+
+impl Pack {
+    pub fn new(id: i64, name: String, color: i64) -> Self {
+        Self {
+            id,
+            name,
+            color: u16::try_from(color.rem_euclid(360)).unwrap_or_default(),
+        }
+    }
+
+    pub async fn fetch_all(repo: &impl Repo) -> Result<Vec<Self>> {
+        repo.fetch_all().await
+    }
+
+    pub async fn create(repo: &impl Repo, params: NewPackParams) -> Result<Self> {
+        repo.create(params).await
+    }
+
+    pub async fn update(repo: &impl Repo, pack: Pack) -> Result<()> {
+        repo.update(pack).await
+    }
+
+    pub async fn delete(repo: &impl Repo, id: i64) -> Result<()> {
+        repo.delete(id).await
+    }
+
+    /// Moves the tag into the pack, or out of any pack.
+    pub async fn assign(repo: &impl Repo, tag_id: i64, pack_id: Option<i64>) -> Result<()> {
+        repo.assign(tag_id, pack_id).await
+    }
+}
+
+#[async_trait]
+pub trait Repo {
+    async fn fetch_all(&self) -> Result<Vec<Pack>>;
+    async fn create(&self, params: NewPackParams) -> Result<Pack>;
+    async fn update(&self, pack: Pack) -> Result<()>;
+    async fn delete(&self, id: i64) -> Result<()>;
+    async fn assign(&self, tag_id: i64, pack_id: Option<i64>) -> Result<()>;
+}
@@ -0,0 +1,45 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+/// Readable text of a bookmarked page, used by the full-text search.
+#[derive(Debug, Clone)]
+pub struct PageText {
+    pub bookmark_id: i64,
+    pub text: String,
+    pub extracted_at: DateTime<Utc>,
+}
+
+impl PageText {
+    pub fn new(bookmark_id: i64, text: String) -> Self {
+        Self {
+            bookmark_id,
+            text,
+            extracted_at: Utc::now(),
+        }
+    }
+
+    /// Replaces the previous text of the page if there's one.
+    pub async fn save(repo: &impl Repo, page_text: &PageText) -> Result<()> {
+        repo.save(page_text).await
+    }
+
+    /// Texts of all the pages by the ids of their bookmarks.
+    pub async fn fetch_all(repo: &impl Repo) -> Result<HashMap<i64, String>> {
+        repo.fetch_all().await
+    }
+
+    /// Ids of the bookmarks whose pages have been indexed.
+    pub async fn fetch_ids(repo: &impl Repo) -> Result<HashSet<i64>> {
+        repo.fetch_ids().await
+    }
+}
+
+#[async_trait]
+pub trait Repo {
+    async fn save(&self, page_text: &PageText) -> Result<()>;
+    async fn fetch_all(&self) -> Result<HashMap<i64, String>>;
+    async fn fetch_ids(&self) -> Result<HashSet<i64>>;
+}
@@ -0,0 +1,54 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::{models::tags::Tag, settings::Column};
+
+/// A named combination of the search queries, the tags filter and the sort order,
+/// run again against the library whenever it's picked.
+#[derive(Debug, Clone)]
+pub struct SavedSearch {
+    pub id: i64,
+    pub name: String,
+    pub title_query: String,
+    pub link_query: String,
+    pub tags: Vec<Tag>,
+    pub sort_column: Option<Column>,
+    pub sort_descending: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewSavedSearchParams {
+    pub name: String,
+    pub title_query: String,
+    pub link_query: String,
+    pub tag_ids: Vec<i64>,
+    pub sort_column: Option<Column>,
+    pub sort_descending: bool,
+}
+
+impl SavedSearch {
+    pub async fn fetch_all(repo: &impl Repo) -> Result<Vec<Self>> {
+        repo.fetch_all().await
+    }
+
+    /// Saves the search, replacing the one with the same name.
+    pub async fn create(repo: &impl Repo, params: NewSavedSearchParams) -> Result<Self> {
+        repo.create(params).await
+    }
+
+    pub async fn rename(repo: &impl Repo, id: i64, name: String) -> Result<()> {
+        repo.rename(id, name).await
+    }
+
+    pub async fn delete(repo: &impl Repo, id: i64) -> Result<()> {
+        repo.delete(id).await
+    }
+}
+
+#[async_trait]
+pub trait Repo {
+    async fn fetch_all(&self) -> Result<Vec<SavedSearch>>;
+    async fn create(&self, params: NewSavedSearchParams) -> Result<SavedSearch>;
+    async fn rename(&self, id: i64, name: String) -> Result<()>;
+    async fn delete(&self, id: i64) -> Result<()>;
+}
@@ -0,0 +1,60 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::models::tags::Tag;
+
+/// Amount of the latest searches kept in the history.
+pub const HISTORY_LIMIT: i64 = 200;
+
+/// A search that has been submitted, recalled later in the search inputs.
+#[derive(Debug, Clone, Default)]
+pub struct SearchHistoryEntry {
+    pub title_query: String,
+    pub link_query: String,
+    pub tags: Vec<Tag>,
+}
+
+impl SearchHistoryEntry {
+    /// The remembered searches, the latest one last.
+    pub async fn fetch_all(repo: &impl Repo) -> Result<Vec<Self>> {
+        repo.fetch_all().await
+    }
+
+    /// Remembers the search as the latest one, the same earlier search and the ones
+    /// over [`HISTORY_LIMIT`] are forgotten.
+    pub async fn remember(repo: &impl Repo, entry: Self) -> Result<()> {
+        repo.remember(entry).await
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.title_query.is_empty() && self.link_query.is_empty() && self.tags.is_empty()
+    }
+
+    /// Whether both are the same queries with the same tags, in whatever order.
+    pub fn is_same(&self, other: &Self) -> bool {
+        self.title_query == other.title_query
+            && self.link_query == other.link_query
+            && self.tags.len() == other.tags.len()
+            && self
+                .tags
+                .iter()
+                .all(|tag| other.tags.iter().any(|other| other.id == tag.id))
+    }
+
+    /// Whether the queries or the tag names contain the text, ignoring the case.
+    pub fn matches(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+        self.title_query.to_lowercase().contains(&text)
+            || self.link_query.to_lowercase().contains(&text)
+            || self
+                .tags
+                .iter()
+                .any(|tag| tag.name.to_lowercase().contains(&text))
+    }
+}
+
+#[async_trait]
+pub trait Repo {
+    async fn fetch_all(&self) -> Result<Vec<SearchHistoryEntry>>;
+    async fn remember(&self, entry: SearchHistoryEntry) -> Result<()>;
+}
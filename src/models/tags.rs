@@ -1,16 +1,67 @@
+use std::collections::{HashMap, HashSet};
+
 use anyhow::Result;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
+use super::packs::NewPackParams;
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct Tag {
     pub id: i64,
+    /// The full path, e.g. `lang/rust`.
+    pub name: String,
+    /// The parent and the pack are not loaded for the tags that come along with bookmarks.
+    pub parent_id: Option<i64>,
+    pub pack_id: Option<i64>,
+}
+
+/// A tag as it comes from an importer.
+#[derive(Debug)]
+pub struct NewTagParams {
     pub name: String,
+    /// The pack the tag belongs to at the source.
+    pub pack: Option<NewPackParams>,
+}
+
+impl From<String> for NewTagParams {
+    fn from(name: String) -> Self {
+        Self { name, pack: None }
+    }
+}
+
+/// A tag that looks like a duplicate of another one.
+#[derive(Debug, Clone)]
+pub struct MergeCandidate {
+    pub tag: Tag,
+    /// Amount of bookmarks with the tag.
+    pub uses: usize,
+    /// The tag it's proposed to be merged into.
+    pub into: Tag,
+    pub into_uses: usize,
+}
+
+impl MergeCandidate {
+    /// Proposes the merge the other way around.
+    pub fn swap(&mut self) {
+        std::mem::swap(&mut self.tag, &mut self.into);
+        std::mem::swap(&mut self.uses, &mut self.into_uses);
+    }
 }
 
 impl Tag {
     pub fn new(id: i64, name: String) -> Self {
-        Self { id, name }
+        Self {
+            id,
+            name,
+            parent_id: None,
+            pack_id: None,
+        }
+    }
+
+    /// The last segment of the path, e.g. `rust` for `lang/rust`.
+    pub fn label(&self) -> &str {
+        self.name.rsplit('/').next().unwrap_or(&self.name)
     }
 
     pub async fn fetch_all(repo: &impl Repo) -> Result<Vec<Self>> {
@@ -32,13 +83,102 @@ impl Tag {
     pub async fn delete(repo: &impl Repo, id: i64) -> Result<Self> {
         repo.delete(id).await
     }
+
+    /// Moves the bookmarks, children and aliases of the tag to another one,
+    /// the name of the tag becomes an alias of that one.
+    pub async fn merge(repo: &impl Repo, from_id: i64, into_id: i64) -> Result<()> {
+        repo.merge(from_id, into_id).await
+    }
+}
+
+/// Tags that differ only in case, separators or a plural ending from another one.
+///
+/// Every group of them is proposed to be merged into its most used tag.
+pub fn merge_candidates(tags: &[Tag], usage: &HashMap<i64, usize>) -> Vec<MergeCandidate> {
+    let mut groups: HashMap<String, Vec<&Tag>> = HashMap::new();
+    for tag in tags {
+        groups.entry(normalize(&tag.name)).or_default().push(tag);
+    }
+
+    let uses = |tag: &Tag| usage.get(&tag.id).copied().unwrap_or_default();
+    let mut candidates = Vec::new();
+    for group in groups.values().filter(|group| group.len() > 1) {
+        let Some(into) = group
+            .iter()
+            .max_by(|a, b| uses(a).cmp(&uses(b)).then(b.id.cmp(&a.id)))
+        else {
+            continue;
+        };
+        for tag in group.iter().filter(|tag| tag.id != into.id) {
+            candidates.push(MergeCandidate {
+                tag: (*tag).clone(),
+                uses: uses(tag),
+                into: (*into).clone(),
+                into_uses: uses(into),
+            });
+        }
+    }
+    candidates.sort_by(|a, b| a.into.name.cmp(&b.into.name).then(a.tag.id.cmp(&b.tag.id)));
+    candidates
+}
+
+/// The form of the name near-duplicates share: `Web-Dev/APIs` and `webdev/api`.
+fn normalize(name: &str) -> String {
+    name.split('/')
+        .map(|segment| {
+            let folded: String = segment
+                .chars()
+                .filter(|c| !matches!(c, '-' | '_' | '.' | ' '))
+                .flat_map(char::to_lowercase)
+                .collect();
+            // plurals, but not the short words like `js` or `css`
+            match folded.strip_suffix('s') {
+                Some(singular) if singular.chars().count() >= 3 => singular.to_string(),
+                _ => folded,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Names of the tag and all its ancestors, the root goes first: `lang`, `lang/rust`.
+pub fn ancestry(name: &str) -> Vec<&str> {
+    name.match_indices('/')
+        .map(|(index, _)| &name[..index])
+        .filter(|prefix| !prefix.is_empty())
+        .chain(std::iter::once(name))
+        .collect()
+}
+
+/// Ids of the tags along with the ids of all their descendants.
+pub fn with_descendants(tags: &[Tag], ids: impl IntoIterator<Item = i64>) -> HashSet<i64> {
+    let mut found: HashSet<i64> = ids.into_iter().collect();
+    // every pass goes at least one level deeper
+    loop {
+        let before = found.len();
+        for tag in tags {
+            if tag
+                .parent_id
+                .is_some_and(|parent_id| found.contains(&parent_id))
+            {
+                found.insert(tag.id);
+            }
+        }
+        if found.len() == before {
+            return found;
+        }
+    }
 }
 
 #[async_trait]
 pub trait Repo {
     async fn fetch_all(&self) -> Result<Vec<Tag>>;
+    /// Creates the tag along with its missing ancestors,
+    /// unless the name already resolves to a tag.
     async fn create(&self, name: String) -> Result<Tag>;
     async fn get(&self, id: i64) -> Result<Tag>;
+    /// Finds the tag by its name or alias, case-insensitively.
     async fn get_by_name(&self, name: String) -> Result<Tag>;
     async fn delete(&self, id: i64) -> Result<Tag>;
+    async fn merge(&self, from_id: i64, into_id: i64) -> Result<()>;
 }
@@ -0,0 +1,73 @@
+use chrono::{DateTime, Local, TimeDelta};
+
+use crate::app::StatefulList;
+
+// Notifications older than that are only visible in the log
+const TRANSIENT_FOR: TimeDelta = TimeDelta::seconds(5);
+const LOG_CAPACITY: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub level: Level,
+    pub text: String,
+    pub at: DateTime<Local>,
+}
+
+/// Messages shown in the status bar and kept in the message log.
+#[derive(Debug, Default)]
+pub struct Notifications {
+    pub log: StatefulList<Notification>,
+    pub show_log: bool,
+}
+
+impl Notifications {
+    pub fn info(&mut self, text: impl Into<String>) {
+        self.push(Level::Info, text.into());
+    }
+
+    pub fn warning(&mut self, text: impl Into<String>) {
+        self.push(Level::Warning, text.into());
+    }
+
+    pub fn error(&mut self, text: impl Into<String>) {
+        self.push(Level::Error, text.into());
+    }
+
+    fn push(&mut self, level: Level, text: String) {
+        self.log.items.push(Notification {
+            level,
+            text,
+            at: Local::now(),
+        });
+
+        if self.log.items.len() > LOG_CAPACITY {
+            self.log.items.remove(0);
+            if let Some(selected) = self.log.state.selected() {
+                self.log.state.select(Some(selected.saturating_sub(1)));
+            }
+        }
+    }
+
+    /// The latest notification, if it's still fresh enough to be shown in the status bar.
+    pub fn current(&self, now: DateTime<Local>) -> Option<&Notification> {
+        self.log
+            .items
+            .last()
+            .filter(|notification| now - notification.at < TRANSIENT_FOR)
+    }
+
+    pub fn toggle_log(&mut self) {
+        self.show_log = !self.show_log;
+        if self.show_log {
+            // start from the most recent message
+            self.log.state.select(self.log.items.len().checked_sub(1));
+        }
+    }
+}
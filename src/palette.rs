@@ -0,0 +1,282 @@
+use std::cmp::Reverse;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use simsearch::SimSearch;
+
+use crate::{
+    app::{App, Mode},
+    message::Message,
+    models::tags::Tag,
+};
+
+// Amount of the recently opened bookmarks offered
+const RECENT_SHOWN: usize = 10;
+
+/// What choosing an entry of the command palette does.
+#[derive(Debug, Clone)]
+pub enum Action {
+    /// Presses the key, so the action goes exactly the way it goes when typed.
+    Key(KeyEvent),
+    FilterByTag(Tag),
+    OpenBookmark(i64),
+    RunSavedSearch(i64),
+}
+
+impl Action {
+    pub fn into_message(self) -> Message {
+        match self {
+            Action::Key(key_event) => Message::from_key(key_event),
+            Action::FilterByTag(tag) => Message::FilterByTag(tag),
+            Action::OpenBookmark(id) => Message::OpenBookmark(id),
+            Action::RunSavedSearch(id) => Message::RunSavedSearch(id),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub title: String,
+    pub action: Action,
+}
+
+impl Entry {
+    fn key(title: impl Into<String>, code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self {
+            title: title.into(),
+            action: Action::Key(KeyEvent::new(code, modifiers)),
+        }
+    }
+
+    fn plain_key(title: &str, key: char) -> Self {
+        Self::key(title, KeyCode::Char(key), KeyModifiers::NONE)
+    }
+
+    /// The key the entry is bound to, if any.
+    pub fn binding(&self) -> Option<String> {
+        match &self.action {
+            Action::Key(key_event) => Some(key_label(key_event)),
+            _ => None,
+        }
+    }
+}
+
+/// The key as the help spells it, e.g. "Ctrl+P" or "F5".
+pub fn key_label(key_event: &KeyEvent) -> String {
+    let key = match key_event.code {
+        KeyCode::F(number) => format!("F{number}"),
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            c.to_ascii_uppercase().to_string()
+        }
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Delete => "Del".to_string(),
+        code => format!("{code:?}"),
+    };
+
+    if key_event.modifiers.contains(KeyModifiers::CONTROL) {
+        format!("Ctrl+{key}")
+    } else if key_event.modifiers.contains(KeyModifiers::ALT) {
+        format!("Alt+{key}")
+    } else {
+        key
+    }
+}
+
+/// Everything that can be done in the current mode, then the recently opened bookmarks,
+/// the saved searches and the tags to filter by.
+pub fn entries(app: &App) -> Vec<Entry> {
+    let f_key = |title: &str, number: u8| Entry::key(title, KeyCode::F(number), KeyModifiers::NONE);
+    let ctrl =
+        |title: &str, key: char| Entry::key(title, KeyCode::Char(key), KeyModifiers::CONTROL);
+
+    let mut entries = vec![
+        f_key("Show help", 1),
+        f_key("Show the message log", 2),
+        f_key("Create a bookmark", 3),
+        f_key("Reading list", 4),
+        f_key("Sync the bookmarks", 5),
+        f_key("Broken links", 6),
+        f_key("Check all links", 7),
+        f_key("Index the pages for the content search", 8),
+        f_key("Tag packs", 9),
+        f_key("Merge near-duplicate tags", 10),
+        f_key("Tag by the rules", 11),
+        f_key("Reset the filters", 12),
+        Entry::plain_key("Switch between searching and scrolling", '`'),
+        ctrl("Search in the page texts", 'f'),
+        ctrl("Collections", 'o'),
+        ctrl("Save the search", 's'),
+        ctrl("Saved searches", 'l'),
+        ctrl("Search the history", 'r'),
+        ctrl("Show or hide the details", 'd'),
+        ctrl("Quit", 'c'),
+    ];
+
+    entries.extend(mode_entries(&app.mode));
+
+    for (index, bookmark) in app.pinned.iter().take(9).enumerate() {
+        entries.push(Entry::key(
+            format!("Open pinned: {}", bookmark.name()),
+            KeyCode::Char(char::from_digit(index as u32 + 1, 10).unwrap_or('1')),
+            KeyModifiers::ALT,
+        ));
+    }
+
+    let mut recent: Vec<_> = app
+        .bookmarks
+        .iter()
+        .filter(|bookmark| bookmark.opened_at.is_some())
+        .collect();
+    recent.sort_by_key(|bookmark| Reverse(bookmark.opened_at));
+    entries.extend(recent.into_iter().take(RECENT_SHOWN).map(|bookmark| Entry {
+        title: format!("Open recent: {}", bookmark.name()),
+        action: Action::OpenBookmark(bookmark.id),
+    }));
+
+    entries.extend(app.saved_searches.iter().map(|search| Entry {
+        title: format!("Run saved search: {}", search.name),
+        action: Action::RunSavedSearch(search.id),
+    }));
+
+    entries.extend(app.tags.iter().map(|tag| Entry {
+        title: format!("Filter by tag: {}", tag.name),
+        action: Action::FilterByTag(tag.clone()),
+    }));
+
+    entries
+}
+
+/// The keys of the view that is open.
+fn mode_entries(mode: &Mode) -> Vec<Entry> {
+    let enter = |title: &str| Entry::key(title, KeyCode::Enter, KeyModifiers::NONE);
+    let delete = |title: &str| Entry::key(title, KeyCode::Delete, KeyModifiers::NONE);
+    let key = Entry::plain_key;
+
+    match mode {
+        Mode::Scrolling => vec![
+            enter("Open the bookmark"),
+            delete("Delete the bookmark"),
+            key("Mark the bookmark", ' '),
+            key("Mark as read", 'r'),
+            key("Mark as unread", 'u'),
+            key("Mark as archived", 'x'),
+            key("Pin or unpin", 'p'),
+            key("Archive the page", 'a'),
+            key("Open the archived copy", 'o'),
+            key("Sort by the next column", 's'),
+            key("Reverse the sort", 'S'),
+            key("Move to a collection", 'm'),
+            key("Copy the link", 'y'),
+            key("Copy as a Markdown link", 'Y'),
+            key("Copy the title", 't'),
+        ],
+        Mode::ReadingList(_) => vec![
+            enter("Open the bookmark"),
+            delete("Delete the bookmark"),
+            key("Show unread, read or archived", 'f'),
+            key("Mark as read", 'r'),
+            key("Mark as unread", 'u'),
+            key("Mark as archived", 'x'),
+        ],
+        Mode::BrokenLinks(_) => vec![
+            key("Move to the redirect destination", 'r'),
+            key("Open the archived copy", 'o'),
+            key("Check the link again", 'c'),
+            delete("Delete the bookmark"),
+        ],
+        Mode::Packs(_) => vec![
+            key("New pack", 'n'),
+            key("Rename the pack", 'e'),
+            key("Change the color of the pack", 'c'),
+            delete("Delete the pack"),
+        ],
+        Mode::Collections(_) => vec![
+            enter("Show the collection"),
+            key("New collection", 'n'),
+            key("Rename the collection", 'e'),
+            key("Move the collection up", '['),
+            key("Move the collection down", ']'),
+            key("Fold or unfold the collection", ' '),
+            delete("Delete the collection"),
+        ],
+        Mode::TagMerges(_) => vec![
+            enter("Merge the tags"),
+            key("Swap the merged tags", 's'),
+            delete("Dismiss the merge"),
+        ],
+        Mode::AutoTagging(_) => vec![
+            enter("Apply the changes"),
+            key("Show the next rule only", 'f'),
+            delete("Dismiss the change"),
+        ],
+        Mode::SavedSearches(_) => vec![
+            enter("Run the saved search"),
+            key("Rename the saved search", 'e'),
+            delete("Delete the saved search"),
+        ],
+        Mode::Create(_) => vec![Entry::key(
+            "Paste the link",
+            KeyCode::Char('v'),
+            KeyModifiers::CONTROL,
+        )],
+        Mode::Search(_) => vec![],
+    }
+}
+
+/// The entries that match the query, the best matches first.
+pub fn filter(entries: &[Entry], query: &str) -> Vec<Entry> {
+    if query.trim().is_empty() {
+        return entries.to_vec();
+    }
+
+    let mut engine: SimSearch<usize> = SimSearch::new();
+    for (index, entry) in entries.iter().enumerate() {
+        engine.insert(index, &entry.title);
+    }
+    engine
+        .search(query)
+        .into_iter()
+        .filter_map(|index| entries.get(index).cloned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::SearchingParams;
+
+    fn titles(entries: &[Entry]) -> Vec<&str> {
+        entries.iter().map(|entry| entry.title.as_str()).collect()
+    }
+
+    #[test]
+    fn ranks_the_best_match_first() {
+        let entries = entries(&App::new());
+        let found = filter(&entries, "sync");
+        assert_eq!(found[0].title, "Sync the bookmarks");
+        assert_eq!(found[0].binding().as_deref(), Some("F5"));
+
+        // nothing typed, everything is there in its order
+        assert_eq!(titles(&filter(&entries, " ")), titles(&entries));
+    }
+
+    #[test]
+    fn offers_the_keys_of_the_open_view() {
+        let scrolling = entries(&App::new());
+        assert!(titles(&scrolling).contains(&"Mark as read"));
+
+        let mut app = App::new();
+        app.mode = Mode::Search(SearchingParams::default());
+        // the letters would be typed into the search
+        let searching = entries(&app);
+        assert!(!titles(&searching).contains(&"Mark as read"));
+        assert!(searching.iter().all(|entry| !matches!(
+            entry.action,
+            Action::Key(KeyEvent {
+                code: KeyCode::Char(c),
+                modifiers: KeyModifiers::NONE,
+                ..
+            }) if c.is_alphanumeric()
+        )));
+    }
+}
@@ -1,9 +1,23 @@
-use anyhow::Result;
+use std::{path::PathBuf, str::FromStr};
+
+use anyhow::{anyhow, Result};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
 pub struct Settings {
     pub tagpacker: Tagpacker,
+    #[serde(default)]
+    pub http: Http,
+    #[serde(default)]
+    pub link_checks: LinkChecks,
+    #[serde(default)]
+    pub archives: Archives,
+    #[serde(default)]
+    pub tagging: Tagging,
+    #[serde(default)]
+    pub browser: Browser,
+    #[serde(default)]
+    pub list: BookmarkList,
 }
 
 #[derive(Debug, Deserialize)]
@@ -11,6 +25,174 @@ pub struct Tagpacker {
     pub user_id: String,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Http {
+    pub timeout_secs: u64,
+    pub max_page_bytes: usize,
+}
+
+impl Default for Http {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 10,
+            max_page_bytes: 2 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct LinkChecks {
+    /// Amount of links checked at the same time.
+    pub concurrency: usize,
+    /// Pause between two requests to the same host.
+    pub per_host_interval_ms: u64,
+}
+
+impl Default for LinkChecks {
+    fn default() -> Self {
+        Self {
+            concurrency: 8,
+            per_host_interval_ms: 1000,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct Archives {
+    /// Whether stylesheets and images are embedded into the archived page.
+    pub inline_resources: bool,
+    /// Size of the archived page with the embedded files, the ones that don't fit stay links.
+    pub max_archive_bytes: usize,
+    /// Whether new bookmarks are archived right after they are created.
+    pub on_create: bool,
+}
+
+impl Default for Archives {
+    fn default() -> Self {
+        Self {
+            inline_resources: true,
+            max_archive_bytes: 10 * 1024 * 1024,
+            on_create: false,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct Tagging {
+    pub rules: Vec<TagRule>,
+}
+
+/// Adds the tag to the bookmarks that meet all the given conditions.
+#[derive(Debug, Deserialize, Clone)]
+pub struct TagRule {
+    pub tag: String,
+    /// Host of the link, its subdomains match as well.
+    pub domain: Option<String>,
+    /// Regular expression the link matches.
+    pub url: Option<String>,
+    /// Text the title contains, case-insensitively.
+    pub title_contains: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct Browser {
+    /// Bookmarks exported by a browser into an HTML file, imported along with Tagpacker ones.
+    pub bookmarks_file: Option<PathBuf>,
+}
+
+/// Columns of the bookmarks table, in the order they are shown.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct BookmarkList {
+    pub columns: Vec<ListColumn>,
+}
+
+impl Default for BookmarkList {
+    fn default() -> Self {
+        Self {
+            columns: vec![
+                ListColumn::new(Column::Title, 40),
+                ListColumn::new(Column::Url, 40),
+                ListColumn::new(Column::Tags, 20),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct ListColumn {
+    pub column: Column,
+    /// Share of the table width in percent.
+    pub width: u16,
+}
+
+impl ListColumn {
+    pub fn new(column: Column, width: u16) -> Self {
+        Self { column, width }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Column {
+    Title,
+    Domain,
+    Url,
+    Tags,
+    /// Date the bookmark was added.
+    Added,
+    /// Date the bookmark was opened last time.
+    Opened,
+}
+
+impl Column {
+    pub fn header(&self) -> &'static str {
+        match self {
+            Self::Title => "Title",
+            Self::Domain => "Domain",
+            Self::Url => "Link",
+            Self::Tags => "Tags",
+            Self::Added => "Added",
+            Self::Opened => "Opened",
+        }
+    }
+
+    /// The name the column goes by in the settings and in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Title => "title",
+            Self::Domain => "domain",
+            Self::Url => "url",
+            Self::Tags => "tags",
+            Self::Added => "added",
+            Self::Opened => "opened",
+        }
+    }
+}
+
+impl FromStr for Column {
+    type Err = anyhow::Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        [
+            Self::Title,
+            Self::Domain,
+            Self::Url,
+            Self::Tags,
+            Self::Added,
+            Self::Opened,
+        ]
+        .into_iter()
+        .find(|column| column.as_str() == name)
+        .ok_or_else(|| anyhow!("There's no column named {name}"))
+    }
+}
+
 impl Settings {
     pub fn get_configuration() -> Result<Settings> {
         let base_path = std::env::current_dir().expect("Failed to determine the current directory");
@@ -0,0 +1,194 @@
+use std::collections::{HashMap, HashSet};
+
+use url::Url;
+
+use crate::models::{bookmarks::Bookmark, tags::Tag};
+
+/// Amount of the suggested tags, each of them is picked with its number.
+pub const SUGGESTIONS_SHOWN: usize = 5;
+
+// How much every kind of evidence counts
+const SAME_HOST_WEIGHT: f64 = 3.0;
+const CO_OCCURRENCE_WEIGHT: f64 = 2.0;
+const TITLE_TOKEN_WEIGHT: f64 = 1.5;
+
+/// Where the tags are used in the library and along with what.
+#[derive(Debug, Clone, Default)]
+pub struct TagStats {
+    /// Uses of the tags by the hosts of the bookmarks.
+    by_host: HashMap<String, HashMap<i64, usize>>,
+    bookmarks_by_host: HashMap<String, usize>,
+    /// Tags of every bookmark.
+    tag_sets: Vec<HashSet<i64>>,
+}
+
+impl TagStats {
+    pub fn new(bookmarks: &[Bookmark]) -> Self {
+        let mut stats = Self::default();
+        for bookmark in bookmarks {
+            let tag_ids: HashSet<i64> = bookmark.tags.iter().flatten().map(|tag| tag.id).collect();
+            if let Some(host) = host_of(&bookmark.url.inner) {
+                *stats.bookmarks_by_host.entry(host.clone()).or_insert(0) += 1;
+                let uses = stats.by_host.entry(host).or_default();
+                for id in &tag_ids {
+                    *uses.entry(*id).or_insert(0) += 1;
+                }
+            }
+            if !tag_ids.is_empty() {
+                stats.tag_sets.push(tag_ids);
+            }
+        }
+        stats
+    }
+}
+
+/// The tags that fit the new bookmark best, the selected ones are left out.
+///
+/// A tag scores for being used on the same site, for going along with the
+/// selected tags in other bookmarks and for sharing words with the title.
+pub fn suggest(
+    stats: &TagStats,
+    tags: &[Tag],
+    url: Option<&Url>,
+    title: &str,
+    selected: &[Tag],
+) -> Vec<Tag> {
+    let selected_ids: HashSet<i64> = selected.iter().map(|tag| tag.id).collect();
+    let mut scores: HashMap<i64, f64> = HashMap::new();
+
+    if let Some(host) = url.and_then(host_of) {
+        let total = stats
+            .bookmarks_by_host
+            .get(&host)
+            .copied()
+            .unwrap_or_default();
+        for (id, uses) in stats.by_host.get(&host).into_iter().flatten() {
+            *scores.entry(*id).or_default() += SAME_HOST_WEIGHT * share(*uses, total);
+        }
+    }
+
+    for selected_id in &selected_ids {
+        let with_selected: Vec<&HashSet<i64>> = stats
+            .tag_sets
+            .iter()
+            .filter(|tag_set| tag_set.contains(selected_id))
+            .collect();
+        for tag_set in &with_selected {
+            for id in tag_set.iter().filter(|id| *id != selected_id) {
+                *scores.entry(*id).or_default() +=
+                    CO_OCCURRENCE_WEIGHT * share(1, with_selected.len());
+            }
+        }
+    }
+
+    let title_tokens = tokens(title);
+    for tag in tags {
+        let overlap = tokens(tag.label()).intersection(&title_tokens).count();
+        if overlap > 0 {
+            *scores.entry(tag.id).or_default() += TITLE_TOKEN_WEIGHT * overlap as f64;
+        }
+    }
+
+    let mut ranked: Vec<(&Tag, f64)> = tags
+        .iter()
+        .filter(|tag| !selected_ids.contains(&tag.id))
+        .filter_map(|tag| Some((tag, *scores.get(&tag.id)?)))
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.name.cmp(&b.0.name)));
+    ranked
+        .into_iter()
+        .take(SUGGESTIONS_SHOWN)
+        .map(|(tag, _)| tag.clone())
+        .collect()
+}
+
+fn host_of(url: &Url) -> Option<String> {
+    let host = url.host_str()?.to_lowercase();
+    Some(host.strip_prefix("www.").unwrap_or(&host).to_string())
+}
+
+fn share(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+/// Lowercased words of the text, the short ones say too little.
+fn tokens(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| token.chars().count() >= 2)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags() -> Vec<Tag> {
+        vec![
+            Tag::new(1, "docs".to_string()),
+            Tag::new(2, "lang/rust".to_string()),
+            Tag::new(3, "reference".to_string()),
+            Tag::new(4, "go".to_string()),
+        ]
+    }
+
+    fn library() -> TagStats {
+        let tags = tags();
+        TagStats::new(&[
+            Bookmark::new(
+                1,
+                Some("The standard library".to_string()),
+                "https://www.doc.rust-lang.org/std/".to_string(),
+                Some(vec![tags[0].clone(), tags[2].clone()]),
+                "unread",
+                None,
+                None,
+            ),
+            Bookmark::new(
+                2,
+                Some("Go by example".to_string()),
+                "https://gobyexample.com/".to_string(),
+                Some(vec![tags[3].clone()]),
+                "unread",
+                None,
+                None,
+            ),
+        ])
+    }
+
+    fn names(tags: Vec<Tag>) -> Vec<String> {
+        tags.into_iter().map(|tag| tag.name).collect()
+    }
+
+    #[test]
+    fn ranks_the_same_site_above_the_title() {
+        let url = Url::parse("https://doc.rust-lang.org/book/").unwrap();
+        let suggested = suggest(&library(), &tags(), Some(&url), "Learning Rust", &[]);
+        assert_eq!(names(suggested), vec!["docs", "reference", "lang/rust"]);
+    }
+
+    #[test]
+    fn leaves_the_selected_tags_out() {
+        let tags = tags();
+        let url = Url::parse("https://doc.rust-lang.org/book/").unwrap();
+        let suggested = suggest(
+            &library(),
+            &tags,
+            Some(&url),
+            "Learning Rust",
+            &[tags[0].clone()],
+        );
+        assert_eq!(names(suggested), vec!["reference", "lang/rust"]);
+    }
+
+    #[test]
+    fn suggests_nothing_without_evidence() {
+        let url = Url::parse("https://tokio.rs/").unwrap();
+        assert!(suggest(&library(), &tags(), Some(&url), "Tokio", &[]).is_empty());
+        assert!(suggest(&TagStats::default(), &tags(), None, "", &[]).is_empty());
+    }
+}
@@ -0,0 +1,248 @@
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use url::Url;
+
+use crate::{
+    models::bookmarks::Bookmark,
+    settings::{Settings, TagRule},
+};
+
+/// A tagging rule from the settings, ready to be matched.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub tag: String,
+    domain: Option<String>,
+    url: Option<Regex>,
+    title_contains: Option<String>,
+}
+
+/// A tag that a rule adds to a bookmark.
+#[derive(Debug, Clone)]
+pub struct TagChange {
+    pub bookmark_id: i64,
+    pub title: Option<String>,
+    pub url: String,
+    pub tag: String,
+    /// Index of the rule that adds the tag.
+    pub rule: usize,
+}
+
+impl Rule {
+    pub fn new(rule: &TagRule) -> Result<Self> {
+        if rule.domain.is_none() && rule.url.is_none() && rule.title_contains.is_none() {
+            return Err(anyhow!(
+                "The rule for the tag {} has no conditions",
+                rule.tag
+            ));
+        }
+        let url = rule
+            .url
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .map_err(|error| anyhow!("The rule for the tag {}: {error}", rule.tag))?;
+
+        Ok(Self {
+            tag: rule.tag.clone(),
+            domain: rule.domain.as_ref().map(|domain| domain.to_lowercase()),
+            url,
+            title_contains: rule.title_contains.as_ref().map(|text| text.to_lowercase()),
+        })
+    }
+
+    pub fn matches(&self, title: Option<&str>, url: &Url) -> bool {
+        let domain_matches = self.domain.as_ref().map_or(true, |domain| {
+            url.host_str().is_some_and(|host| {
+                let host = host.to_lowercase();
+                host == *domain || host.ends_with(&format!(".{domain}"))
+            })
+        });
+        let url_matches = self
+            .url
+            .as_ref()
+            .map_or(true, |url_regex| url_regex.is_match(url.as_str()));
+        let title_matches = self.title_contains.as_ref().map_or(true, |text| {
+            title.is_some_and(|title| title.to_lowercase().contains(text.as_str()))
+        });
+
+        domain_matches && url_matches && title_matches
+    }
+
+    /// The conditions of the rule, e.g. `domain github.com -> code`.
+    pub fn describe(&self) -> String {
+        let mut conditions = Vec::new();
+        if let Some(domain) = &self.domain {
+            conditions.push(format!("domain {domain}"));
+        }
+        if let Some(url) = &self.url {
+            conditions.push(format!("link matches {url}"));
+        }
+        if let Some(text) = &self.title_contains {
+            conditions.push(format!("title contains \"{text}\""));
+        }
+        format!("{} -> {}", conditions.join(" and "), self.tag)
+    }
+}
+
+/// The rules of the settings, an invalid one fails them all, so it doesn't go unnoticed.
+pub fn load_rules() -> Result<Vec<Rule>> {
+    let Ok(settings) = Settings::get_configuration() else {
+        return Ok(vec![]);
+    };
+    settings.tagging.rules.iter().map(Rule::new).collect()
+}
+
+/// Tags the rules add to a bookmark with the title and the link, without repeats.
+pub fn tags_for(rules: &[Rule], title: Option<&str>, url: &Url) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for rule in rules.iter().filter(|rule| rule.matches(title, url)) {
+        if !tags.iter().any(|tag| tag.eq_ignore_ascii_case(&rule.tag)) {
+            tags.push(rule.tag.clone());
+        }
+    }
+    tags
+}
+
+/// Tags the rules would add to the bookmarks that don't have them yet.
+pub fn preview(rules: &[Rule], bookmarks: &[Bookmark]) -> Vec<TagChange> {
+    let mut changes = Vec::new();
+    for bookmark in bookmarks {
+        let url = &bookmark.url.inner;
+        let has_tag = |name: &str| {
+            bookmark
+                .tags
+                .iter()
+                .flatten()
+                .any(|tag| tag.name.eq_ignore_ascii_case(name))
+        };
+        for (index, rule) in rules.iter().enumerate() {
+            if has_tag(&rule.tag) || !rule.matches(bookmark.title.as_deref(), url) {
+                continue;
+            }
+            changes.push(TagChange {
+                bookmark_id: bookmark.id,
+                title: bookmark.title.clone(),
+                url: url.to_string(),
+                tag: rule.tag.clone(),
+                rule: index,
+            });
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::tags::Tag;
+
+    fn rule(tag: &str, domain: Option<&str>, url: Option<&str>, title: Option<&str>) -> TagRule {
+        TagRule {
+            tag: tag.to_string(),
+            domain: domain.map(ToString::to_string),
+            url: url.map(ToString::to_string),
+            title_contains: title.map(ToString::to_string),
+        }
+    }
+
+    fn link(url: &str) -> Url {
+        Url::parse(url).unwrap()
+    }
+
+    #[test]
+    fn matches_the_domain_with_its_subdomains() {
+        let rule = Rule::new(&rule("code", Some("GitHub.com"), None, None)).unwrap();
+
+        assert!(rule.matches(None, &link("https://github.com/rust-lang/rust")));
+        assert!(rule.matches(None, &link("https://gist.GITHUB.com/someone")));
+        assert!(!rule.matches(None, &link("https://notgithub.com/")));
+        assert!(!rule.matches(None, &link("https://github.com.evil.org/")));
+    }
+
+    #[test]
+    fn matches_the_title_ignoring_the_case() {
+        let rule = Rule::new(&rule("spec", None, None, Some("RFC"))).unwrap();
+
+        assert!(rule.matches(Some("The rfc 9110"), &link("https://example.com/")));
+        assert!(!rule.matches(Some("A spec"), &link("https://example.com/")));
+        assert!(!rule.matches(None, &link("https://example.com/rfc")));
+    }
+
+    #[test]
+    fn needs_all_the_conditions() {
+        let rule = Rule::new(&rule("docs", Some("rust-lang.org"), Some("/docs?/"), None)).unwrap();
+
+        assert!(rule.matches(None, &link("https://doc.rust-lang.org/doc/std")));
+        assert!(!rule.matches(None, &link("https://rust-lang.org/learn")));
+        assert!(!rule.matches(None, &link("https://example.com/docs/")));
+    }
+
+    #[test]
+    fn refuses_a_rule_without_conditions() {
+        let error = Rule::new(&rule("everything", None, None, None)).unwrap_err();
+        assert!(error.to_string().contains("everything"));
+    }
+
+    #[test]
+    fn refuses_a_bad_regex() {
+        let error = Rule::new(&rule("broken", None, Some("(unclosed"), None)).unwrap_err();
+        assert!(error.to_string().contains("broken"));
+    }
+
+    #[test]
+    fn adds_each_tag_once() {
+        let rules: Vec<Rule> = [
+            rule("code", Some("github.com"), None, None),
+            rule("Code", None, Some("/rust"), None),
+            rule("rust", None, None, Some("rust")),
+            rule("spec", None, None, Some("RFC")),
+        ]
+        .iter()
+        .map(|rule| Rule::new(rule).unwrap())
+        .collect();
+
+        let tags = tags_for(
+            &rules,
+            Some("The Rust repository"),
+            &link("https://github.com/rust-lang/rust"),
+        );
+        assert_eq!(tags, vec!["code", "rust"]);
+    }
+
+    #[test]
+    fn previews_the_missing_tags_only() {
+        let rules: Vec<Rule> = [
+            rule("code", Some("github.com"), None, None),
+            rule("rust", None, None, Some("rust")),
+        ]
+        .iter()
+        .map(|rule| Rule::new(rule).unwrap())
+        .collect();
+        let bookmarks = vec![
+            Bookmark::new(
+                1,
+                Some("Rust".to_string()),
+                "https://github.com/rust-lang/rust".to_string(),
+                Some(vec![Tag::new(1, "CODE".to_string())]),
+                "unread",
+                None,
+                None,
+            ),
+            Bookmark::new(
+                2,
+                Some("Go".to_string()),
+                "https://go.dev/".to_string(),
+                None,
+                "unread",
+                None,
+                None,
+            ),
+        ];
+
+        let changes: Vec<(i64, String, usize)> = preview(&rules, &bookmarks)
+            .into_iter()
+            .map(|change| (change.bookmark_id, change.tag, change.rule))
+            .collect();
+        assert_eq!(changes, vec![(1, "rust".to_string(), 1)]);
+    }
+}
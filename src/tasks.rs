@@ -0,0 +1,85 @@
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{event::Event, message::Message};
+
+const SPINNER: [char; 8] = ['⣾', '⣽', '⣻', '⢿', '⡿', '⣟', '⣯', '⣷'];
+
+/// Handle given to a running task to report its progress back.
+#[derive(Debug, Clone)]
+pub struct Progress {
+    id: u64,
+    sender: UnboundedSender<Event>,
+}
+
+impl Progress {
+    pub fn new(id: u64, sender: UnboundedSender<Event>) -> Self {
+        Self { id, sender }
+    }
+
+    pub fn report(&self, done: usize, total: usize) {
+        // the main loop is gone if sending fails, nothing to report to
+        let _ = self
+            .sender
+            .send(Event::Message(Box::new(Message::TaskProgress {
+                id: self.id,
+                done,
+                total,
+            })));
+    }
+}
+
+/// A background job as the UI sees it.
+#[derive(Debug)]
+pub struct Task {
+    pub id: u64,
+    pub label: String,
+    pub progress: Option<(usize, usize)>,
+}
+
+/// Bookkeeping of the running background jobs, the jobs themselves are run by
+/// [`crate::command::Executor`].
+#[derive(Debug, Default)]
+pub struct Tasks {
+    pub running: Vec<Task>,
+    next_id: u64,
+}
+
+impl Tasks {
+    /// Registers a new job and returns the id its messages are going to refer to.
+    pub fn start(&mut self, label: impl Into<String>) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.running.push(Task {
+            id,
+            label: label.into(),
+            progress: None,
+        });
+
+        id
+    }
+
+    pub fn is_busy(&self) -> bool {
+        !self.running.is_empty()
+    }
+
+    pub fn set_progress(&mut self, id: u64, done: usize, total: usize) {
+        if let Some(task) = self.running.iter_mut().find(|task| task.id == id) {
+            task.progress = Some((done, total));
+        }
+    }
+
+    /// Forgets about the finished task, returns `None` if it had been cancelled before.
+    pub fn finish(&mut self, id: u64) -> Option<Task> {
+        let index = self.running.iter().position(|task| task.id == id)?;
+        Some(self.running.remove(index))
+    }
+
+    pub fn cancel_all(&mut self) -> Vec<Task> {
+        self.running.drain(..).collect()
+    }
+
+    pub fn spinner(tick: usize) -> char {
+        SPINNER[tick % SPINNER.len()]
+    }
+}
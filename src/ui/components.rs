@@ -1,4 +1,16 @@
+pub mod auto_tagging;
+pub mod broken_links;
+pub mod collections;
 pub mod create;
+pub mod details;
 pub mod help;
+pub mod packs;
+pub mod palette;
+pub mod pinned;
+pub mod reading_list;
+pub mod saved_searches;
 pub mod scrolling;
 pub mod search;
+pub mod snippets;
+pub mod status;
+pub mod tag_merges;
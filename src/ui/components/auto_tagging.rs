@@ -0,0 +1,46 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem},
+};
+
+use crate::app::AutoTaggingParams;
+
+pub fn collect_tag_changes_items(params: &AutoTaggingParams) -> List<'static> {
+    let mut items = Vec::<ListItem>::new();
+
+    for change in &params.items.items {
+        items.push(ListItem::new(Line::from(vec![
+            Span::styled(
+                format!("+{} ", change.tag),
+                Style::default().fg(Color::Green),
+            ),
+            Span::styled(
+                change.title.clone().unwrap_or_default(),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(
+                format!(" {}", change.url),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ])));
+    }
+
+    let shown = match params.shown_rule.and_then(|rule| params.rules.get(rule)) {
+        Some(rule) => format!("rule {rule}"),
+        None => format!("all {} rules", params.rules.len()),
+    };
+    let title = format!(
+        "Tags to add: {}, {shown} (f next rule, Enter apply the shown, Del dismiss)",
+        params.items.items.len()
+    );
+    List::new(items)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .style(Style::default().fg(Color::White))
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">>")
+}
@@ -0,0 +1,62 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem},
+};
+
+use crate::app::BrokenLinksParams;
+
+pub fn collect_broken_links_items(params: &BrokenLinksParams) -> List<'static> {
+    let mut items = Vec::<ListItem>::new();
+
+    for broken_link in &params.items.items {
+        let check = &broken_link.check;
+        let (status, color) = match (&check.error, check.status_code) {
+            (Some(_), _) => ("ERR".to_string(), Color::Red),
+            (None, Some(code)) if code >= 400 => (code.to_string(), Color::Red),
+            (None, Some(code)) => (code.to_string(), Color::Yellow),
+            (None, None) => ("???".to_string(), Color::Red),
+        };
+
+        let mut line = vec![
+            Span::styled(format!("[{status}] "), Style::default().fg(color)),
+            Span::styled(
+                broken_link.title.clone().unwrap_or_default(),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::raw(format!(" {}", broken_link.url)),
+        ];
+        if let Some(final_url) = &check.final_url {
+            line.push(Span::styled(
+                format!(" -> {final_url}"),
+                Style::default().fg(Color::Cyan),
+            ));
+        }
+        if let Some(error) = &check.error {
+            line.push(Span::styled(
+                format!(" ({error})"),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        line.push(Span::styled(
+            format!(" checked {}", check.checked_at.format("%Y-%m-%d %H:%M")),
+            Style::default().fg(Color::DarkGray),
+        ));
+
+        items.push(ListItem::new(Line::from(line)));
+    }
+
+    let title = format!(
+        "Broken links: {} (Enter open, o open copy, Del delete, r retarget, c re-check)",
+        params.items.items.len()
+    );
+    List::new(items)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .style(Style::default().fg(Color::White))
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">>")
+}
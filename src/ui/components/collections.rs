@@ -0,0 +1,82 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+};
+
+use crate::app::{App, CollectionDraft, Mode};
+
+pub fn collect_collections_items(app: &App) -> List<'static> {
+    let moving = match &app.mode {
+        Mode::Collections(params) => params.moving.len(),
+        _ => 0,
+    };
+    let mut items = Vec::<ListItem>::new();
+
+    for row in &app.collections_items.items {
+        let indent = "  ".repeat(row.depth);
+        let marker = match &row.collection {
+            Some(collection) if row.has_children => {
+                if app.collapsed_collections.contains(&collection.id) {
+                    "▸ "
+                } else {
+                    "▾ "
+                }
+            }
+            _ => "  ",
+        };
+        let (name, id) = match &row.collection {
+            Some(collection) => (collection.name.clone(), Some(collection.id)),
+            None if moving > 0 => ("No collection".to_string(), None),
+            None => ("All bookmarks".to_string(), None),
+        };
+        // the collection the library is narrowed down to stands out
+        let style = if id == app.collection_filter {
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Yellow)
+        };
+        items.push(ListItem::new(Line::from(vec![
+            Span::raw(format!("{indent}{marker}")),
+            Span::styled(name, style),
+        ])));
+    }
+
+    let title = if moving > 0 {
+        format!("Move {moving} bookmarks to (Enter)")
+    } else {
+        "Collections".to_string()
+    };
+    let border_style = if matches!(app.mode, Mode::Collections(_)) {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    };
+    List::new(items)
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(border_style),
+        )
+        .style(Style::default().fg(Color::White))
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">>")
+}
+
+pub fn render_collection_name_input(draft: &CollectionDraft) -> Paragraph<'static> {
+    let title = if draft.id.is_some() {
+        "Rename (Enter saves)"
+    } else {
+        "New collection (Enter saves)"
+    };
+    Paragraph::new(draft.name.value().to_string())
+        .style(Style::default().fg(Color::Yellow))
+        .block(Block::default().borders(Borders::ALL).title(title))
+}
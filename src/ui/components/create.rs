@@ -4,11 +4,12 @@ use ratatui::{
     prelude::{Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
 };
 
 use crate::{
-    app::{ActiveWindow, CreationParams},
+    app::{ActiveWindow, CreationAreas, CreationParams},
+    notifications::Level,
     tui::Frame,
     ui::helpers::{centered_rect, set_cursor},
 };
@@ -16,15 +17,29 @@ use crate::{
 pub fn collect_creation_tags_items(params: &CreationParams) -> List<'static> {
     let mut tags = Vec::<ListItem>::new();
 
-    for tag in &params.tags_items.items {
-        tags.push(ListItem::new(Line::from(Span::styled(
-            tag.name.to_string(),
-            Style::default().fg(Color::Yellow),
-        ))));
+    for (index, tag) in params.tags_items.items.iter().enumerate() {
+        // the suggestions are numbered, so they're picked with a single key
+        let line = if index < params.suggested {
+            Line::from(vec![
+                Span::styled(format!("{} ", index + 1), Style::default().fg(Color::Cyan)),
+                Span::styled(tag.name.to_string(), Style::default().fg(Color::Green)),
+            ])
+        } else {
+            Line::from(Span::styled(
+                tag.name.to_string(),
+                Style::default().fg(Color::Yellow),
+            ))
+        };
+        tags.push(ListItem::new(line));
     }
 
+    let title = if params.suggested > 0 {
+        format!("Tags (1-{} picks a suggestion)", params.suggested)
+    } else {
+        "Tags".to_string()
+    };
     List::new(tags)
-        .block(Block::default().title("Tags").borders(Borders::ALL))
+        .block(Block::default().title(title).borders(Borders::ALL))
         .style(match params.active_window {
             ActiveWindow::Tags => Style::default().fg(Color::Yellow),
             _ => Style::default(),
@@ -37,7 +52,7 @@ pub fn collect_creation_tags_items(params: &CreationParams) -> List<'static> {
         .highlight_symbol(">>")
 }
 
-pub fn collect_creation_selected_tags(params: &CreationParams) -> List {
+pub fn collect_creation_selected_tags(params: &CreationParams) -> List<'_> {
     let mut tags = Vec::<ListItem>::new();
 
     for tag in &params.selected_tags {
@@ -50,13 +65,13 @@ pub fn collect_creation_selected_tags(params: &CreationParams) -> List {
     List::new(tags)
         .block(
             Block::default()
-                .title("Selected Tags")
+                .title("Selected Tags (Backspace takes back)")
                 .borders(Borders::ALL),
         )
         .style(Style::default())
 }
 
-pub fn render_creation_popup(params: &CreationParams, f: &mut Frame) -> Result<()> {
+pub fn render_creation_popup(params: &mut CreationParams, f: &mut Frame) -> Result<CreationAreas> {
     let popup_block = Block::default()
         .title("Create a brand-new bookmark")
         .borders(Borders::ALL)
@@ -89,6 +104,12 @@ pub fn render_creation_popup(params: &CreationParams, f: &mut Frame) -> Result<(
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
         .split(h_popup_chunks[1]);
 
+    // the name of a tag is typed above the list it narrows down
+    let tag_input_and_list = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(h_tags_chunks[0]);
+
     let title_text = params.title.to_string();
     let title_block = Paragraph::new(title_text)
         .style(match params.active_window {
@@ -114,22 +135,65 @@ pub fn render_creation_popup(params: &CreationParams, f: &mut Frame) -> Result<(
         ActiveWindow::Title => {
             set_cursor(&params.title, &v_inputs_chunks[0], f)?;
         }
-        ActiveWindow::Tags => {}
+        ActiveWindow::Tags => {
+            set_cursor(&params.tag_input, &tag_input_and_list[0], f)?;
+        }
     }
 
-    let status_text =
-        Paragraph::new(String::new()).block(Block::default().borders(Borders::ALL).title("Status"));
+    let mut status_lines: Vec<Line> = params
+        .validation
+        .notes
+        .iter()
+        .map(|(level, note)| {
+            let color = match level {
+                Level::Info => Color::Green,
+                Level::Warning => Color::Yellow,
+                Level::Error => Color::Red,
+            };
+            Line::from(Span::styled(note.to_string(), Style::default().fg(color)))
+        })
+        .collect();
+    if let Some(metadata) = params.metadata() {
+        if let Some(description) = &metadata.description {
+            status_lines.push(Line::from(Span::styled(
+                description.to_string(),
+                Style::default().fg(Color::Gray),
+            )));
+        }
+    }
+    let status_text = Paragraph::new(status_lines)
+        .wrap(Wrap { trim: true })
+        .block(Block::default().borders(Borders::ALL).title("Status"));
     f.render_widget(status_text, v_inputs_chunks[2]);
 
+    let tag_input = Paragraph::new(params.tag_input.value().to_string())
+        .style(match params.active_window {
+            ActiveWindow::Tags => Style::default().fg(Color::Yellow),
+            _ => Style::default(),
+        })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Find or create a tag"),
+        );
+    f.render_widget(tag_input, tag_input_and_list[0]);
+
     let tags_selection = collect_creation_tags_items(params);
     f.render_stateful_widget(
         tags_selection,
-        h_tags_chunks[0],
-        &mut params.tags_items.state.clone(),
+        tag_input_and_list[1],
+        &mut params.tags_items.state,
     );
 
     let selected_tags = collect_creation_selected_tags(params);
     f.render_widget(selected_tags, h_tags_chunks[1]);
 
-    Ok(())
+    Ok(CreationAreas {
+        popup: area,
+        title: v_inputs_chunks[0],
+        link: v_inputs_chunks[1],
+        tag_input: tag_input_and_list[0],
+        tags: tag_input_and_list[1],
+        selected_tags: h_tags_chunks[1],
+    })
 }
@@ -0,0 +1,144 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+use crate::{app::App, ui::helpers::pack_color};
+
+/// Everything known about the highlighted bookmark, the long lines are wrapped.
+pub fn render_details_panel(app: &App) -> Paragraph<'static> {
+    let block = Block::default()
+        .title("Details (Ctrl+D hides)")
+        .borders(Borders::ALL);
+    let Some(bookmark) = app.highlighted_bookmark() else {
+        return Paragraph::new(Span::styled(
+            "Highlight a bookmark to see its details",
+            Style::default().fg(Color::DarkGray),
+        ))
+        .block(block);
+    };
+
+    let label = |name: &str| Span::styled(format!("{name}: "), Style::default().fg(Color::Gray));
+    let value = |text: String| Span::styled(text, Style::default().fg(Color::Yellow));
+    let date = |date: chrono::DateTime<chrono::Utc>| date.format("%Y-%m-%d %H:%M").to_string();
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            bookmark.title.clone().unwrap_or_default(),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(vec![label("URL"), value(bookmark.url.inner.to_string())]),
+        Line::from(vec![
+            label("Domain"),
+            value(
+                bookmark
+                    .url
+                    .inner
+                    .host_str()
+                    .unwrap_or_default()
+                    .to_string(),
+            ),
+        ]),
+    ];
+
+    let mut tags_line = vec![label("Tags")];
+    for (index, tag) in bookmark.tags.iter().flatten().enumerate() {
+        if index > 0 {
+            tags_line.push(Span::raw(", "));
+        }
+        // the tags of the bookmarks come without their packs
+        let color = app
+            .tags
+            .iter()
+            .find(|known| known.id == tag.id)
+            .and_then(|known| app.pack_of(known))
+            .map_or(Color::Yellow, |pack| pack_color(pack.color));
+        tags_line.push(Span::styled(tag.name.clone(), Style::default().fg(color)));
+    }
+    lines.push(Line::from(tags_line));
+
+    if let Some(collection_id) = bookmark.collection_id {
+        lines.push(Line::from(vec![
+            label("Collection"),
+            value(app.collection_path(collection_id).join(" / ")),
+        ]));
+    }
+    lines.push(Line::from(vec![
+        label("Status"),
+        value(bookmark.status.as_str().to_string()),
+    ]));
+    lines.push(Line::from(vec![
+        label("Added"),
+        value(bookmark.created_at.map_or("unknown".to_string(), date)),
+    ]));
+    if let Some(pinned_at) = bookmark.pinned_at {
+        lines.push(Line::from(vec![label("Pinned"), value(date(pinned_at))]));
+    }
+
+    if let Some(description) = &bookmark.description {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::raw(description.clone())));
+    }
+
+    lines.push(Line::from(""));
+    if let Some(text) = app.page_texts.get(&bookmark.id) {
+        lines.push(Line::from(vec![
+            label("Page text"),
+            value(format!("{} words indexed", text.split_whitespace().count())),
+        ]));
+    }
+    // the rest is loaded separately, it may lag behind the cursor for a moment
+    match app
+        .details
+        .as_ref()
+        .filter(|details| details.bookmark_id == bookmark.id)
+    {
+        Some(details) => {
+            if let (Some(archived_at), Some(size)) = (details.archived_at, details.archive_size) {
+                lines.push(Line::from(vec![
+                    label("Archived"),
+                    value(format!("{}, {} KB", date(archived_at), size / 1024)),
+                ]));
+            }
+            match &details.check {
+                Some(check) => {
+                    let outcome = match (&check.error, check.status_code) {
+                        (Some(error), _) => error.clone(),
+                        (None, Some(code)) => format!("HTTP {code}"),
+                        (None, None) => "no response".to_string(),
+                    };
+                    let style = if check.is_broken() {
+                        Style::default().fg(Color::Red)
+                    } else {
+                        Style::default().fg(Color::Green)
+                    };
+                    lines.push(Line::from(vec![
+                        label("Checked"),
+                        value(date(check.checked_at)),
+                        Span::raw(", "),
+                        Span::styled(outcome, style),
+                    ]));
+                    if let Some(final_url) = &check.final_url {
+                        lines.push(Line::from(vec![
+                            label("Redirects to"),
+                            value(final_url.clone()),
+                        ]));
+                    }
+                }
+                None => lines.push(Line::from(vec![label("Checked"), value("never".into())])),
+            }
+        }
+        None => lines.push(Line::from(Span::styled(
+            "Loading...",
+            Style::default().fg(Color::DarkGray),
+        ))),
+    }
+
+    Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false })
+}
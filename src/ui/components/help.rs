@@ -13,7 +13,7 @@ pub fn render_help_popup(f: &mut Frame) {
         .borders(Borders::ALL)
         .style(Style::default().bg(Color::DarkGray));
 
-    let area = centered_rect(90, 50, f.size());
+    let area = centered_rect(90, 80, f.size());
     // Clear underlaying layer
     f.render_widget(Clear, area);
     f.render_widget(popup_block, area);
@@ -21,7 +21,7 @@ pub fn render_help_popup(f: &mut Frame) {
     let v_popup_chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(1)
-        .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
         .split(area);
 
     let h_popup_chunks = Layout::default()
@@ -49,6 +49,38 @@ pub fn render_help_popup(f: &mut Frame) {
             Span::styled(" Enter ", Style::new().yellow().italic()),
             Span::raw("in Tags window to filter bookmarks by the tag"),
         ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" Space/Right ", Style::new().yellow().italic()),
+            Span::raw(
+                "in Tags window to expand/collapse a tag, a parent tag filters by its children too",
+            ),
+        ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" g ", Style::new().yellow().italic()),
+            Span::raw("in Tags window to move a tag into the next pack"),
+        ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" Ctrl-F ", Style::new().yellow().italic()),
+            Span::raw("to search the page texts along with the titles"),
+        ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" Enter ", Style::new().yellow().italic()),
+            Span::raw("in Titles or Links window to submit the search and move to the results"),
+        ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" Up/Down ", Style::new().yellow().italic()),
+            Span::raw("in Titles or Links window to recall the submitted searches"),
+        ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" Ctrl+R ", Style::new().yellow().italic()),
+            Span::raw("to look through the submitted searches, again for an older match"),
+        ]),
     ];
     let search_panel = Paragraph::new(search_text)
         .block(search_block)
@@ -75,6 +107,48 @@ pub fn render_help_popup(f: &mut Frame) {
             Span::styled(" Del ", Style::new().yellow().italic()),
             Span::raw("to delete highlighted bookmark"),
         ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" Space ", Style::new().yellow().italic()),
+            Span::raw("to mark/unmark a bookmark"),
+        ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" a ", Style::new().yellow().italic()),
+            Span::raw("to archive the marked or highlighted pages"),
+        ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" o ", Style::new().yellow().italic()),
+            Span::raw("to open the archived copy of a page"),
+        ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" r/u/x ", Style::new().yellow().italic()),
+            Span::raw("to mark as read/unread/archived, opening marks as read"),
+        ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" p ", Style::new().yellow().italic()),
+            Span::raw("to pin/unpin the marked or highlighted bookmarks"),
+        ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" m ", Style::new().yellow().italic()),
+            Span::raw("to move the marked or highlighted bookmarks to a collection"),
+        ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" s/S ", Style::new().yellow().italic()),
+            Span::raw("to change/reverse the sort column, or click a header"),
+        ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" y/Y/t ", Style::new().yellow().italic()),
+            Span::raw(
+                "to copy the link/Markdown link/title of the marked or highlighted bookmarks",
+            ),
+        ]),
     ];
     let scrolling_panel = Paragraph::new(scrolling_text)
         .block(scrolling_block)
@@ -96,6 +170,27 @@ pub fn render_help_popup(f: &mut Frame) {
             Span::styled(" Enter ", Style::new().yellow().italic()),
             Span::raw("to create a bookmark"),
         ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" 1-5 ", Style::new().yellow().italic()),
+            Span::raw("in Tags window to pick a suggested tag, unless typing"),
+        ]),
+        Line::from(vec![Span::raw(
+            "Type in Tags window to find a tag, Enter creates it if there's none",
+        )]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" Enter ", Style::new().yellow().italic()),
+            Span::raw("on a selected tag or Backspace to deselect it"),
+        ]),
+        Line::from(vec![Span::raw(
+            "Leaving the Link field fetches the page title and description",
+        )]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" Ctrl+V ", Style::new().yellow().italic()),
+            Span::raw("to paste the link from the clipboard"),
+        ]),
     ];
     let creation_panel = Paragraph::new(creation_text)
         .block(creation_block)
@@ -115,7 +210,7 @@ pub fn render_help_popup(f: &mut Frame) {
         Line::from(vec![
             Span::raw("Press"),
             Span::styled(" Escape ", Style::new().yellow().italic()),
-            Span::raw("to close the app"),
+            Span::raw("to cancel background jobs or to close the app"),
         ]),
         Line::from(vec![
             Span::raw("Press"),
@@ -127,16 +222,93 @@ pub fn render_help_popup(f: &mut Frame) {
             Span::styled(" F1 ", Style::new().yellow().italic()),
             Span::raw("to close/reopen this window"),
         ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" F2 ", Style::new().yellow().italic()),
+            Span::raw("to open/close the message log"),
+        ]),
         Line::from(vec![
             Span::raw("Press"),
             Span::styled(" F3 ", Style::new().yellow().italic()),
             Span::raw("to switch to creation mode"),
         ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" F4 ", Style::new().yellow().italic()),
+            Span::raw("to show/hide the reading list (f switches unread/read/archived)"),
+        ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" F6 ", Style::new().yellow().italic()),
+            Span::raw("to show/hide broken links (Del deletes, r retargets, c re-checks, o opens the copy)"),
+        ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" F7 ", Style::new().yellow().italic()),
+            Span::raw("to check all the links"),
+        ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" F8 ", Style::new().yellow().italic()),
+            Span::raw("to index the texts of the pages for searching"),
+        ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" F9 ", Style::new().yellow().italic()),
+            Span::raw("to show/hide the tag packs (n new, e rename, c color, Del delete)"),
+        ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" F10 ", Style::new().yellow().italic()),
+            Span::raw("to show/hide near-duplicate tags (Enter merges, s swaps, Del dismisses)"),
+        ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" F11 ", Style::new().yellow().italic()),
+            Span::raw(
+                "to preview the tagging rules (f picks a rule, Enter applies, Del dismisses)",
+            ),
+        ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" Ctrl+D ", Style::new().yellow().italic()),
+            Span::raw("to show/hide the details of the highlighted bookmark"),
+        ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" Ctrl+O ", Style::new().yellow().italic()),
+            Span::raw(
+                "to browse the collections (Enter filters, n new, e rename, [/] reorder, Del delete)",
+            ),
+        ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" Ctrl+S ", Style::new().yellow().italic()),
+            Span::raw("to save the search, "),
+            Span::styled("Ctrl+L", Style::new().yellow().italic()),
+            Span::raw(" to run a saved one (e rename, Del delete)"),
+        ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" Ctrl+P ", Style::new().yellow().italic()),
+            Span::raw("to find any action, a tag to filter by or a recent bookmark by name"),
+        ]),
+        Line::from(vec![
+            Span::raw("Press"),
+            Span::styled(" 1-9 ", Style::new().yellow().italic()),
+            Span::raw("to open a pinned bookmark, Alt+1-9 while typing"),
+        ]),
         Line::from(vec![
             Span::raw("Press"),
             Span::styled(" F12 ", Style::new().yellow().italic()),
             Span::raw("to reset searching state"),
         ]),
+        Line::from(vec![
+            Span::styled("Click", Style::new().yellow().italic()),
+            Span::raw(" selects a row or a panel, "),
+            Span::styled("double-click", Style::new().yellow().italic()),
+            Span::raw(" opens it, click on a selected tag removes it"),
+        ]),
     ];
     let general_panel = Paragraph::new(general_text)
         .block(general_block)
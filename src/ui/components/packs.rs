@@ -0,0 +1,55 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+};
+
+use crate::{
+    app::{PackDraft, PacksParams},
+    models::tags::Tag,
+    ui::helpers::pack_color,
+};
+
+pub fn collect_packs_items(params: &PacksParams, tags: &[Tag]) -> List<'static> {
+    let mut items = Vec::<ListItem>::new();
+
+    for pack in &params.items.items {
+        let count = tags
+            .iter()
+            .filter(|tag| tag.pack_id == Some(pack.id))
+            .count();
+        items.push(ListItem::new(Line::from(vec![
+            Span::styled("■ ", Style::default().fg(pack_color(pack.color))),
+            Span::styled(pack.name.clone(), Style::default().fg(Color::Yellow)),
+            Span::styled(
+                format!(" {count} tags, hue {}°", pack.color),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ])));
+    }
+
+    let title = format!(
+        "Tag packs: {} (n new, e rename, c color, Del delete, g in Tags window moves a tag)",
+        params.items.items.len()
+    );
+    List::new(items)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .style(Style::default().fg(Color::White))
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">>")
+}
+
+pub fn render_pack_name_input(draft: &PackDraft) -> Paragraph<'static> {
+    let title = if draft.id.is_some() {
+        "Rename the pack (Enter saves, Esc cancels)"
+    } else {
+        "New pack (Enter saves, Esc cancels)"
+    };
+    Paragraph::new(draft.name.value().to_string())
+        .style(Style::default().fg(Color::Yellow))
+        .block(Block::default().borders(Borders::ALL).title(title))
+}
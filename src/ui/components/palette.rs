@@ -0,0 +1,63 @@
+use anyhow::Result;
+use ratatui::{
+    prelude::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+};
+
+use crate::{
+    app::PaletteParams,
+    tui::Frame,
+    ui::helpers::{centered_rect, set_cursor},
+};
+
+pub fn render_palette_popup(palette: &mut PaletteParams, f: &mut Frame) -> Result<()> {
+    let area = centered_rect(60, 60, f.size());
+    // clear underlaying layer first
+    f.render_widget(Clear, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(area);
+
+    let query = Paragraph::new(palette.query.value().to_string())
+        .style(Style::default().fg(Color::Yellow))
+        .block(
+            Block::default()
+                .title("Command palette (Enter runs, Esc closes)")
+                .borders(Borders::ALL)
+                .style(Style::default().bg(Color::DarkGray)),
+        );
+    f.render_widget(query, chunks[0]);
+    set_cursor(&palette.query, &chunks[0], f)?;
+
+    let items: Vec<ListItem> = palette
+        .items
+        .items
+        .iter()
+        .map(|entry| {
+            let mut line = vec![Span::raw(entry.title.clone())];
+            if let Some(binding) = entry.binding() {
+                line.push(Span::styled(
+                    format!("  {binding}"),
+                    Style::new().yellow().italic(),
+                ));
+            }
+            ListItem::new(Line::from(line))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().bg(Color::DarkGray)),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol(">>");
+    f.render_stateful_widget(list, chunks[1], &mut palette.items.state);
+
+    Ok(())
+}
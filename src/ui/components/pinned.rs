@@ -0,0 +1,46 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::app::App;
+
+/// Amount of pinned bookmarks that have a number key.
+pub const PINNED_SHOWN: usize = 9;
+
+pub fn render_pinned_panel(app: &App) -> Paragraph<'static> {
+    let lines: Vec<Line> = app
+        .pinned
+        .iter()
+        .take(PINNED_SHOWN)
+        .enumerate()
+        .map(|(index, bookmark)| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{} ", index + 1),
+                    Style::default()
+                        .fg(Color::Magenta)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    bookmark.title.clone().unwrap_or_default(),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled(
+                    format!(" {}", String::from(bookmark.url.clone())),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ])
+        })
+        .collect();
+
+    let mut title = "Pinned (1-9 or Alt+1-9 opens)".to_string();
+    if app.pinned.len() > PINNED_SHOWN {
+        title.push_str(&format!(
+            ", {} more not shown",
+            app.pinned.len() - PINNED_SHOWN
+        ));
+    }
+    Paragraph::new(lines).block(Block::default().title(title).borders(Borders::ALL))
+}
@@ -0,0 +1,57 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem},
+};
+
+use crate::app::ReadingListParams;
+
+pub fn collect_reading_list_items(params: &ReadingListParams) -> List<'static> {
+    let mut items = Vec::<ListItem>::new();
+
+    for bookmark in &params.items.items {
+        let added = bookmark
+            .created_at
+            .map(|created_at| created_at.format("%Y-%m-%d").to_string())
+            .unwrap_or_else(|| "----------".to_string());
+        let tags = bookmark
+            .tags
+            .iter()
+            .flatten()
+            .map(|tag| tag.name.clone())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut line = vec![
+            Span::styled(format!("{added} "), Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                bookmark.title.clone().unwrap_or_default(),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::raw(format!(" {}", String::from(bookmark.url.clone()))),
+        ];
+        if !tags.is_empty() {
+            line.push(Span::styled(
+                format!(" [{tags}]"),
+                Style::default().fg(Color::Cyan),
+            ));
+        }
+
+        items.push(ListItem::new(Line::from(line)));
+    }
+
+    let title = format!(
+        "Reading list, {}: {} (Enter open, r read, u unread, x archive, f switch, Del delete)",
+        params.shown.as_str(),
+        params.items.items.len()
+    );
+    List::new(items)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .style(Style::default().fg(Color::White))
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">>")
+}
@@ -0,0 +1,69 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+};
+use tui_input::Input;
+
+use crate::{app::SavedSearchesParams, models::saved_searches::SavedSearch};
+
+pub fn collect_saved_searches_items(params: &SavedSearchesParams) -> List<'static> {
+    let items: Vec<ListItem> = params
+        .items
+        .items
+        .iter()
+        .map(|search| {
+            ListItem::new(Line::from(vec![
+                Span::styled(search.name.clone(), Style::default().fg(Color::Yellow)),
+                Span::styled(
+                    format!(" {}", describe(search)),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]))
+        })
+        .collect();
+
+    let title = format!(
+        "Saved searches: {} (Enter runs, e rename, Del delete, Ctrl+S saves the current one)",
+        params.items.items.len()
+    );
+    List::new(items)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .style(Style::default().fg(Color::White))
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">>")
+}
+
+/// What the search is made of, e.g. "title: rust, tags: async, sorted by Added ▼".
+fn describe(search: &SavedSearch) -> String {
+    let mut parts = vec![];
+    if !search.title_query.is_empty() {
+        parts.push(format!("title: {}", search.title_query));
+    }
+    if !search.link_query.is_empty() {
+        parts.push(format!("link: {}", search.link_query));
+    }
+    if !search.tags.is_empty() {
+        let names: Vec<&str> = search.tags.iter().map(|tag| tag.name.as_str()).collect();
+        parts.push(format!("tags: {}", names.join(", ")));
+    }
+    if let Some(column) = search.sort_column {
+        let arrow = if search.sort_descending { "▼" } else { "▲" };
+        parts.push(format!("sorted by {} {arrow}", column.header()));
+    }
+    parts.join(", ")
+}
+
+pub fn render_saved_search_name_input(name: &Input) -> Paragraph<'static> {
+    Paragraph::new(name.value().to_string())
+        .style(Style::default().fg(Color::Yellow))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Rename the saved search (Enter saves, Esc cancels)"),
+        )
+}
@@ -1,80 +1,202 @@
 use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem},
+    widgets::{Block, Borders, Cell, HighlightSpacing, Row, Table},
 };
 
-use crate::app::App;
+use std::{collections::HashMap, ops::Range};
 
-pub fn collect_list_items(app: &App) -> Vec<List<'static>> {
-    let bookmarks = app.bookmarks_items.items.clone();
-    let titles_header = format!(
-        "Titles: {}/{}",
+use crate::{
+    app::App,
+    models::bookmarks::{Bookmark, ReadingStatus},
+    settings::{Column, ListColumn},
+    ui::helpers::pack_color,
+};
+
+const HIGHLIGHT_SYMBOL: &str = ">>";
+// Blank characters between two columns
+const COLUMN_SPACING: u16 = 1;
+
+/// Widths of the columns as the table takes them.
+pub fn column_widths(columns: &[ListColumn]) -> Vec<Constraint> {
+    columns
+        .iter()
+        .map(|shown| Constraint::Percentage(shown.width))
+        .collect()
+}
+
+/// Indexes of the bookmarks that fit into the table rendered into `area`, scrolled
+/// the way the table would scroll them to keep the selected one in view.
+pub fn visible_rows(app: &mut App, area: Rect) -> Range<usize> {
+    // the borders and the header aren't rows
+    let height = usize::from(area.height.saturating_sub(3));
+    let len = app.bookmarks_items.items.len();
+    let state = &mut app.bookmarks_items.state;
+
+    let mut offset = state.offset().min(len.saturating_sub(1));
+    if let Some(selected) = state.selected() {
+        if selected >= offset + height {
+            offset = (selected + 1).saturating_sub(height);
+        } else if selected < offset {
+            offset = selected;
+        }
+    }
+    *state.offset_mut() = offset;
+
+    offset..(offset + height).min(len)
+}
+
+/// The table of the bookmarks in `rows`, the rest aren't built at all as a library
+/// may hold far more of them than fit on the screen.
+pub fn render_bookmarks_table<'a>(
+    app: &App,
+    widths: &'a [Constraint],
+    rows: Range<usize>,
+) -> Table<'a> {
+    let mut title = format!(
+        "Bookmarks: {}/{}",
         app.bookmarks_items.items.len(),
         app.bookmarks.len()
     );
-
-    let mut titles = Vec::<ListItem>::new();
-    let mut links = Vec::<ListItem>::new();
-    let mut tags = Vec::<ListItem>::new();
-
-    for bookmark in bookmarks {
-        // titles
-        let title = bookmark.title.unwrap_or(String::new());
-        titles.push(ListItem::new(Line::from(Span::styled(
-            title.to_string(),
-            Style::default().fg(Color::Yellow),
-        ))));
-
-        // links
-        let url: String = bookmark.url.into();
-        links.push(ListItem::new(Line::from(Span::styled(
-            url.to_string(),
-            Style::default().fg(Color::Yellow),
-        ))));
-
-        // tags
-        let parsed_tags = bookmark
-            .tags
-            .unwrap_or(vec![])
-            .iter()
-            .map(|tag| tag.name.clone())
-            .collect::<Vec<String>>()
-            .join(", ");
-
-        tags.push(ListItem::new(Line::from(Span::styled(
-            parsed_tags.to_string(),
-            Style::default().fg(Color::Yellow),
-        ))));
+    if !app.marked.is_empty() {
+        title.push_str(&format!(", marked: {}", app.marked.len()));
     }
 
-    vec![
-        List::new(titles)
-            .block(Block::default().title(titles_header).borders(Borders::ALL))
-            .style(Style::default().fg(Color::White))
-            .highlight_style(
-                Style::default()
-                    .bg(Color::DarkGray)
-                    .add_modifier(Modifier::BOLD),
-            )
-            .highlight_symbol(">>"),
-        List::new(links)
-            .block(Block::default().title("Links").borders(Borders::ALL))
-            .style(Style::default().fg(Color::White))
-            .highlight_style(
-                Style::default()
-                    .bg(Color::DarkGray)
-                    .add_modifier(Modifier::BOLD),
+    // the tags of the bookmarks come without their packs
+    let tag_colors: HashMap<i64, Color> = app
+        .tags
+        .iter()
+        .filter_map(|tag| Some((tag.id, pack_color(app.pack_of(tag)?.color))))
+        .collect();
+
+    let header = Row::new(app.columns.iter().map(|shown| {
+        let arrow = match app.sort {
+            Some(sort) if sort.column == shown.column && sort.descending => " ▼",
+            Some(sort) if sort.column == shown.column => " ▲",
+            _ => "",
+        };
+        Cell::from(format!("{}{arrow}", shown.column.header()))
+    }))
+    .style(
+        Style::default()
+            .fg(Color::Gray)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let rows = app
+        .bookmarks_items
+        .items
+        .iter()
+        .skip(rows.start)
+        .take(rows.len())
+        .map(|bookmark| {
+            Row::new(
+                app.columns
+                    .iter()
+                    .map(|shown| cell(app, bookmark, shown.column, &tag_colors)),
             )
-            .highlight_symbol(">>"),
-        List::new(tags)
-            .block(Block::default().title("Tags").borders(Borders::ALL))
-            .style(Style::default().fg(Color::White))
-            .highlight_style(
+        });
+
+    Table::new(rows)
+        .header(header)
+        .widths(widths)
+        .column_spacing(COLUMN_SPACING)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .style(Style::default().fg(Color::White))
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(HIGHLIGHT_SYMBOL)
+        // the columns stay in place, so the headers can be clicked
+        .highlight_spacing(HighlightSpacing::Always)
+}
+
+fn cell(
+    app: &App,
+    bookmark: &Bookmark,
+    column: Column,
+    tag_colors: &HashMap<i64, Color>,
+) -> Cell<'static> {
+    let plain = |text: String| Cell::from(Span::styled(text, Style::default().fg(Color::Yellow)));
+    let date = |date: Option<chrono::DateTime<chrono::Utc>>| {
+        plain(date.map_or(String::new(), |date| date.format("%Y-%m-%d").to_string()))
+    };
+
+    match column {
+        Column::Title => {
+            let mut line = vec![];
+            if app.marked.contains(&bookmark.id) {
+                line.push(Span::styled("* ", Style::default().fg(Color::Magenta)));
+            }
+            // what's waiting to be read stands out
+            let style = if bookmark.status == ReadingStatus::Unread {
                 Style::default()
-                    .bg(Color::DarkGray)
-                    .add_modifier(Modifier::BOLD),
-            )
-            .highlight_symbol(">>"),
-    ]
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Yellow)
+            };
+            line.push(Span::styled(
+                bookmark.title.clone().unwrap_or_default(),
+                style,
+            ));
+            if app.archived.contains(&bookmark.id) {
+                line.push(Span::styled(
+                    " [offline copy]",
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            Cell::from(Line::from(line))
+        }
+        Column::Domain => plain(
+            bookmark
+                .url
+                .inner
+                .host_str()
+                .unwrap_or_default()
+                .to_string(),
+        ),
+        Column::Url => plain(bookmark.url.inner.to_string()),
+        Column::Tags => {
+            let mut line = vec![];
+            for (index, tag) in bookmark.tags.iter().flatten().enumerate() {
+                if index > 0 {
+                    line.push(Span::styled(", ", Style::default().fg(Color::Yellow)));
+                }
+                let color = tag_colors.get(&tag.id).copied().unwrap_or(Color::Yellow);
+                line.push(Span::styled(tag.name.clone(), Style::default().fg(color)));
+            }
+            Cell::from(Line::from(line))
+        }
+        Column::Added => date(bookmark.created_at),
+        Column::Opened => date(bookmark.opened_at),
+    }
+}
+
+/// The column whose header is at the clicked terminal column of the table rendered into `area`.
+pub fn column_at(area: &Rect, columns: &[ListColumn], x: u16) -> Option<Column> {
+    // the same split the table does: the highlight symbol, then the columns with gaps between them
+    let mut constraints = vec![Constraint::Length(
+        u16::try_from(HIGHLIGHT_SYMBOL.chars().count()).unwrap_or_default(),
+    )];
+    for width in column_widths(columns) {
+        constraints.push(width);
+        constraints.push(Constraint::Length(COLUMN_SPACING));
+    }
+    let inner = Rect::new(area.x + 1, area.y, area.width.saturating_sub(2), 1);
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(constraints)
+        .split(inner);
+
+    chunks
+        .iter()
+        .skip(1)
+        .step_by(2)
+        .zip(columns)
+        .find(|(chunk, _)| x >= chunk.x && x < chunk.x + chunk.width + COLUMN_SPACING)
+        .map(|(_, shown)| shown.column)
 }
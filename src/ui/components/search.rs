@@ -4,19 +4,34 @@ use ratatui::{
     widgets::{Block, Borders, List, ListItem, Paragraph},
 };
 
-use crate::app::{ActiveWindow, App, Mode};
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    app::{ActiveWindow, App, Mode, ReverseSearch, SearchingParams, TagsRow},
+    models::search_history::SearchHistoryEntry,
+    ui::helpers::pack_color,
+};
 
 // TODO: unbloat this function
-pub fn render_titles_search_panel(app: &App) -> Paragraph {
+pub fn render_titles_search_panel(app: &App) -> Paragraph<'_> {
     let block = match &app.mode {
+        Mode::Search(SearchingParams {
+            reverse_search: Some(reverse_search),
+            ..
+        }) => render_reverse_search_panel(app, reverse_search),
         Mode::Search(searhing_params) => {
             let text = searhing_params.title.to_string();
+            let title = if searhing_params.in_content {
+                "Titles and page texts"
+            } else {
+                "Titles"
+            };
             Paragraph::new(text)
                 .style(match searhing_params.active_window {
                     ActiveWindow::Title => Style::default().fg(Color::Yellow),
                     _ => Style::default(),
                 })
-                .block(Block::default().borders(Borders::ALL).title("Titles"))
+                .block(Block::default().borders(Borders::ALL).title(title))
         }
         _ => Paragraph::new("")
             .style(Style::default())
@@ -26,7 +41,48 @@ pub fn render_titles_search_panel(app: &App) -> Paragraph {
     block
 }
 
-pub fn render_links_search_panel(app: &App) -> Paragraph {
+/// The query looked for through the search history along with the match.
+fn render_reverse_search_panel<'a>(app: &'a App, reverse_search: &ReverseSearch) -> Paragraph<'a> {
+    let found = match reverse_search
+        .matched
+        .and_then(|index| app.search_history.get(index))
+    {
+        Some(search) => Span::styled(
+            format!("  {}", describe(search)),
+            Style::default().fg(Color::Gray),
+        ),
+        None => Span::styled("  nothing found", Style::default().fg(Color::Red)),
+    };
+
+    Paragraph::new(Line::from(vec![
+        Span::raw(reverse_search.query.value().to_string()),
+        found,
+    ]))
+    .style(Style::default().fg(Color::Yellow))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Search history (Ctrl+R older, Enter picks, Esc cancels)"),
+    )
+}
+
+/// What the search is made of, e.g. "title: rust, tags: async".
+fn describe(search: &SearchHistoryEntry) -> String {
+    let mut parts = vec![];
+    if !search.title_query.is_empty() {
+        parts.push(format!("title: {}", search.title_query));
+    }
+    if !search.link_query.is_empty() {
+        parts.push(format!("link: {}", search.link_query));
+    }
+    if !search.tags.is_empty() {
+        let names: Vec<&str> = search.tags.iter().map(|tag| tag.name.as_str()).collect();
+        parts.push(format!("tags: {}", names.join(", ")));
+    }
+    parts.join(", ")
+}
+
+pub fn render_links_search_panel(app: &App) -> Paragraph<'_> {
     let block = match &app.mode {
         Mode::Search(searhing_params) => {
             let text = searhing_params.link.to_string();
@@ -47,16 +103,63 @@ pub fn render_links_search_panel(app: &App) -> Paragraph {
 
 pub fn collect_tags_items(app: &App) -> List<'static> {
     let mut tags = Vec::<ListItem>::new();
+    let parents: HashMap<i64, i64> = app
+        .tags
+        .iter()
+        .filter_map(|tag| Some((tag.id, tag.parent_id?)))
+        .collect();
+    let parent_ids: HashSet<i64> = parents.values().copied().collect();
 
-    for tag in &app.tags_items.items {
+    // the tags are indented under the headers of their packs
+    let pack_indent = if app.packs.is_empty() { "" } else { "  " };
+
+    for row in &app.tags_items.items {
+        let tag = match row {
+            TagsRow::Pack(pack) => {
+                let id = pack.as_ref().map(|pack| pack.id);
+                let marker = if app.collapsed_packs.contains(&id) {
+                    "▸ "
+                } else {
+                    "▾ "
+                };
+                let (name, color) = match pack {
+                    Some(pack) => (pack.name.as_str(), pack_color(pack.color)),
+                    None => ("Without a pack", Color::Gray),
+                };
+                tags.push(ListItem::new(Line::from(Span::styled(
+                    format!("{marker}{name}"),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ))));
+                continue;
+            }
+            TagsRow::Tag(tag) => tag,
+        };
+
+        let depth = std::iter::successors(parents.get(&tag.id), |id| parents.get(id))
+            .take(parents.len())
+            .count();
+        let marker = if !parent_ids.contains(&tag.id) {
+            "  "
+        } else if app.expanded_tags.contains(&tag.id) {
+            "▾ "
+        } else {
+            "▸ "
+        };
+        let color = app
+            .pack_of(tag)
+            .map_or(Color::Yellow, |pack| pack_color(pack.color));
         tags.push(ListItem::new(Line::from(Span::styled(
-            tag.name.to_string(),
-            Style::default().fg(Color::Yellow),
+            format!("{pack_indent}{}{marker}{}", "  ".repeat(depth), tag.label()),
+            Style::default().fg(color),
         ))));
     }
 
     List::new(tags)
-        .block(Block::default().title("Tags").borders(Borders::ALL))
+        .block(
+            Block::default()
+                .title("Tags (Space/Right expands)")
+                .borders(Borders::ALL),
+        )
         .style(if let Mode::Search(params) = &app.mode {
             match params.active_window {
                 ActiveWindow::Tags => Style::default().fg(Color::Yellow),
@@ -73,7 +176,7 @@ pub fn collect_tags_items(app: &App) -> List<'static> {
         .highlight_symbol(">>")
 }
 
-pub fn collect_selected_tags_items(app: &App) -> List {
+pub fn collect_selected_tags_items(app: &App) -> List<'_> {
     let mut tags = Vec::<ListItem>::new();
 
     for tag in &app.tags_filter {
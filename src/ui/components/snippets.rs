@@ -0,0 +1,54 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+use crate::{
+    app::{App, SearchingParams},
+    fulltext::Snippet,
+};
+
+/// Matches in the page texts of the found bookmarks, in the order of the results.
+pub fn render_snippets_panel<'a>(app: &'a App, params: &'a SearchingParams) -> Paragraph<'a> {
+    let mut lines = Vec::new();
+    for bookmark in &app.bookmarks_items.items {
+        let Some(snippet) = params.snippets.get(&bookmark.id) else {
+            continue;
+        };
+
+        lines.push(Line::from(Span::styled(
+            bookmark.title.clone().unwrap_or_default(),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )));
+        lines.push(highlighted(snippet));
+        lines.push(Line::from(""));
+    }
+
+    let title = format!("Page text matches: {}", params.snippets.len());
+    Paragraph::new(lines)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .wrap(Wrap { trim: true })
+}
+
+fn highlighted(snippet: &Snippet) -> Line<'_> {
+    let mut spans = Vec::new();
+    let mut position = 0;
+    for range in &snippet.highlights {
+        // the words of the query may overlap
+        if range.start < position {
+            continue;
+        }
+        spans.push(Span::raw(&snippet.text[position..range.start]));
+        spans.push(Span::styled(
+            &snippet.text[range.clone()],
+            Style::default().fg(Color::Black).bg(Color::Yellow),
+        ));
+        position = range.end;
+    }
+    spans.push(Span::raw(&snippet.text[position..]));
+
+    Line::from(spans)
+}
@@ -0,0 +1,107 @@
+use chrono::Local;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+};
+
+use crate::{
+    app::{App, Mode},
+    notifications::{Level, Notification, Notifications},
+    tasks::Tasks,
+    tui::Frame,
+    ui::helpers::{centered_rect, progress_bar},
+};
+
+fn level_style(level: Level) -> Style {
+    match level {
+        Level::Info => Style::default().fg(Color::Green),
+        Level::Warning => Style::default().fg(Color::Yellow),
+        Level::Error => Style::default().fg(Color::Red),
+    }
+}
+
+fn notification_spans(notification: &Notification) -> Vec<Span<'static>> {
+    let level = match notification.level {
+        Level::Info => "INFO",
+        Level::Warning => "WARN",
+        Level::Error => "ERROR",
+    };
+
+    vec![
+        Span::styled(
+            notification.at.format("%H:%M:%S ").to_string(),
+            Style::default().fg(Color::DarkGray),
+        ),
+        Span::styled(
+            format!("{level}: "),
+            level_style(notification.level).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(notification.text.clone(), level_style(notification.level)),
+    ]
+}
+
+pub fn render_status_bar(app: &App) -> Paragraph<'static> {
+    let mode_name = match app.mode {
+        Mode::Search(_) => "Searching Mode",
+        Mode::Create(_) => "Creation Mode",
+        Mode::Scrolling => "Scrolling Mode",
+        Mode::BrokenLinks(_) => "Broken Links",
+        Mode::ReadingList(_) => "Reading List",
+        Mode::Packs(_) => "Tag Packs",
+        Mode::TagMerges(_) => "Tag Merges",
+        Mode::AutoTagging(_) => "Auto Tagging",
+        Mode::Collections(_) => "Collections",
+        Mode::SavedSearches(_) => "Saved Searches",
+    };
+    let mut status = vec![Span::styled(mode_name, Style::default().fg(Color::Green))];
+
+    for task in &app.tasks.running {
+        status.push(Span::raw(" | "));
+        status.push(Span::styled(
+            format!("{} {}", Tasks::spinner(app.tick), task.label),
+            Style::default().fg(Color::Yellow),
+        ));
+        if let Some((done, total)) = task.progress {
+            status.push(Span::raw(format!(" {}", progress_bar(done, total))));
+        }
+    }
+    if app.tasks.is_busy() {
+        status.push(Span::styled(
+            " (Esc to cancel)",
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    if let Some(notification) = app.notifications.current(Local::now()) {
+        status.push(Span::raw(" | "));
+        status.extend(notification_spans(notification));
+    }
+
+    Paragraph::new(Line::from(status)).block(Block::default().borders(Borders::ALL))
+}
+
+pub fn render_log_popup(notifications: &mut Notifications, f: &mut Frame) {
+    let area = centered_rect(80, 60, f.size());
+    // clear underlaying layer first
+    f.render_widget(Clear, area);
+
+    let items: Vec<ListItem> = notifications
+        .log
+        .items
+        .iter()
+        .map(|notification| ListItem::new(Line::from(notification_spans(notification))))
+        .collect();
+
+    let log = List::new(items)
+        .block(
+            Block::default()
+                .title("Messages (F2/Esc to close)")
+                .borders(Borders::ALL)
+                .style(Style::default().bg(Color::DarkGray)),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol(">>");
+
+    f.render_stateful_widget(log, area, &mut notifications.log.state);
+}
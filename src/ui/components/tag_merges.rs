@@ -0,0 +1,47 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem},
+};
+
+use crate::app::TagMergesParams;
+
+pub fn collect_tag_merges_items(params: &TagMergesParams) -> List<'static> {
+    let mut items = Vec::<ListItem>::new();
+
+    for candidate in &params.items.items {
+        items.push(ListItem::new(Line::from(vec![
+            Span::styled(
+                candidate.tag.name.clone(),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(
+                format!(" ({} bookmarks)", candidate.uses),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::raw(" -> "),
+            Span::styled(
+                candidate.into.name.clone(),
+                Style::default().fg(Color::Green),
+            ),
+            Span::styled(
+                format!(" ({} bookmarks)", candidate.into_uses),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ])));
+    }
+
+    let title = format!(
+        "Near-duplicate tags: {} (Enter merge, s swap, Del dismiss)",
+        params.items.items.len()
+    );
+    List::new(items)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .style(Style::default().fg(Color::White))
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">>")
+}
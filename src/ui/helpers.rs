@@ -1,5 +1,8 @@
 use anyhow::Result;
-use ratatui::prelude::{Constraint, Direction, Layout, Rect};
+use ratatui::{
+    prelude::{Constraint, Direction, Layout, Rect},
+    style::Color,
+};
 use tui_input::Input;
 
 use crate::tui::Frame;
@@ -41,3 +44,60 @@ pub fn set_cursor(input: &Input, place_to_render: &Rect, f: &mut Frame) -> Resul
     );
     Ok(())
 }
+
+pub fn is_inside(area: &Rect, column: u16, row: u16) -> bool {
+    column >= area.x && column < area.x + area.width && row >= area.y && row < area.y + area.height
+}
+
+/// Maps a clicked terminal row onto an index of a bordered list
+/// that is rendered into the given area and scrolled by `offset` items.
+pub fn list_index_at(area: &Rect, offset: usize, column: u16, row: u16) -> Option<usize> {
+    // Skip the borders, only the inner part of the block contains items
+    let inner = Rect::new(
+        area.x + 1,
+        area.y + 1,
+        area.width.saturating_sub(2),
+        area.height.saturating_sub(2),
+    );
+    if !is_inside(&inner, column, row) {
+        return None;
+    }
+
+    Some(offset + usize::from(row - inner.y))
+}
+
+/// Text progress bar like `[#####-----] 5/10`.
+pub fn progress_bar(done: usize, total: usize) -> String {
+    const WIDTH: usize = 20;
+    let filled = (done * WIDTH)
+        .checked_div(total)
+        .unwrap_or(WIDTH)
+        .min(WIDTH);
+
+    format!(
+        "[{}{}] {done}/{total}",
+        "#".repeat(filled),
+        "-".repeat(WIDTH - filled)
+    )
+}
+
+/// Bright enough color of the given hue to be read on a dark background.
+pub fn pack_color(hue: u16) -> Color {
+    const SATURATION: f64 = 0.6;
+    const VALUE: f64 = 0.95;
+
+    let sector = f64::from(hue % 360) / 60.0;
+    let chroma = VALUE * SATURATION;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (r, g, b) = match sector as u8 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let channel = |value: f64| ((value + VALUE - chroma) * 255.0).round() as u8;
+
+    Color::Rgb(channel(r), channel(g), channel(b))
+}
@@ -2,25 +2,38 @@ use anyhow::Result;
 
 use ratatui::{
     prelude::{Constraint, Direction, Layout},
-    style::{Color, Style},
-    text::{Line, Span},
-    widgets::{Block, Borders, Paragraph},
+    widgets::TableState,
 };
 
 use crate::{
-    app::{ActiveWindow, App, Mode},
+    app::{ActiveWindow, App, CollectionsParams, Mode, SearchingParams},
     tui::Frame,
     ui::components::{
-        scrolling::collect_list_items,
+        auto_tagging::collect_tag_changes_items,
+        broken_links::collect_broken_links_items,
+        collections::{collect_collections_items, render_collection_name_input},
+        packs::{collect_packs_items, render_pack_name_input},
+        reading_list::collect_reading_list_items,
+        saved_searches::{collect_saved_searches_items, render_saved_search_name_input},
+        scrolling::{column_widths, render_bookmarks_table, visible_rows},
         search::{
             collect_selected_tags_items, collect_tags_items, render_links_search_panel,
             render_titles_search_panel,
         },
+        tag_merges::collect_tag_merges_items,
     },
 };
 
 use super::{
-    components::{create::render_creation_popup, help::render_help_popup},
+    components::{
+        create::render_creation_popup,
+        details::render_details_panel,
+        help::render_help_popup,
+        palette::render_palette_popup,
+        pinned::{render_pinned_panel, PINNED_SHOWN},
+        snippets::render_snippets_panel,
+        status::{render_log_popup, render_status_bar},
+    },
     helpers::set_cursor,
 };
 
@@ -37,17 +50,56 @@ pub fn render(app: &mut App, f: &mut Frame) -> Result<()> {
         )
         .split(f.size());
 
-    let results_panel_layout = Layout::default()
+    // matches in the page texts and the details of the bookmark are shown next to the results
+    let shows_snippets = matches!(&app.mode, Mode::Search(params) if params.in_content);
+    let results_and_snippets = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints(
-            [
-                Constraint::Percentage(40),
-                Constraint::Percentage(40),
-                Constraint::Percentage(20),
-            ]
-            .as_ref(),
-        )
+        .constraints(if shows_snippets || app.show_details {
+            [Constraint::Percentage(65), Constraint::Percentage(35)]
+        } else {
+            [Constraint::Percentage(100), Constraint::Percentage(0)]
+        })
         .split(main_window_layout[0]);
+    let snippets_and_details = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(match (shows_snippets, app.show_details) {
+            (true, true) => [Constraint::Percentage(50), Constraint::Percentage(50)],
+            (true, false) => [Constraint::Percentage(100), Constraint::Percentage(0)],
+            (false, _) => [Constraint::Percentage(0), Constraint::Percentage(100)],
+        })
+        .split(results_and_snippets[1]);
+
+    // pinned bookmarks go on top of the results, as many as there are numbers for them
+    let shows_pinned = !app.pinned.is_empty()
+        && !matches!(
+            app.mode,
+            Mode::BrokenLinks(_)
+                | Mode::ReadingList(_)
+                | Mode::Packs(_)
+                | Mode::TagMerges(_)
+                | Mode::AutoTagging(_)
+                | Mode::SavedSearches(_)
+        );
+    let pinned_height = if shows_pinned {
+        u16::try_from(app.pinned.len().min(PINNED_SHOWN))? + 2
+    } else {
+        0
+    };
+    let pinned_and_results = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(pinned_height), Constraint::Min(0)])
+        .split(results_and_snippets[0]);
+
+    // the collections sidebar is there as soon as there's any collection
+    let shows_sidebar = !app.collections.is_empty() || matches!(app.mode, Mode::Collections(_));
+    let sidebar_and_results = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(if shows_sidebar {
+            [Constraint::Percentage(20), Constraint::Percentage(80)]
+        } else {
+            [Constraint::Percentage(0), Constraint::Percentage(100)]
+        })
+        .split(pinned_and_results[1]);
 
     let search_panel_layout = Layout::default()
         .direction(Direction::Horizontal)
@@ -64,13 +116,131 @@ pub fn render(app: &mut App, f: &mut Frame) -> Result<()> {
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
         .split(search_panel_layout[1]);
 
-    let results_list = collect_list_items(app);
-    for (i, list) in results_list.into_iter().enumerate() {
+    app.areas.results = results_and_snippets[0];
+    app.areas.bookmarks = sidebar_and_results[1];
+    app.areas.pinned = pinned_and_results[0];
+    app.areas.collections = Default::default();
+    app.areas.title_search = title_and_link_panels[0];
+    app.areas.link_search = title_and_link_panels[1];
+    app.areas.tags = tags_panels[0];
+    app.areas.selected_tags = tags_panels[1];
+
+    if let Mode::BrokenLinks(params) = &mut app.mode {
+        let broken_links = collect_broken_links_items(params);
         f.render_stateful_widget(
-            list,
-            results_panel_layout[i],
-            &mut app.bookmarks_items.state,
+            broken_links,
+            results_and_snippets[0],
+            &mut params.items.state,
         );
+    } else if let Mode::ReadingList(params) = &mut app.mode {
+        let reading_list = collect_reading_list_items(params);
+        f.render_stateful_widget(
+            reading_list,
+            results_and_snippets[0],
+            &mut params.items.state,
+        );
+    } else if let Mode::AutoTagging(params) = &mut app.mode {
+        let changes = collect_tag_changes_items(params);
+        f.render_stateful_widget(changes, results_and_snippets[0], &mut params.items.state);
+    } else if let Mode::TagMerges(params) = &mut app.mode {
+        let tag_merges = collect_tag_merges_items(params);
+        f.render_stateful_widget(tag_merges, results_and_snippets[0], &mut params.items.state);
+    } else if let Mode::Packs(params) = &mut app.mode {
+        // the name being typed goes under the list
+        let list_and_input = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(if params.editing.is_some() { 3 } else { 0 }),
+            ])
+            .split(results_and_snippets[0]);
+
+        let packs = collect_packs_items(params, &app.tags);
+        f.render_stateful_widget(packs, list_and_input[0], &mut params.items.state);
+        if let Some(draft) = &params.editing {
+            f.render_widget(render_pack_name_input(draft), list_and_input[1]);
+            set_cursor(&draft.name, &list_and_input[1], f)?;
+        }
+    } else if let Mode::SavedSearches(params) = &mut app.mode {
+        // the new name goes under the list
+        let list_and_input = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(if params.renaming.is_some() { 3 } else { 0 }),
+            ])
+            .split(results_and_snippets[0]);
+
+        let searches = collect_saved_searches_items(params);
+        f.render_stateful_widget(searches, list_and_input[0], &mut params.items.state);
+        if let Some(name) = &params.renaming {
+            f.render_widget(render_saved_search_name_input(name), list_and_input[1]);
+            set_cursor(name, &list_and_input[1], f)?;
+        }
+    } else {
+        if shows_pinned {
+            let pinned = render_pinned_panel(app);
+            f.render_widget(pinned, pinned_and_results[0]);
+        }
+
+        if shows_sidebar {
+            // the name being typed goes under the tree
+            let editing = match &app.mode {
+                Mode::Collections(params) => params.editing.as_ref(),
+                _ => None,
+            };
+            let tree_and_input = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Min(0),
+                    Constraint::Length(if editing.is_some() { 3 } else { 0 }),
+                ])
+                .split(sidebar_and_results[0]);
+
+            app.areas.collections = tree_and_input[0];
+            let collections = collect_collections_items(app);
+            f.render_stateful_widget(
+                collections,
+                tree_and_input[0],
+                &mut app.collections_items.state,
+            );
+            if let Mode::Collections(CollectionsParams {
+                editing: Some(draft),
+                ..
+            }) = &app.mode
+            {
+                f.render_widget(render_collection_name_input(draft), tree_and_input[1]);
+                set_cursor(&draft.name, &tree_and_input[1], f)?;
+            }
+        }
+
+        // the table scrolls the same way the lists do, so the cursor is kept in the list state
+        let widths = column_widths(&app.columns);
+        let rows = visible_rows(app, sidebar_and_results[1]);
+        // the table gets only the rows in view, so it counts from the first of them
+        let mut table_state = TableState::default().with_selected(
+            app.bookmarks_items
+                .state
+                .selected()
+                .and_then(|index| index.checked_sub(rows.start)),
+        );
+        f.render_stateful_widget(
+            render_bookmarks_table(app, &widths, rows),
+            sidebar_and_results[1],
+            &mut table_state,
+        );
+    }
+
+    if let Mode::Search(params) = &app.mode {
+        if params.in_content {
+            let snippets = render_snippets_panel(app, params);
+            f.render_widget(snippets, snippets_and_details[0]);
+        }
+    }
+
+    if app.show_details {
+        let details = render_details_panel(app);
+        f.render_widget(details, snippets_and_details[1]);
     }
 
     let titles_search = render_titles_search_panel(app);
@@ -86,6 +256,12 @@ pub fn render(app: &mut App, f: &mut Frame) -> Result<()> {
     f.render_widget(selected_tags, tags_panels[1]);
 
     match &app.mode {
+        Mode::Search(SearchingParams {
+            reverse_search: Some(reverse_search),
+            ..
+        }) => {
+            set_cursor(&reverse_search.query, &title_and_link_panels[0], f)?;
+        }
         Mode::Search(params) => match params.active_window {
             ActiveWindow::Link => {
                 set_cursor(&params.link, &title_and_link_panels[1], f)?;
@@ -95,28 +271,36 @@ pub fn render(app: &mut App, f: &mut Frame) -> Result<()> {
             }
             ActiveWindow::Tags => {}
         },
-        Mode::Create(_) | Mode::Scrolling => {}
+        Mode::Create(_)
+        | Mode::Scrolling
+        | Mode::BrokenLinks(_)
+        | Mode::ReadingList(_)
+        | Mode::Packs(_)
+        | Mode::TagMerges(_)
+        | Mode::AutoTagging(_)
+        | Mode::Collections(_)
+        | Mode::SavedSearches(_) => {}
     }
 
-    let mode_name = match app.mode {
-        Mode::Search(_) => "Searching Mode",
-        Mode::Create(_) => "Creation Mode",
-        Mode::Scrolling => "Scrolling Mode",
+    let status_bar = render_status_bar(app);
+    f.render_widget(status_bar, main_window_layout[2]);
+
+    app.areas.creation = if let Mode::Create(params) = &mut app.mode {
+        Some(render_creation_popup(params, f)?)
+    } else {
+        None
     };
-    let mode_footer = Paragraph::new(Line::from(Span::styled(
-        mode_name,
-        Style::default().fg(Color::Green),
-    )))
-    .block(Block::default().borders(Borders::ALL));
 
-    f.render_widget(mode_footer, main_window_layout[2]);
+    if app.render_help {
+        render_help_popup(f);
+    }
 
-    if let Mode::Create(params) = &app.mode {
-        render_creation_popup(params, f)?;
+    if app.notifications.show_log {
+        render_log_popup(&mut app.notifications, f);
     }
 
-    if app.render_help {
-        render_help_popup(f);
+    if let Some(palette) = &mut app.palette {
+        render_palette_popup(palette, f)?;
     }
 
     Ok(())
@@ -1,66 +1,2726 @@
-use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::{collections::HashMap, time::Instant};
 
-use crate::app::{ActiveWindow, App, Mode};
+use crossterm::event::{Event, KeyCode, KeyEvent};
+use ratatui::layout::Rect;
+use tui_input::{backend::crossterm::EventHandler, Input};
+use url::Url;
 
-pub async fn update(app: &mut App, key_event: KeyEvent) -> Result<()> {
-    match key_event.code {
-        KeyCode::Esc => app.quit(),
-        KeyCode::Char('c') if key_event.modifiers == KeyModifiers::CONTROL => app.quit(),
-        KeyCode::F(1) => {
+use crate::{
+    app::{
+        search, ActiveWindow, App, CollectionDraft, CollectionsParams, Mode, PackDraft,
+        PacksParams, ResultsQuery, ReverseSearch, SavedSearchesParams, SearchingParams,
+        StatefulList,
+    },
+    command::Command,
+    fulltext::{search_texts, Snippet},
+    message::{key_number, Message},
+    models::{
+        bookmarks::{Bookmark, ReadingStatus},
+        collections::NewCollectionParams,
+        packs::{NewPackParams, Pack},
+        saved_searches::SavedSearch,
+        search_history::SearchHistoryEntry,
+    },
+    ui::{
+        components::scrolling::column_at,
+        helpers::{is_inside, list_index_at},
+    },
+};
+
+/// Applies the message to the state and returns the side effects it asks for.
+///
+/// It never touches the terminal, the database or the network, so any
+/// state transition can be checked by feeding messages into it.
+pub fn update(mut app: App, message: Message) -> (App, Vec<Command>) {
+    let mut commands = match message {
+        Message::Tick => {
+            app.on_tick();
+            vec![]
+        }
+        Message::Quit => {
+            app.quit();
+            vec![]
+        }
+        Message::TogglePalette => {
+            app.toggle_palette();
+            vec![]
+        }
+        // the palette takes the keys while it's open
+        Message::Escape if app.palette.is_some() => {
+            app.palette = None;
+            vec![]
+        }
+        Message::Next if app.palette.is_some() => {
+            if let Some(palette) = &mut app.palette {
+                palette.items.next();
+            }
+            vec![]
+        }
+        Message::Previous if app.palette.is_some() => {
+            if let Some(palette) = &mut app.palette {
+                palette.items.previous();
+            }
+            vec![]
+        }
+        Message::Enter if app.palette.is_some() => {
+            let action = app
+                .palette
+                .take()
+                .and_then(|palette| palette.selected().map(|entry| entry.action.clone()));
+            match action {
+                // the action goes the same way as if its key has been pressed
+                Some(action) => return update(app, action.into_message()),
+                None => vec![],
+            }
+        }
+        Message::Input(key_event) | Message::Unselect(key_event) if app.palette.is_some() => {
+            if let Some(palette) = &mut app.palette {
+                palette.query.handle_event(&Event::Key(key_event));
+                palette.filter();
+            }
+            vec![]
+        }
+        Message::Delete | Message::NextWindow | Message::ToggleMode if app.palette.is_some() => {
+            vec![]
+        }
+        Message::ReverseSearch => {
+            if !matches!(app.mode, Mode::Search(_)) {
+                app.activate_window(ActiveWindow::Title);
+            }
+            if let Mode::Search(params) = &mut app.mode {
+                // pressed again, it goes on to the older match
+                let older = params.reverse_search.is_some();
+                params
+                    .reverse_search
+                    .get_or_insert_with(ReverseSearch::default)
+                    .find(&app.search_history, older);
+            }
+            vec![]
+        }
+        // the reverse search takes the keys while it's going on
+        Message::Escape if is_reverse_searching(&app) => {
+            if let Mode::Search(params) = &mut app.mode {
+                params.reverse_search = None;
+            }
+            vec![]
+        }
+        Message::Enter if is_reverse_searching(&app) => {
+            let matched = match &mut app.mode {
+                Mode::Search(params) => params
+                    .reverse_search
+                    .take()
+                    .and_then(|reverse_search| reverse_search.matched),
+                _ => None,
+            };
+            match matched.and_then(|index| app.search_history.get(index).cloned()) {
+                Some(search) => fill_search(&mut app, search),
+                None => vec![],
+            }
+        }
+        Message::Input(key_event) | Message::Unselect(key_event) if is_reverse_searching(&app) => {
+            if let Mode::Search(SearchingParams {
+                reverse_search: Some(reverse_search),
+                ..
+            }) = &mut app.mode
+            {
+                reverse_search.query.handle_event(&Event::Key(key_event));
+                reverse_search.find(&app.search_history, false);
+            }
+            vec![]
+        }
+        Message::Next | Message::Previous | Message::NextWindow | Message::Delete
+            if is_reverse_searching(&app) =>
+        {
+            vec![]
+        }
+        Message::FilterByTag(tag) => {
+            app.tags_filter = vec![tag];
+            vec![refresh(&app)]
+        }
+        Message::OpenBookmark(id) => {
+            match app.bookmarks.iter().find(|bookmark| bookmark.id == id) {
+                Some(bookmark) => open(bookmark),
+                None => vec![],
+            }
+        }
+        Message::RunSavedSearch(id) => {
+            let search = app
+                .saved_searches
+                .iter()
+                .find(|search| search.id == id)
+                .cloned();
+            match search {
+                Some(search) => run_saved_search(&mut app, &search),
+                None => vec![],
+            }
+        }
+        Message::Escape => {
+            if app.notifications.show_log {
+                app.notifications.toggle_log();
+                vec![]
+            } else if let Mode::Packs(PacksParams {
+                editing: editing @ Some(_),
+                ..
+            }) = &mut app.mode
+            {
+                *editing = None;
+                vec![]
+            } else if let Mode::Collections(CollectionsParams {
+                editing: editing @ Some(_),
+                ..
+            }) = &mut app.mode
+            {
+                *editing = None;
+                vec![]
+            } else if let Mode::SavedSearches(SavedSearchesParams {
+                renaming: renaming @ Some(_),
+                ..
+            }) = &mut app.mode
+            {
+                *renaming = None;
+                vec![]
+            } else if matches!(&app.mode, Mode::Collections(params) if !params.moving.is_empty()) {
+                app.mode = Mode::Scrolling;
+                vec![]
+            } else if app.tasks.is_busy() {
+                let cancelled = app.tasks.cancel_all();
+                for task in &cancelled {
+                    app.notifications
+                        .warning(format!("Cancelled: {}", task.label));
+                }
+                vec![Command::Cancel(
+                    cancelled.into_iter().map(|task| task.id).collect(),
+                )]
+            } else {
+                app.quit();
+                vec![]
+            }
+        }
+        Message::ToggleHelp => {
             app.toggle_help_render();
+            vec![]
+        }
+        Message::ToggleLog => {
+            app.notifications.toggle_log();
+            vec![]
         }
-        KeyCode::F(3) => {
+        Message::ToggleMode => {
+            let commands = remember_search(&app);
+            app.toggle_mode();
+            commands
+        }
+        Message::ToggleCreationMode => {
             app.toggle_creation_mode();
+            match app.mode {
+                Mode::Create(_) => vec![Command::LoadKnownUrls, Command::LoadTagStats],
+                _ => vec![],
+            }
+        }
+        Message::NextWindow => {
+            let mut commands = vec![];
+            if let Mode::Create(params) = &mut app.mode {
+                if let ActiveWindow::Link = params.active_window {
+                    params.complete_link();
+                    commands.extend(fetch_metadata(&mut app));
+                }
+            }
+            app.change_active_window();
+            commands
+        }
+        Message::ToggleBrokenLinks => {
+            app.toggle_broken_links();
+            match app.mode {
+                Mode::BrokenLinks(_) => vec![Command::LoadBrokenLinks],
+                _ => vec![],
+            }
         }
-        KeyCode::F(5) => {
-            app.sync_bmarks().await?;
+        Message::ToggleReadingList => {
+            app.toggle_reading_list();
+            vec![]
         }
-        KeyCode::F(12) => {
-            app.reset().await?;
+        Message::TogglePacks => {
+            app.toggle_packs();
+            vec![]
         }
-        KeyCode::Left => match &mut app.mode {
-            Mode::Search(params) => {
-                if let ActiveWindow::Tags = params.active_window {
-                    app.tags_items.unselect();
-                } else {
-                    app.add_char(key_event)?;
-                };
+        Message::ToggleDetails => {
+            app.toggle_details();
+            vec![]
+        }
+        Message::DetailsLoaded(details) => {
+            app.details = Some(*details);
+            vec![]
+        }
+        Message::ToggleSavedSearches => {
+            app.toggle_saved_searches();
+            vec![]
+        }
+        Message::SaveSearch => match app.search_to_save() {
+            Some(params) => vec![Command::SaveSearch(params)],
+            None => {
+                app.notifications
+                    .warning("There's nothing to save, type a query or pick tags first");
+                vec![]
             }
-            Mode::Scrolling => app.bookmarks_items.unselect(),
-            Mode::Create(_) => {}
         },
-        KeyCode::Down => match &mut app.mode {
-            Mode::Search(params) => {
-                if let ActiveWindow::Tags = params.active_window {
-                    app.tags_items.next();
-                };
+        Message::SavedSearchesLoaded(searches) => {
+            app.set_saved_searches(searches);
+            vec![]
+        }
+        Message::SearchSaved(name) => {
+            if let Mode::SavedSearches(params) = &mut app.mode {
+                params.renaming = None;
             }
-            Mode::Scrolling => app.bookmarks_items.next(),
-            Mode::Create(params) => {
-                if let ActiveWindow::Tags = params.active_window {
-                    params.tags_items.next();
-                };
+            app.notifications.info(format!(
+                "Saved the search {name}, Ctrl+L lists the saved ones"
+            ));
+            vec![Command::LoadSavedSearches]
+        }
+        Message::SavedSearchDeleted(_) => {
+            app.notifications.info("Deleted the saved search");
+            vec![Command::LoadSavedSearches]
+        }
+        Message::Paste => match app.mode {
+            Mode::Create(_) => vec![Command::Paste],
+            _ => vec![],
+        },
+        Message::Pasted(text) => {
+            let mut commands = vec![];
+            if let Mode::Create(params) = &mut app.mode {
+                // a link is a single line, whatever is copied along with it
+                let link = text.lines().next().unwrap_or_default().trim();
+                params.link = Input::new(link.to_string());
+                params.active_window = ActiveWindow::Link;
+                params.validate();
+                params.complete_link();
+                params.suggest_tags();
+                commands.extend(fetch_metadata(&mut app));
+            }
+            commands
+        }
+        Message::Copied(what) => {
+            app.notifications.info(format!("Copied {what}"));
+            vec![]
+        }
+        Message::SearchHistoryLoaded(history) => {
+            app.set_search_history(history);
+            vec![]
+        }
+        Message::ToggleCollections => {
+            app.toggle_collections();
+            vec![]
+        }
+        Message::ToggleAutoTagging => {
+            app.toggle_auto_tagging();
+            match app.mode {
+                Mode::AutoTagging(_) => vec![Command::PreviewTagRules],
+                _ => vec![],
+            }
+        }
+        Message::ToggleTagMerges => {
+            app.toggle_tag_merges();
+            match app.mode {
+                Mode::TagMerges(_) => vec![Command::LoadTagMerges],
+                _ => vec![],
+            }
+        }
+        Message::ToggleContentSearch => {
+            if let Mode::Search(params) = &mut app.mode {
+                params.in_content = !params.in_content;
+            } else {
+                app.activate_window(ActiveWindow::Title);
+                if let Mode::Search(params) = &mut app.mode {
+                    params.in_content = true;
+                }
+            }
+            search_titles(&mut app);
+            vec![]
+        }
+        Message::IndexPages => {
+            let task_id = app.tasks.start("Indexing page texts");
+            vec![Command::IndexPages { task_id }]
+        }
+        Message::OpenPinned(number) => open_pinned(&mut app, number),
+        Message::CheckLinks => {
+            let task_id = app.tasks.start("Checking links");
+            vec![Command::CheckLinks { task_id, ids: None }]
+        }
+        Message::Sync => {
+            let task_id = app.tasks.start("Syncing bookmarks");
+            vec![Command::Sync { task_id }]
+        }
+        Message::Reset => {
+            // the reset search can be recalled from the history
+            let mut commands = remember_search(&app);
+            app.results_query = None;
+            app.tags_filter = Vec::new();
+            app.collection_filter = None;
+            commands.push(refresh(&app));
+            commands
+        }
+        Message::Unselect(key_event) => {
+            match &mut app.mode {
+                Mode::Search(params) => {
+                    if let ActiveWindow::Tags = params.active_window {
+                        app.tags_items.unselect();
+                    } else {
+                        add_char(&mut app, key_event);
+                    };
+                }
+                Mode::Scrolling => app.bookmarks_items.unselect(),
+                Mode::BrokenLinks(params) => params.items.unselect(),
+                Mode::ReadingList(params) => params.items.unselect(),
+                Mode::TagMerges(params) => params.items.unselect(),
+                Mode::AutoTagging(params) => params.items.unselect(),
+                Mode::Packs(params) => match &mut params.editing {
+                    Some(draft) => {
+                        draft.name.handle_event(&Event::Key(key_event));
+                    }
+                    None => params.items.unselect(),
+                },
+                Mode::Collections(params) => match &mut params.editing {
+                    Some(draft) => {
+                        draft.name.handle_event(&Event::Key(key_event));
+                    }
+                    None => app.collections_items.unselect(),
+                },
+                Mode::SavedSearches(params) => match &mut params.renaming {
+                    Some(name) => {
+                        name.handle_event(&Event::Key(key_event));
+                    }
+                    None => params.items.unselect(),
+                },
+                Mode::Create(_) => {}
+            }
+            vec![]
+        }
+        Message::Next if app.notifications.show_log => {
+            app.notifications.log.next();
+            vec![]
+        }
+        Message::Previous if app.notifications.show_log => {
+            app.notifications.log.previous();
+            vec![]
+        }
+        // the arrows recall the history in the search inputs
+        Message::Next if is_typing_search(&app) => recall(&mut app, false),
+        Message::Previous if is_typing_search(&app) => recall(&mut app, true),
+        Message::Next => {
+            match &mut app.mode {
+                Mode::Search(params) => {
+                    if let ActiveWindow::Tags = params.active_window {
+                        app.tags_items.next();
+                    };
+                }
+                Mode::Scrolling => app.bookmarks_items.next(),
+                Mode::BrokenLinks(params) => params.items.next(),
+                Mode::ReadingList(params) => params.items.next(),
+                Mode::TagMerges(params) => params.items.next(),
+                Mode::AutoTagging(params) => params.items.next(),
+                Mode::Packs(params) => params.items.next(),
+                Mode::Collections(_) => app.collections_items.next(),
+                Mode::SavedSearches(params) => params.items.next(),
+                Mode::Create(params) => {
+                    if let ActiveWindow::Tags = params.active_window {
+                        params.tags_items.next();
+                    };
+                }
+            }
+            vec![]
+        }
+        Message::Previous => {
+            match &mut app.mode {
+                Mode::Search(params) => {
+                    if let ActiveWindow::Tags = params.active_window {
+                        app.tags_items.previous();
+                    };
+                }
+                Mode::Scrolling => app.bookmarks_items.previous(),
+                Mode::BrokenLinks(params) => params.items.previous(),
+                Mode::ReadingList(params) => params.items.previous(),
+                Mode::TagMerges(params) => params.items.previous(),
+                Mode::AutoTagging(params) => params.items.previous(),
+                Mode::Packs(params) => params.items.previous(),
+                Mode::Collections(_) => app.collections_items.previous(),
+                Mode::SavedSearches(params) => params.items.previous(),
+                Mode::Create(params) => {
+                    if let ActiveWindow::Tags = params.active_window {
+                        params.tags_items.previous();
+                    };
+                }
+            }
+            vec![]
+        }
+        Message::Enter => resolve_enter(&mut app),
+        Message::Delete => on_delete(&mut app),
+        Message::Input(key_event) => match (&app.mode, key_event.code) {
+            (
+                Mode::Search(SearchingParams {
+                    active_window: ActiveWindow::Tags,
+                    ..
+                }),
+                KeyCode::Right | KeyCode::Char(' '),
+            ) => {
+                app.toggle_selected_tag();
+                vec![]
             }
+            (
+                Mode::Search(SearchingParams {
+                    active_window: ActiveWindow::Tags,
+                    ..
+                }),
+                KeyCode::Char('g'),
+            ) => move_to_next_pack(&app),
+            (Mode::Create(params), KeyCode::Char(digit @ '1'..='9'))
+                if matches!(params.active_window, ActiveWindow::Tags)
+                    && params.tag_input.value().is_empty() =>
+            {
+                if let Mode::Create(params) = &mut app.mode {
+                    params.pick_suggestion(key_number(digit));
+                }
+                vec![]
+            }
+            (Mode::Search(_) | Mode::Create(_), _) => {
+                add_char(&mut app, key_event);
+                vec![]
+            }
+            (
+                Mode::Packs(PacksParams {
+                    editing: Some(_), ..
+                }),
+                _,
+            ) => {
+                if let Mode::Packs(PacksParams {
+                    editing: Some(draft),
+                    ..
+                }) = &mut app.mode
+                {
+                    draft.name.handle_event(&Event::Key(key_event));
+                }
+                vec![]
+            }
+            (
+                Mode::Collections(CollectionsParams {
+                    editing: Some(_), ..
+                }),
+                _,
+            ) => {
+                if let Mode::Collections(CollectionsParams {
+                    editing: Some(draft),
+                    ..
+                }) = &mut app.mode
+                {
+                    draft.name.handle_event(&Event::Key(key_event));
+                }
+                vec![]
+            }
+            (
+                Mode::SavedSearches(SavedSearchesParams {
+                    renaming: Some(_), ..
+                }),
+                _,
+            ) => {
+                if let Mode::SavedSearches(SavedSearchesParams {
+                    renaming: Some(name),
+                    ..
+                }) = &mut app.mode
+                {
+                    name.handle_event(&Event::Key(key_event));
+                }
+                vec![]
+            }
+            // digits are typed into the inputs, otherwise they open the pinned bookmarks
+            (_, KeyCode::Char(digit @ '1'..='9')) => open_pinned(&mut app, key_number(digit)),
+            (Mode::BrokenLinks(_), _) => on_broken_link_key(&mut app, key_event),
+            (Mode::ReadingList(_), _) => on_reading_list_key(&mut app, key_event),
+            (Mode::Packs(_), _) => on_pack_key(&mut app, key_event),
+            (Mode::SavedSearches(_), _) => on_saved_search_key(&mut app, key_event),
+            (Mode::TagMerges(_), KeyCode::Char('s')) => {
+                if let Mode::TagMerges(params) = &mut app.mode {
+                    if let Some(index) = params.items.state.selected() {
+                        params.items.items[index].swap();
+                    }
+                }
+                vec![]
+            }
+            (Mode::TagMerges(_), _) => vec![],
+            (Mode::AutoTagging(_), KeyCode::Char('f')) => {
+                if let Mode::AutoTagging(params) = &mut app.mode {
+                    params.cycle_rule();
+                }
+                vec![]
+            }
+            (Mode::AutoTagging(_), _) => vec![],
+            (Mode::Collections(_), _) => on_collection_key(&mut app, key_event),
+            (Mode::Scrolling, _) => on_bookmark_key(&mut app, key_event),
         },
-        KeyCode::Up => match &mut app.mode {
-            Mode::Search(params) => {
-                if let ActiveWindow::Tags = params.active_window {
-                    app.tags_items.previous();
-                };
+        Message::Click { column, row, at } => on_click(&mut app, column, row, at),
+        Message::ScrollDown { column, row } => {
+            on_scroll(&mut app, column, row, true);
+            vec![]
+        }
+        Message::ScrollUp { column, row } => {
+            on_scroll(&mut app, column, row, false);
+            vec![]
+        }
+        Message::Refreshed {
+            bookmarks,
+            pinned,
+            tags,
+            packs,
+            collections,
+            archived,
+        } => {
+            app.set_library(bookmarks, tags, packs, collections);
+            app.pinned = pinned;
+            app.archived = archived;
+            rerun_search(&mut app);
+            vec![]
+        }
+        Message::BookmarksLoaded(bookmarks) => {
+            app.append_bookmarks(bookmarks);
+            rerun_search(&mut app);
+            vec![]
+        }
+        Message::BookmarkCreated {
+            bookmark,
+            auto_tags,
+        } => {
+            let id = bookmark.id;
+            app.notifications
+                .info(format!("Created a bookmark for {}", bookmark.url.inner));
+            if !auto_tags.is_empty() {
+                app.notifications
+                    .info(format!("Tagged by the rules: {}", auto_tags.join(", ")));
+            }
+            app.mode = Mode::Scrolling;
+            // the rules may have created tags, and a filtered view or the search results
+            // may not show the new bookmark
+            let mut commands = if auto_tags.is_empty()
+                && app.tags_filter.is_empty()
+                && app.collection_filter.is_none()
+                && !app.lists_results()
+            {
+                app.insert_bookmark(bookmark);
+                vec![]
+            } else {
+                vec![refresh(&app)]
+            };
+            if app.archive_on_create {
+                let task_id = app.tasks.start("Archiving the page");
+                commands.push(Command::ArchiveBookmarks {
+                    task_id,
+                    ids: vec![id],
+                });
+            }
+            commands
+        }
+        Message::KnownUrls(known_urls) => {
+            if let Mode::Create(params) = &mut app.mode {
+                params.known_urls = known_urls;
+                params.validate();
+            }
+            vec![]
+        }
+        Message::TagCreated(tag) => {
+            app.notifications
+                .info(format!("Created the tag {}", tag.name));
+            if let Mode::Create(params) = &mut app.mode {
+                params.add_typed_tag(tag);
+            }
+            vec![refresh(&app)]
+        }
+        Message::TagStatsLoaded(tag_stats) => {
+            if let Mode::Create(params) = &mut app.mode {
+                params.tag_stats = *tag_stats;
+                params.suggest_tags();
+            }
+            vec![]
+        }
+        Message::MetadataFetched { url, metadata } => {
+            if let Mode::Create(params) = &mut app.mode {
+                params.apply_metadata(url, *metadata);
+            }
+            vec![]
+        }
+        Message::BookmarkOpened { id, at } => {
+            app.set_opened(id, at);
+            vec![]
+        }
+        Message::BookmarkDeleted(id) => {
+            app.notifications.info("Deleted the bookmark");
+            app.remove_bookmark(id);
+            reload_view(&app)
+        }
+        Message::PinnedChanged { count, pinned } => {
+            let action = if pinned { "Pinned" } else { "Unpinned" };
+            app.notifications
+                .info(format!("{action} {count} bookmarks"));
+            vec![refresh(&app)]
+        }
+        Message::ReadingStatusChanged { count, status } => {
+            app.notifications
+                .info(format!("Marked {count} bookmarks as {}", status.as_str()));
+            vec![refresh(&app)]
+        }
+        Message::BookmarkRetargeted(_) => {
+            app.notifications
+                .info("Moved the bookmark to the redirect destination");
+            reload(&app)
+        }
+        Message::PackSaved(pack) => {
+            if let Mode::Packs(params) = &mut app.mode {
+                params.editing = None;
+            }
+            app.notifications
+                .info(format!("Saved the pack {}", pack.name));
+            vec![refresh(&app)]
+        }
+        Message::PackDeleted(_) => {
+            app.notifications
+                .info("Deleted the pack, its tags are left without one");
+            vec![refresh(&app)]
+        }
+        Message::PackAssigned { tag, pack } => {
+            app.notifications.info(match pack {
+                Some(pack) => format!("Moved the tag {tag} into the pack {pack}"),
+                None => format!("Took the tag {tag} out of its pack"),
+            });
+            vec![refresh(&app)]
+        }
+        Message::CollectionSaved(name) => {
+            if let Mode::Collections(params) = &mut app.mode {
+                params.editing = None;
+            }
+            app.notifications
+                .info(format!("Saved the collection {name}"));
+            vec![refresh(&app)]
+        }
+        Message::CollectionDeleted(_) => {
+            app.notifications.info(
+                "Deleted the collection with the nested ones, their bookmarks are left without one",
+            );
+            vec![refresh(&app)]
+        }
+        Message::CollectionsReordered => vec![refresh(&app)],
+        Message::BookmarksMoved { count, collection } => {
+            app.notifications.info(match collection {
+                Some(collection) => format!("Moved {count} bookmarks into {collection}"),
+                None => format!("Took {count} bookmarks out of their collections"),
+            });
+            if matches!(&app.mode, Mode::Collections(params) if !params.moving.is_empty()) {
+                app.mode = Mode::Scrolling;
+            }
+            vec![refresh(&app)]
+        }
+        Message::TagRulesPreviewed { rules, changes } => {
+            if let Mode::AutoTagging(params) = &mut app.mode {
+                params.set_changes(rules, changes);
+            }
+            vec![]
+        }
+        Message::TagChangesApplied { bookmarks, tags } => {
+            app.notifications
+                .info(format!("Added {tags} tags to {bookmarks} bookmarks"));
+            reload(&app)
+        }
+        Message::TagMergesLoaded(candidates) => {
+            if let Mode::TagMerges(params) = &mut app.mode {
+                params.items.replace_items(candidates);
+            }
+            vec![]
+        }
+        Message::TagsMerged { from, into } => {
+            app.notifications.info(format!(
+                "Merged the tag {} into {}, the old name is kept as an alias",
+                from.name, into.name
+            ));
+            // the merged tag is gone, the filter goes on with the one that took its place
+            for tag in &mut app.tags_filter {
+                if tag.id == from.id {
+                    *tag = into.clone();
+                }
             }
-            Mode::Scrolling => app.bookmarks_items.previous(),
-            Mode::Create(params) => {
-                if let ActiveWindow::Tags = params.active_window {
-                    params.tags_items.previous();
+            reload(&app)
+        }
+        Message::BrokenLinksLoaded(broken_links) => {
+            if let Mode::BrokenLinks(params) = &mut app.mode {
+                params.set_items(broken_links);
+            }
+            vec![]
+        }
+        Message::LinksChecked { checked, broken } => {
+            let text = format!("Checked {checked} links, {broken} of them are broken");
+            if broken > 0 {
+                app.notifications.warning(text);
+            } else {
+                app.notifications.info(text);
+            }
+            reload(&app)
+        }
+        Message::Archived { archived, failed } => {
+            for failure in &failed {
+                app.notifications
+                    .warning(format!("Couldn't archive {failure}"));
+            }
+            let text = format!("Archived {archived} pages");
+            if failed.is_empty() {
+                app.notifications.info(text);
+            } else {
+                app.notifications
+                    .warning(format!("{text}, {} failed", failed.len()));
+            }
+            vec![refresh(&app), Command::LoadPageTexts]
+        }
+        Message::PagesIndexed { indexed, failed } => {
+            for failure in &failed {
+                app.notifications
+                    .warning(format!("Couldn't index {failure}"));
+            }
+            let text = format!("Indexed {indexed} pages");
+            if failed.is_empty() {
+                app.notifications.info(text);
+            } else {
+                app.notifications
+                    .warning(format!("{text}, {} failed", failed.len()));
+            }
+            vec![Command::LoadPageTexts]
+        }
+        Message::PageTextsLoaded(texts) => {
+            app.page_texts = texts;
+            rerun_search(&mut app);
+            vec![]
+        }
+        Message::ArchiveOpened { url, archived_at } => {
+            app.notifications.info(format!(
+                "Opened the copy of {url} archived on {}",
+                archived_at.format("%Y-%m-%d %H:%M")
+            ));
+            vec![]
+        }
+        Message::Synced { count, failures } => {
+            app.notifications.info(format!("Synced {count} bookmarks"));
+            for failure in failures {
+                app.notifications
+                    .error(format!("Couldn't sync from {failure}"));
+            }
+            vec![refresh(&app)]
+        }
+        Message::TaskProgress { id, done, total } => {
+            app.tasks.set_progress(id, done, total);
+            vec![]
+        }
+        Message::TaskFinished { id, outcome } => {
+            // outcomes of cancelled tasks are outdated
+            let Some(task) = app.tasks.finish(id) else {
+                return (app, vec![]);
+            };
+            let outcome = match *outcome {
+                Message::Failed(error) => Message::Failed(format!("{}: {error}", task.label)),
+                outcome => outcome,
+            };
+            return update(app, outcome);
+        }
+        Message::Failed(error) => {
+            app.notifications.error(error);
+            vec![]
+        }
+    };
+    // the details pane follows the cursor whatever has moved it
+    commands.extend(app.request_details().map(Command::LoadDetails));
+
+    (app, commands)
+}
+
+fn refresh(app: &App) -> Command {
+    Command::Refresh {
+        tags_filter: app.tags_filter.clone(),
+        collection_filter: app.collection_filter,
+    }
+}
+
+/// Refreshes the library along with the view that shows a part of it.
+fn reload(app: &App) -> Vec<Command> {
+    let mut commands = vec![refresh(app)];
+    commands.extend(reload_view(app));
+    commands
+}
+
+/// Reloads the view that shows a part of the library, the library itself is up to date.
+fn reload_view(app: &App) -> Vec<Command> {
+    match app.mode {
+        Mode::BrokenLinks(_) => vec![Command::LoadBrokenLinks],
+        Mode::TagMerges(_) => vec![Command::LoadTagMerges],
+        Mode::AutoTagging(_) => vec![Command::PreviewTagRules],
+        _ => vec![],
+    }
+}
+
+fn on_broken_link_key(app: &mut App, key_event: KeyEvent) -> Vec<Command> {
+    let Mode::BrokenLinks(params) = &app.mode else {
+        return vec![];
+    };
+    let Some(broken_link) = params.selected() else {
+        return vec![];
+    };
+
+    match key_event.code {
+        KeyCode::Char('r') => match broken_link.check.final_url.as_deref().map(Url::parse) {
+            Some(Ok(url)) => vec![Command::RetargetBookmark {
+                id: broken_link.bookmark_id,
+                url,
+            }],
+            _ => {
+                app.notifications
+                    .warning("The link doesn't redirect anywhere");
+                vec![]
+            }
+        },
+        KeyCode::Char('o') => {
+            let id = broken_link.bookmark_id;
+            open_archive(app, id)
+        }
+        KeyCode::Char('c') => {
+            let id = broken_link.bookmark_id;
+            let task_id = app.tasks.start("Checking the link");
+            vec![Command::CheckLinks {
+                task_id,
+                ids: Some(vec![id]),
+            }]
+        }
+        _ => vec![],
+    }
+}
+
+fn on_reading_list_key(app: &mut App, key_event: KeyEvent) -> Vec<Command> {
+    let Mode::ReadingList(params) = &mut app.mode else {
+        return vec![];
+    };
+
+    match key_event.code {
+        KeyCode::Char('f') => {
+            params.cycle_shown(&app.bookmarks);
+            vec![]
+        }
+        code => match reading_status_for(code) {
+            Some(status) => params
+                .selected()
+                .map(|bookmark| Command::SetReadingStatus {
+                    ids: vec![bookmark.id],
+                    status,
+                })
+                .into_iter()
+                .collect(),
+            None => vec![],
+        },
+    }
+}
+
+/// Status the key sets in the lists of bookmarks.
+fn reading_status_for(code: KeyCode) -> Option<ReadingStatus> {
+    match code {
+        KeyCode::Char('r') => Some(ReadingStatus::Read),
+        KeyCode::Char('u') => Some(ReadingStatus::Unread),
+        KeyCode::Char('x') => Some(ReadingStatus::Archived),
+        _ => None,
+    }
+}
+
+fn on_bookmark_key(app: &mut App, key_event: KeyEvent) -> Vec<Command> {
+    if let Some(status) = reading_status_for(key_event.code) {
+        let ids = app.targets();
+        if ids.is_empty() {
+            return vec![];
+        }
+        return vec![Command::SetReadingStatus { ids, status }];
+    }
+
+    match key_event.code {
+        KeyCode::Char(' ') => {
+            if let Some(id) = app.selected_bookmark().map(|bookmark| bookmark.id) {
+                app.toggle_mark(id);
+                // marking goes down the list, so several bookmarks are marked in a row
+                app.bookmarks_items.next();
+            }
+            vec![]
+        }
+        KeyCode::Char('a') => {
+            let ids = app.targets();
+            if ids.is_empty() {
+                return vec![];
+            }
+            let task_id = app.tasks.start(format!("Archiving {} pages", ids.len()));
+            vec![Command::ArchiveBookmarks { task_id, ids }]
+        }
+        KeyCode::Char('p') => {
+            let ids = app.targets();
+            if ids.is_empty() {
+                return vec![];
+            }
+            // pins the bookmarks, unless all of them are pinned already
+            let pinned = !ids
+                .iter()
+                .all(|id| app.pinned.iter().any(|bookmark| bookmark.id == *id));
+            vec![Command::SetPinned { ids, pinned }]
+        }
+        KeyCode::Char('o') => match app.selected_bookmark().map(|bookmark| bookmark.id) {
+            Some(id) => open_archive(app, id),
+            None => vec![],
+        },
+        KeyCode::Char('s') => {
+            app.cycle_sort();
+            vec![]
+        }
+        KeyCode::Char('S') => {
+            app.reverse_sort();
+            vec![]
+        }
+        KeyCode::Char('m') => {
+            let ids = app.targets();
+            if !ids.is_empty() {
+                app.start_moving(ids);
+            }
+            vec![]
+        }
+        KeyCode::Char('y') => {
+            copy_bookmarks(app, "link", |bookmark| bookmark.url.inner.to_string())
+        }
+        KeyCode::Char('Y') => copy_bookmarks(app, "Markdown link", Bookmark::markdown_link),
+        KeyCode::Char('t') => copy_bookmarks(app, "title", Bookmark::name),
+        _ => vec![],
+    }
+}
+
+/// Copies the marked bookmarks, or the selected one, a line per bookmark.
+fn copy_bookmarks(app: &App, what: &str, text_of: impl Fn(&Bookmark) -> String) -> Vec<Command> {
+    let ids = app.targets();
+    let texts: Vec<String> = app
+        .bookmarks
+        .iter()
+        .filter(|bookmark| ids.contains(&bookmark.id))
+        .map(text_of)
+        .collect();
+
+    let what = match texts.len() {
+        0 => return vec![],
+        1 => format!("the {what}"),
+        count => format!("{count} {what}s"),
+    };
+    vec![Command::Copy {
+        text: texts.join("\n"),
+        what,
+    }]
+}
+
+fn on_collection_key(app: &mut App, key_event: KeyEvent) -> Vec<Command> {
+    match key_event.code {
+        KeyCode::Right | KeyCode::Char(' ') => {
+            app.toggle_selected_collection();
+            vec![]
+        }
+        code @ (KeyCode::Char('[') | KeyCode::Char(']')) => app
+            .reordered_siblings(code == KeyCode::Char('['))
+            .map(Command::ReorderCollections)
+            .into_iter()
+            .collect(),
+        KeyCode::Char('n') => {
+            // the new collection goes into the selected one
+            let parent_id = app
+                .selected_collection_row()
+                .and_then(|row| row.collection.as_ref())
+                .map(|collection| collection.id);
+            if let Mode::Collections(params) = &mut app.mode {
+                params.editing = Some(CollectionDraft {
+                    parent_id,
+                    ..Default::default()
+                });
+            }
+            vec![]
+        }
+        KeyCode::Char('e') => {
+            let Some(collection) = app
+                .selected_collection_row()
+                .and_then(|row| row.collection.clone())
+            else {
+                return vec![];
+            };
+            if let Mode::Collections(params) = &mut app.mode {
+                params.editing = Some(CollectionDraft {
+                    id: Some(collection.id),
+                    parent_id: collection.parent_id,
+                    name: Input::new(collection.name),
+                });
+            }
+            vec![]
+        }
+        _ => vec![],
+    }
+}
+
+/// Creates the collection or renames it, depending on what's being edited.
+fn save_collection(draft: &CollectionDraft) -> Vec<Command> {
+    let name = draft.name.value().trim().to_string();
+    if name.is_empty() {
+        return vec![];
+    }
+
+    match draft.id {
+        Some(id) => vec![Command::RenameCollection { id, name }],
+        None => vec![Command::CreateCollection(NewCollectionParams {
+            name,
+            parent_id: draft.parent_id,
+        })],
+    }
+}
+
+/// Moves the waiting bookmarks into the selected collection,
+/// or narrows the library down to it if there's nothing to move.
+fn pick_collection(app: &mut App) -> Vec<Command> {
+    let Mode::Collections(params) = &app.mode else {
+        return vec![];
+    };
+    let Some(row) = app.selected_collection_row() else {
+        return vec![];
+    };
+
+    if !params.moving.is_empty() {
+        return vec![Command::MoveBookmarks {
+            ids: params.moving.clone(),
+            collection: row.collection.clone(),
+        }];
+    }
+    app.collection_filter = row.collection.as_ref().map(|collection| collection.id);
+    vec![refresh(app)]
+}
+
+// Degrees the hue of a pack turns by with every press of the color key
+const PACK_HUE_STEP: u16 = 30;
+
+/// Puts the saved search in place, it's run once the library is refreshed with its tags.
+fn run_saved_search(app: &mut App, search: &SavedSearch) -> Vec<Command> {
+    app.apply_saved_search(search);
+    app.notifications
+        .info(format!("Running the saved search {}", search.name));
+    vec![refresh(app)]
+}
+
+fn on_saved_search_key(app: &mut App, key_event: KeyEvent) -> Vec<Command> {
+    let Mode::SavedSearches(params) = &mut app.mode else {
+        return vec![];
+    };
+
+    if key_event.code == KeyCode::Char('e') {
+        if let Some(search) = params.selected() {
+            params.renaming = Some(Input::new(search.name.clone()));
+        }
+    }
+    vec![]
+}
+
+fn on_pack_key(app: &mut App, key_event: KeyEvent) -> Vec<Command> {
+    let Mode::Packs(params) = &mut app.mode else {
+        return vec![];
+    };
+
+    match key_event.code {
+        KeyCode::Char('n') => {
+            params.editing = Some(PackDraft::default());
+            vec![]
+        }
+        KeyCode::Char('e') => {
+            if let Some(pack) = params.selected() {
+                params.editing = Some(PackDraft {
+                    id: Some(pack.id),
+                    name: Input::new(pack.name.clone()),
+                });
+            }
+            vec![]
+        }
+        KeyCode::Char('c') => params
+            .selected()
+            .map(|pack| {
+                Command::UpdatePack(Pack {
+                    color: (pack.color + PACK_HUE_STEP) % 360,
+                    ..pack.clone()
+                })
+            })
+            .into_iter()
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Creates the pack or renames it, depending on what's being edited.
+fn save_pack(packs_count: usize, params: &mut PacksParams) -> Vec<Command> {
+    let Some(draft) = &params.editing else {
+        return vec![];
+    };
+    let name = draft.name.value().trim().to_string();
+    if name.is_empty() {
+        return vec![];
+    }
+
+    match draft
+        .id
+        .and_then(|id| params.items.items.iter().find(|pack| pack.id == id))
+    {
+        Some(pack) => vec![Command::UpdatePack(Pack {
+            name,
+            ..pack.clone()
+        })],
+        None => {
+            // the hues of the new packs are spread around the color wheel
+            let color = u16::try_from(packs_count * 137 % 360).unwrap_or_default();
+            vec![Command::CreatePack(NewPackParams { name, color })]
+        }
+    }
+}
+
+/// Moves the selected tag into the next pack, the last one takes it out of any pack.
+fn move_to_next_pack(app: &App) -> Vec<Command> {
+    let Some(tag) = app.selected_tag() else {
+        return vec![];
+    };
+    if app.packs.is_empty() {
+        return vec![];
+    }
+
+    let next = match tag
+        .pack_id
+        .and_then(|id| app.packs.iter().position(|pack| pack.id == id))
+    {
+        Some(index) => app.packs.get(index + 1),
+        None => app.packs.first(),
+    };
+    vec![Command::AssignPack {
+        tag: tag.clone(),
+        pack: next.cloned(),
+    }]
+}
+
+fn open_pinned(app: &mut App, number: usize) -> Vec<Command> {
+    match number
+        .checked_sub(1)
+        .and_then(|index| app.pinned.get(index))
+    {
+        Some(bookmark) => open(bookmark),
+        None => {
+            app.notifications
+                .warning(format!("There's no pinned bookmark number {number}"));
+            vec![]
+        }
+    }
+}
+
+fn open_archive(app: &mut App, id: i64) -> Vec<Command> {
+    if app.archived.contains(&id) {
+        vec![Command::OpenArchive(id)]
+    } else {
+        app.notifications
+            .warning("The page hasn't been archived yet, press 'a' to do it");
+        vec![]
+    }
+}
+
+/// Asks for the metadata of the page the creation popup points to, unless it's known already.
+fn fetch_metadata(app: &mut App) -> Option<Command> {
+    let Mode::Create(params) = &app.mode else {
+        return None;
+    };
+    if !params.validation.is_valid() || params.metadata().is_some() {
+        return None;
+    }
+    let url = params.validation.url.clone()?;
+
+    let task_id = app.tasks.start("Fetching page info");
+    Some(Command::FetchMetadata { task_id, url })
+}
+
+fn open_bookmark(app: &App, index: usize) -> Vec<Command> {
+    app.bookmarks_items
+        .items
+        .get(index)
+        .map(open)
+        .unwrap_or_default()
+}
+
+/// Opens the bookmark in the browser, it counts as read from then on.
+fn open(bookmark: &Bookmark) -> Vec<Command> {
+    let mut commands = vec![
+        Command::OpenUrl(bookmark.url.clone().into()),
+        Command::MarkOpened(bookmark.id),
+    ];
+    if bookmark.status == ReadingStatus::Unread {
+        commands.push(Command::SetReadingStatus {
+            ids: vec![bookmark.id],
+            status: ReadingStatus::Read,
+        });
+    }
+    commands
+}
+
+fn resolve_enter(app: &mut App) -> Vec<Command> {
+    match &mut app.mode {
+        Mode::SavedSearches(params) => {
+            let Some(search) = params.selected().cloned() else {
+                return vec![];
+            };
+            if let Some(name) = &params.renaming {
+                let name = name.value().trim().to_string();
+                if name.is_empty() {
+                    return vec![];
+                }
+                return vec![Command::RenameSavedSearch {
+                    id: search.id,
+                    name,
+                }];
+            }
+            run_saved_search(app, &search)
+        }
+        Mode::Search(params) if !matches!(params.active_window, ActiveWindow::Tags) => {
+            submit_search(app)
+        }
+        Mode::Search(_) => {
+            if let Some(selected_tag) = app.selected_tag().cloned() {
+                match app
+                    .tags_filter
+                    .iter()
+                    .position(|tag| tag.id == selected_tag.id)
+                {
+                    Some(index) => {
+                        app.tags_filter.remove(index);
+                    }
+                    None => app.tags_filter.push(selected_tag),
+                }
+                return vec![refresh(app)];
+            }
+            // the headers of the packs fold and unfold
+            app.toggle_selected_tag();
+            vec![]
+        }
+        Mode::Create(params) => match params.active_window {
+            ActiveWindow::Link | ActiveWindow::Title => match &params.validation.url {
+                Some(url) if params.validation.is_valid() => vec![Command::CreateBookmark {
+                    title: Some(params.title.to_string()),
+                    url: url.clone(),
+                    tags: params.selected_tags.clone(),
+                    description: params
+                        .metadata()
+                        .and_then(|metadata| metadata.description.clone()),
+                }],
+                _ => {
+                    app.notifications
+                        .warning("Fix the link before saving the bookmark");
+                    vec![]
+                }
+            },
+            ActiveWindow::Tags => {
+                if let Some(tag_index) = params.tags_items.state.selected() {
+                    let selected_tag = params.tags_items.items[tag_index].clone();
+                    params.toggle_tag(selected_tag);
+                    return vec![];
+                }
+
+                let name = params.tag_input.value().trim().to_string();
+                if name.is_empty() {
+                    return vec![];
+                }
+                match params
+                    .tags
+                    .iter()
+                    .find(|tag| tag.name.eq_ignore_ascii_case(&name))
+                {
+                    Some(tag) => {
+                        params.add_typed_tag(tag.clone());
+                        vec![]
+                    }
+                    None => vec![Command::CreateTag(name)],
+                }
+            }
+        },
+        Mode::Scrolling => match app.bookmarks_items.state.selected() {
+            Some(url_index) => open_bookmark(app, url_index),
+            None => vec![],
+        },
+        Mode::BrokenLinks(params) => params
+            .selected()
+            .map(|broken_link| Command::OpenUrl(broken_link.url.clone()))
+            .into_iter()
+            .collect(),
+        Mode::ReadingList(params) => params.selected().map(open).unwrap_or_default(),
+        Mode::Packs(params) => save_pack(app.packs.len(), params),
+        Mode::Collections(CollectionsParams {
+            editing: Some(draft),
+            ..
+        }) => save_collection(draft),
+        Mode::Collections(_) => pick_collection(app),
+        Mode::AutoTagging(params) if params.items.items.is_empty() => vec![],
+        Mode::AutoTagging(params) => vec![Command::ApplyTagChanges(params.items.items.clone())],
+        Mode::TagMerges(params) => params
+            .selected()
+            .map(|candidate| Command::MergeTags {
+                from: candidate.tag.clone(),
+                into: candidate.into.clone(),
+            })
+            .into_iter()
+            .collect(),
+    }
+}
+
+fn on_delete(app: &mut App) -> Vec<Command> {
+    match &mut app.mode {
+        Mode::Search(_) | Mode::Create(_) => vec![],
+        Mode::SavedSearches(SavedSearchesParams {
+            renaming: Some(_), ..
+        }) => vec![],
+        Mode::SavedSearches(params) => params
+            .selected()
+            .map(|search| Command::DeleteSavedSearch(search.id))
+            .into_iter()
+            .collect(),
+        Mode::AutoTagging(params) => {
+            params.dismiss_selected();
+            vec![]
+        }
+        // a dismissed proposal shows up again the next time the tags are looked through
+        Mode::TagMerges(params) => {
+            if let Some(index) = params.items.state.selected() {
+                let mut items = std::mem::take(&mut params.items.items);
+                items.remove(index);
+                params.items.replace_items(items);
+            }
+            vec![]
+        }
+        Mode::BrokenLinks(params) => params
+            .selected()
+            .map(|broken_link| Command::DeleteBookmark(broken_link.bookmark_id))
+            .into_iter()
+            .collect(),
+        Mode::ReadingList(params) => params
+            .selected()
+            .map(|bookmark| Command::DeleteBookmark(bookmark.id))
+            .into_iter()
+            .collect(),
+        Mode::Packs(PacksParams {
+            editing: Some(_), ..
+        }) => vec![],
+        Mode::Packs(params) => params
+            .selected()
+            .map(|pack| Command::DeletePack(pack.id))
+            .into_iter()
+            .collect(),
+        Mode::Collections(CollectionsParams {
+            editing: Some(_), ..
+        }) => vec![],
+        Mode::Collections(_) => app
+            .selected_collection_row()
+            .and_then(|row| row.collection.as_ref())
+            .map(|collection| Command::DeleteCollection(collection.id))
+            .into_iter()
+            .collect(),
+        Mode::Scrolling => app
+            .bookmarks_items
+            .state
+            .selected()
+            .map(|index| Command::DeleteBookmark(app.bookmarks_items.items[index].id))
+            .into_iter()
+            .collect(),
+    }
+}
+
+fn add_char(app: &mut App, key_event: KeyEvent) {
+    match app.mode {
+        Mode::Search(ref mut params) => match params.active_window {
+            ActiveWindow::Link => {
+                params.link.handle_event(&Event::Key(key_event));
+                params.forget_recalled();
+                search_links(app);
+            }
+            ActiveWindow::Title => {
+                params.title.handle_event(&Event::Key(key_event));
+                params.forget_recalled();
+                search_titles(app);
+            }
+            ActiveWindow::Tags => {}
+        },
+        Mode::Create(ref mut params) => match params.active_window {
+            ActiveWindow::Link => {
+                params.link.handle_event(&Event::Key(key_event));
+                params.validate();
+                params.suggest_tags();
+            }
+            ActiveWindow::Title => {
+                params.title.handle_event(&Event::Key(key_event));
+                params.suggest_tags();
+            }
+            // backspace with nothing typed takes back the last selected tag
+            ActiveWindow::Tags
+                if key_event.code == KeyCode::Backspace && params.tag_input.value().is_empty() =>
+            {
+                params.selected_tags.pop();
+                params.suggest_tags();
+            }
+            ActiveWindow::Tags => {
+                params.tag_input.handle_event(&Event::Key(key_event));
+                params.tags_items.unselect();
+                params.suggest_tags();
+            }
+        },
+        Mode::Scrolling
+        | Mode::BrokenLinks(_)
+        | Mode::ReadingList(_)
+        | Mode::Packs(_)
+        | Mode::TagMerges(_)
+        | Mode::AutoTagging(_)
+        | Mode::Collections(_)
+        | Mode::SavedSearches(_) => {}
+    }
+}
+
+fn is_reverse_searching(app: &App) -> bool {
+    matches!(
+        &app.mode,
+        Mode::Search(SearchingParams {
+            reverse_search: Some(_),
+            ..
+        })
+    )
+}
+
+/// Whether a query is being typed into the title or the link input.
+fn is_typing_search(app: &App) -> bool {
+    matches!(&app.mode, Mode::Search(params) if !matches!(params.active_window, ActiveWindow::Tags))
+}
+
+fn remember_search(app: &App) -> Vec<Command> {
+    app.search_to_remember()
+        .map(Command::RememberSearch)
+        .into_iter()
+        .collect()
+}
+
+/// Remembers the search and moves to its results.
+fn submit_search(app: &mut App) -> Vec<Command> {
+    let commands = remember_search(app);
+    app.mode = Mode::Scrolling;
+    if !app.bookmarks_items.items.is_empty() {
+        app.bookmarks_items.state.select(Some(0));
+    }
+    commands
+}
+
+/// Puts the older or the newer search from the history into the inputs.
+fn recall(app: &mut App, older: bool) -> Vec<Command> {
+    match app.recall_search(older) {
+        Some(search) => fill_search(app, search),
+        None => vec![],
+    }
+}
+
+/// Puts the search into the inputs, the library is refreshed if it's filtered by other tags.
+fn fill_search(app: &mut App, search: SearchHistoryEntry) -> Vec<Command> {
+    let Mode::Search(params) = &mut app.mode else {
+        return vec![];
+    };
+    let links_only = search.title_query.is_empty() && !search.link_query.is_empty();
+    params.title = Input::new(search.title_query);
+    params.link = Input::new(search.link_query);
+
+    let same_tags = search.tags.len() == app.tags_filter.len()
+        && search
+            .tags
+            .iter()
+            .all(|tag| app.tags_filter.iter().any(|filter| filter.id == tag.id));
+    if !same_tags {
+        // the queries run again once the library is refreshed
+        app.tags_filter = search.tags;
+        return vec![refresh(app)];
+    }
+    if links_only {
+        search_links(app);
+    } else {
+        search_titles(app);
+    }
+    vec![]
+}
+
+/// Filters the bookmarks by the link input.
+fn search_links(app: &mut App) {
+    let Mode::Search(params) = &app.mode else {
+        return;
+    };
+    let query = params.link.to_string();
+    let query = (!query.is_empty()).then_some(ResultsQuery::Links(query));
+    list_found(app, query);
+}
+
+/// Filters the bookmarks by the title input, and by the page texts if the search looks there.
+fn search_titles(app: &mut App) {
+    let Mode::Search(params) = &app.mode else {
+        return;
+    };
+    let query = params.title.to_string();
+    let query = (!query.is_empty()).then_some(ResultsQuery::Titles {
+        query,
+        in_content: params.in_content,
+    });
+    list_found(app, query);
+}
+
+/// Lists the bookmarks found by the typed query, or the whole library without one.
+fn list_found(app: &mut App, query: Option<ResultsQuery>) {
+    let (results, snippets) = match &query {
+        Some(query) => match find(app, query) {
+            Some(found) => found,
+            None => return,
+        },
+        None => (app.bookmarks.clone(), HashMap::new()),
+    };
+    if let Mode::Search(params) = &mut app.mode {
+        params.snippets = snippets;
+    }
+    app.list_results(results, query);
+}
+
+/// Runs the search the bookmarks are listed by again, e.g. over the refreshed library.
+/// While searching that's the typed one, it may have been put into the inputs meanwhile.
+fn rerun_search(app: &mut App) {
+    let query = match &app.mode {
+        Mode::Search(params) => typed_query(params),
+        _ => app.results_query.clone(),
+    };
+    let Some(query) = query else {
+        app.results_query = None;
+        return;
+    };
+    let Some((results, snippets)) = find(app, &query) else {
+        return;
+    };
+    if let Mode::Search(params) = &mut app.mode {
+        params.snippets = snippets;
+    }
+    app.results_query = Some(query);
+    app.relist(results);
+}
+
+fn typed_query(params: &SearchingParams) -> Option<ResultsQuery> {
+    if !params.title.value().is_empty() {
+        Some(ResultsQuery::Titles {
+            query: params.title.to_string(),
+            in_content: params.in_content,
+        })
+    } else if !params.link.value().is_empty() {
+        Some(ResultsQuery::Links(params.link.to_string()))
+    } else {
+        None
+    }
+}
+
+/// The bookmarks found by the query, along with the matches in the page texts by their ids.
+fn find(app: &App, query: &ResultsQuery) -> Option<(Vec<Bookmark>, HashMap<i64, Snippet>)> {
+    let (query, in_content) = match query {
+        ResultsQuery::Titles { query, in_content } => (query, *in_content),
+        ResultsQuery::Links(query) => {
+            let results = search(app.bookmarks.clone(), query, false).ok()?;
+            return Some((results, HashMap::new()));
+        }
+    };
+    let mut results = search(app.bookmarks.clone(), query, true).ok()?;
+
+    let mut snippets = HashMap::new();
+    if in_content {
+        for (id, snippet) in search_texts(&app.page_texts, query) {
+            if !results.iter().any(|bookmark| bookmark.id == id) {
+                // the library is filtered by tags, the texts are not
+                let Some(bookmark) = app.bookmarks.iter().find(|bookmark| bookmark.id == id) else {
+                    continue;
                 };
+                results.push(bookmark.clone());
+            }
+            snippets.insert(id, snippet);
+        }
+    }
+
+    Some((results, snippets))
+}
+
+fn on_click(app: &mut App, column: u16, row: u16, at: Instant) -> Vec<Command> {
+    // popups cover everything, so any click just closes them
+    if app.palette.is_some() {
+        app.palette = None;
+        return vec![];
+    }
+    if app.notifications.show_log {
+        app.notifications.toggle_log();
+        return vec![];
+    }
+    if app.render_help {
+        app.toggle_help_render();
+        return vec![];
+    }
+
+    let is_double = app.register_click(column, row, at);
+    let areas = app.areas;
+
+    if let Mode::Create(params) = &mut app.mode {
+        let Some(areas) = areas.creation else {
+            return vec![];
+        };
+
+        if is_inside(&areas.title, column, row) {
+            params.active_window = ActiveWindow::Title;
+        } else if is_inside(&areas.link, column, row) {
+            params.active_window = ActiveWindow::Link;
+        } else if is_inside(&areas.tag_input, column, row) {
+            params.active_window = ActiveWindow::Tags;
+        } else if is_inside(&areas.tags, column, row) {
+            params.active_window = ActiveWindow::Tags;
+            if let Some(index) = select_clicked(&mut params.tags_items, &areas.tags, column, row) {
+                if is_double {
+                    let tag = params.tags_items.items[index].clone();
+                    params.toggle_tag(tag);
+                }
+            }
+        } else if let Some(index) = list_index_at(&areas.selected_tags, 0, column, row)
+            .filter(|index| *index < params.selected_tags.len())
+        {
+            params.selected_tags.remove(index);
+            params.suggest_tags();
+        }
+
+        return vec![];
+    }
+
+    if is_inside(&areas.pinned, column, row) {
+        // the section has no cursor, a double-click opens the bookmark right away
+        if let Some(index) = list_index_at(&areas.pinned, 0, column, row) {
+            if is_double {
+                return open_pinned(app, index + 1);
+            }
+        }
+        return vec![];
+    }
+
+    if is_inside(&areas.results, column, row) {
+        match &mut app.mode {
+            Mode::BrokenLinks(params) => {
+                if let Some(index) = select_clicked(&mut params.items, &areas.results, column, row)
+                {
+                    if is_double {
+                        return vec![Command::OpenUrl(params.items.items[index].url.clone())];
+                    }
+                }
+                return vec![];
             }
+            Mode::ReadingList(params) => {
+                if let Some(index) = select_clicked(&mut params.items, &areas.results, column, row)
+                {
+                    if is_double {
+                        return open(&params.items.items[index]);
+                    }
+                }
+                return vec![];
+            }
+            Mode::AutoTagging(params) => {
+                select_clicked(&mut params.items, &areas.results, column, row);
+                return vec![];
+            }
+            Mode::TagMerges(params) => {
+                select_clicked(&mut params.items, &areas.results, column, row);
+                return vec![];
+            }
+            Mode::Packs(params) => {
+                select_clicked(&mut params.items, &areas.results, column, row);
+                return vec![];
+            }
+            Mode::SavedSearches(params) => {
+                if select_clicked(&mut params.items, &areas.results, column, row).is_some()
+                    && is_double
+                {
+                    return resolve_enter(app);
+                }
+                return vec![];
+            }
+            _ => {}
+        }
+    }
+
+    if is_inside(&areas.collections, column, row) {
+        if !matches!(app.mode, Mode::Collections(_)) {
+            app.toggle_collections();
+        }
+        if select_clicked(&mut app.collections_items, &areas.collections, column, row).is_some()
+            && is_double
+        {
+            return resolve_enter(app);
+        }
+        return vec![];
+    }
+
+    if is_inside(&areas.bookmarks, column, row) {
+        // the header sorts by its column
+        if row == areas.bookmarks.y + 1 {
+            if let Some(sorted) = column_at(&areas.bookmarks, &app.columns, column) {
+                app.sort_by(sorted);
+            }
+            return vec![];
+        }
+        // the rows go under the header
+        let rows = Rect {
+            y: areas.bookmarks.y + 1,
+            height: areas.bookmarks.height.saturating_sub(1),
+            ..areas.bookmarks
+        };
+        if let Some(index) = select_clicked(&mut app.bookmarks_items, &rows, column, row) {
+            if is_double {
+                return open_bookmark(app, index);
+            }
+        }
+    } else if is_inside(&areas.title_search, column, row) {
+        app.activate_window(ActiveWindow::Title);
+    } else if is_inside(&areas.link_search, column, row) {
+        app.activate_window(ActiveWindow::Link);
+    } else if is_inside(&areas.tags, column, row) {
+        app.activate_window(ActiveWindow::Tags);
+        if select_clicked(&mut app.tags_items, &areas.tags, column, row).is_some() && is_double {
+            return resolve_enter(app);
+        }
+    } else if let Some(index) = list_index_at(&areas.selected_tags, 0, column, row)
+        .filter(|index| *index < app.tags_filter.len())
+    {
+        app.tags_filter.remove(index);
+        return vec![refresh(app)];
+    }
+
+    vec![]
+}
+
+fn on_scroll(app: &mut App, column: u16, row: u16, down: bool) {
+    let areas = app.areas;
+
+    if let Mode::Create(params) = &mut app.mode {
+        if areas
+            .creation
+            .is_some_and(|areas| is_inside(&areas.tags, column, row))
+        {
+            scroll(&mut params.tags_items, down);
+        }
+        return;
+    }
+
+    if is_inside(&areas.results, column, row) {
+        match &mut app.mode {
+            Mode::BrokenLinks(params) => return scroll(&mut params.items, down),
+            Mode::ReadingList(params) => return scroll(&mut params.items, down),
+            Mode::AutoTagging(params) => return scroll(&mut params.items, down),
+            Mode::TagMerges(params) => return scroll(&mut params.items, down),
+            Mode::Packs(params) => return scroll(&mut params.items, down),
+            Mode::SavedSearches(params) => return scroll(&mut params.items, down),
+            _ => {}
+        }
+    }
+
+    if is_inside(&areas.collections, column, row) {
+        scroll(&mut app.collections_items, down);
+    } else if is_inside(&areas.bookmarks, column, row) {
+        scroll(&mut app.bookmarks_items, down);
+    } else if is_inside(&areas.tags, column, row) {
+        scroll(&mut app.tags_items, down);
+    }
+}
+
+/// Selects the item of the bordered list under the click, returns its index.
+fn select_clicked<T>(
+    list: &mut StatefulList<T>,
+    area: &Rect,
+    column: u16,
+    row: u16,
+) -> Option<usize> {
+    let offset = list.state.offset();
+    let index =
+        list_index_at(area, offset, column, row).filter(|index| *index < list.items.len())?;
+    list.state.select(Some(index));
+    Some(index)
+}
+
+fn scroll<T>(list: &mut StatefulList<T>, down: bool) {
+    if down {
+        list.next();
+    } else {
+        list.previous();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyModifiers;
+
+    use super::*;
+    use crate::{
+        models::{
+            packs::{NewPackParams, Pack},
+            tags::Tag,
         },
-        KeyCode::Enter => app.resolve_enter().await?,
-        KeyCode::Delete => app.on_delete().await?,
-        KeyCode::Char('`') => app.toggle_mode(),
-        KeyCode::Tab => app.change_active_window(),
-        _ => app.add_char(key_event)?,
+        notifications::Level,
+        settings::Column,
     };
-    Ok(())
+
+    fn bookmark(id: i64, title: &str, url: &str) -> Bookmark {
+        Bookmark::new(
+            id,
+            Some(title.to_string()),
+            url.to_string(),
+            None,
+            "unread",
+            None,
+            None,
+        )
+    }
+
+    fn library() -> App {
+        let mut app = App::new();
+        app.set_library(
+            vec![
+                bookmark(1, "The Rust book", "https://doc.rust-lang.org/book/"),
+                bookmark(2, "Go by example", "https://gobyexample.com/"),
+                bookmark(
+                    3,
+                    "Rust by example",
+                    "https://doc.rust-lang.org/rust-by-example/",
+                ),
+            ],
+            vec![Tag::new(1, "rust".to_string())],
+            vec![],
+            vec![],
+        );
+        app
+    }
+
+    fn key(code: KeyCode) -> Message {
+        Message::from_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    /// Feeds the messages one by one, returns the commands of the last one.
+    fn feed(app: App, messages: impl IntoIterator<Item = Message>) -> (App, Vec<Command>) {
+        messages
+            .into_iter()
+            .fold((app, vec![]), |(app, _), message| update(app, message))
+    }
+
+    fn typed(text: &str) -> Vec<Message> {
+        text.chars().map(|c| key(KeyCode::Char(c))).collect()
+    }
+
+    #[test]
+    fn creates_the_typed_bookmark() {
+        let (app, commands) = update(library(), key(KeyCode::F(3)));
+        assert!(matches!(app.mode, Mode::Create(_)));
+        assert!(matches!(
+            commands.as_slice(),
+            [Command::LoadKnownUrls, Command::LoadTagStats]
+        ));
+
+        let mut messages = typed("Tokio");
+        // the title, the tags, then the link
+        messages.extend([key(KeyCode::Tab), key(KeyCode::Tab)]);
+        messages.extend(typed("tokio.rs"));
+        messages.push(key(KeyCode::Enter));
+        let (_, commands) = feed(app, messages);
+
+        match commands.as_slice() {
+            [Command::CreateBookmark {
+                title, url, tags, ..
+            }] => {
+                assert_eq!(title.as_deref(), Some("Tokio"));
+                assert_eq!(url.as_str(), "https://tokio.rs/");
+                assert!(tags.is_empty());
+            }
+            commands => panic!("unexpected commands {commands:?}"),
+        }
+    }
+
+    #[test]
+    fn doesnt_create_a_bookmark_with_a_broken_link() {
+        let mut messages = vec![key(KeyCode::F(3)), key(KeyCode::Tab), key(KeyCode::Tab)];
+        messages.extend(typed("not a link"));
+        messages.push(key(KeyCode::Enter));
+        let (app, commands) = feed(library(), messages);
+
+        assert!(commands.is_empty());
+        assert!(matches!(app.mode, Mode::Create(_)));
+    }
+
+    #[test]
+    fn deletes_the_selected_bookmark() {
+        let (app, _) = feed(library(), [key(KeyCode::Down), key(KeyCode::Down)]);
+        let (app, commands) = update(app, key(KeyCode::Delete));
+        assert!(matches!(commands.as_slice(), [Command::DeleteBookmark(2)]));
+
+        let (app, _) = update(app, Message::BookmarkDeleted(2));
+        assert!(app.bookmarks.iter().all(|bookmark| bookmark.id != 2));
+        assert!(app
+            .bookmarks_items
+            .items
+            .iter()
+            .all(|bookmark| bookmark.id != 2));
+    }
+
+    #[test]
+    fn deletes_nothing_without_a_selection() {
+        let (_, commands) = update(library(), key(KeyCode::Delete));
+        assert!(commands.is_empty());
+    }
+
+    #[test]
+    fn submits_the_search() {
+        let mut messages = vec![key(KeyCode::Char('`'))];
+        messages.extend(typed("rust"));
+        let (app, _) = feed(library(), messages);
+        assert!(matches!(app.mode, Mode::Search(_)));
+        assert!(app
+            .bookmarks_items
+            .items
+            .iter()
+            .all(|bookmark| bookmark.id != 2));
+
+        let (app, commands) = update(app, key(KeyCode::Enter));
+        assert!(matches!(app.mode, Mode::Scrolling));
+        assert_eq!(app.bookmarks_items.state.selected(), Some(0));
+        match commands.as_slice() {
+            [Command::RememberSearch(search)] => assert_eq!(search.title_query, "rust"),
+            commands => panic!("unexpected commands {commands:?}"),
+        }
+    }
+
+    #[test]
+    fn recalls_the_submitted_search() {
+        let (app, _) = update(
+            library(),
+            Message::SearchHistoryLoaded(vec![SearchHistoryEntry {
+                title_query: "go".to_string(),
+                ..Default::default()
+            }]),
+        );
+        let (app, _) = feed(app, [key(KeyCode::Char('`')), key(KeyCode::Up)]);
+
+        let Mode::Search(params) = &app.mode else {
+            panic!("not searching");
+        };
+        assert_eq!(params.title.value(), "go");
+        assert!(app
+            .bookmarks_items
+            .items
+            .iter()
+            .any(|bookmark| bookmark.id == 2));
+    }
+
+    #[test]
+    fn toggles_the_modes() {
+        let app = library();
+        assert!(matches!(app.mode, Mode::Scrolling));
+
+        let (app, _) = update(app, Message::ToggleMode);
+        assert!(matches!(app.mode, Mode::Search(_)));
+        let (app, commands) = update(app, Message::ToggleMode);
+        assert!(matches!(app.mode, Mode::Scrolling));
+        // nothing has been typed, so nothing is remembered
+        assert!(commands.is_empty());
+
+        let (app, commands) = update(app, Message::ToggleBrokenLinks);
+        assert!(matches!(app.mode, Mode::BrokenLinks(_)));
+        assert!(matches!(commands.as_slice(), [Command::LoadBrokenLinks]));
+        let (app, _) = update(app, Message::ToggleBrokenLinks);
+        assert!(matches!(app.mode, Mode::Scrolling));
+
+        let (app, _) = update(app, Message::ToggleReadingList);
+        assert!(matches!(app.mode, Mode::ReadingList(_)));
+        // another view replaces the open one
+        let (app, _) = update(app, Message::TogglePacks);
+        assert!(matches!(app.mode, Mode::Packs(_)));
+        let (app, _) = update(app, Message::ToggleMode);
+        assert!(matches!(app.mode, Mode::Scrolling));
+    }
+
+    #[test]
+    fn escape_closes_the_log_before_quitting() {
+        let (app, _) = update(library(), Message::ToggleLog);
+        let (app, _) = update(app, Message::Escape);
+        assert!(!app.notifications.show_log);
+        assert!(!app.is_should_quit());
+
+        let (app, _) = update(app, Message::Escape);
+        assert!(app.is_should_quit());
+    }
+
+    #[test]
+    fn inserts_the_created_bookmark_into_the_library() {
+        let created = bookmark(4, "Tokio", "https://tokio.rs/");
+        let (app, commands) = update(
+            library(),
+            Message::BookmarkCreated {
+                bookmark: created,
+                auto_tags: vec![],
+            },
+        );
+
+        assert!(commands.is_empty());
+        assert!(app
+            .bookmarks_items
+            .items
+            .iter()
+            .any(|bookmark| bookmark.id == 4));
+    }
+
+    #[test]
+    fn refreshes_the_search_results_instead_of_growing_them() {
+        let mut messages = vec![key(KeyCode::Char('`'))];
+        messages.extend(typed("rust"));
+        messages.push(key(KeyCode::Enter));
+        let (app, _) = feed(library(), messages);
+        let results = app.bookmarks_items.items.len();
+
+        let (app, commands) = update(
+            app,
+            Message::BookmarksLoaded(vec![bookmark(4, "Go tour", "https://go.dev/tour/")]),
+        );
+        assert!(commands.is_empty());
+        assert_eq!(app.bookmarks_items.items.len(), results);
+        assert_eq!(app.bookmarks.len(), 4);
+
+        let (app, commands) = update(
+            app,
+            Message::BookmarkCreated {
+                bookmark: bookmark(5, "Tokio", "https://tokio.rs/"),
+                auto_tags: vec![],
+            },
+        );
+        assert!(matches!(commands.as_slice(), [Command::Refresh { .. }]));
+        assert!(app
+            .bookmarks_items
+            .items
+            .iter()
+            .all(|bookmark| bookmark.id != 5));
+
+        let (app, _) = update(app, key(KeyCode::Down));
+        let selected = app.selected_bookmark().map(|bookmark| bookmark.id);
+        assert!(selected.is_some());
+
+        let mut bookmarks = library().bookmarks;
+        bookmarks.push(bookmark(5, "Tokio", "https://tokio.rs/"));
+        bookmarks.push(bookmark(
+            6,
+            "Async Rust",
+            "https://rust-lang.github.io/async-book/",
+        ));
+        let (app, _) = update(app, refreshed(bookmarks));
+
+        let mut results: Vec<_> = app
+            .bookmarks_items
+            .items
+            .iter()
+            .map(|bookmark| bookmark.id)
+            .collect();
+        results.sort();
+        assert_eq!(results, vec![1, 3, 6]);
+        assert_eq!(
+            app.selected_bookmark().map(|bookmark| bookmark.id),
+            selected
+        );
+    }
+
+    fn refreshed(bookmarks: Vec<Bookmark>) -> Message {
+        Message::Refreshed {
+            bookmarks,
+            pinned: vec![],
+            tags: vec![Tag::new(1, "rust".to_string())],
+            packs: vec![],
+            collections: vec![],
+            archived: Default::default(),
+        }
+    }
+
+    #[test]
+    fn keeps_the_selected_bookmark_across_refreshes() {
+        let (app, _) = feed(library(), [key(KeyCode::Down), key(KeyCode::Down)]);
+        assert_eq!(app.selected_bookmark().map(|bookmark| bookmark.id), Some(2));
+
+        // e.g. pinned, the first one is gone meanwhile
+        let mut bookmarks = library().bookmarks;
+        bookmarks.remove(0);
+        let (app, _) = update(app, refreshed(bookmarks));
+        assert_eq!(app.bookmarks_items.items.len(), 2);
+        assert_eq!(app.selected_bookmark().map(|bookmark| bookmark.id), Some(2));
+    }
+
+    #[test]
+    fn toggles_the_selected_tag_in_the_filter() {
+        let messages = [
+            key(KeyCode::Char('`')),
+            key(KeyCode::Tab),
+            key(KeyCode::Down),
+            key(KeyCode::Enter),
+        ];
+        let (app, commands) = feed(library(), messages);
+        let filter: Vec<_> = app.tags_filter.iter().map(|tag| tag.id).collect();
+        assert_eq!(filter, vec![1]);
+        assert!(matches!(commands.as_slice(), [Command::Refresh { .. }]));
+
+        let (app, commands) = update(app, key(KeyCode::Enter));
+        assert!(app.tags_filter.is_empty());
+        assert!(matches!(commands.as_slice(), [Command::Refresh { .. }]));
+    }
+
+    fn listed(app: &App) -> Vec<i64> {
+        app.bookmarks_items
+            .items
+            .iter()
+            .map(|bookmark| bookmark.id)
+            .collect()
+    }
+
+    fn click(column: u16, row: u16) -> Message {
+        Message::Click {
+            column,
+            row,
+            at: Instant::now(),
+        }
+    }
+
+    /// The library laid out the way a 100 by 10 terminal shows it.
+    fn shown_library() -> App {
+        let mut app = library();
+        app.areas.bookmarks = Rect::new(0, 0, 100, 10);
+        app
+    }
+
+    #[test]
+    fn cycles_through_the_sort_columns() {
+        let (app, _) = feed(library(), [key(KeyCode::Down), key(KeyCode::Down)]);
+        assert_eq!(app.selected_bookmark().map(|bookmark| bookmark.id), Some(2));
+
+        // by the title, the cursor stays on the bookmark
+        let (app, _) = update(app, key(KeyCode::Char('s')));
+        assert_eq!(listed(&app), vec![2, 3, 1]);
+        assert_eq!(app.bookmarks_items.state.selected(), Some(0));
+
+        let (app, _) = update(app, key(KeyCode::Char('S')));
+        assert_eq!(listed(&app), vec![1, 3, 2]);
+        assert_eq!(app.bookmarks_items.state.selected(), Some(2));
+
+        // by the link, in the same direction as the columns go one by one
+        let (app, _) = update(app, key(KeyCode::Char('s')));
+        assert!(matches!(app.sort, Some(sort) if sort.column == Column::Url && !sort.descending));
+        assert_eq!(listed(&app), vec![1, 3, 2]);
+
+        let (app, _) = feed(app, [key(KeyCode::Char('s')), key(KeyCode::Char('s'))]);
+        assert!(app.sort.is_none());
+    }
+
+    #[test]
+    fn sorts_by_the_clicked_header() {
+        // the header is under the top border, the columns share the width by 40, 40 and 20 percent
+        let (app, _) = update(shown_library(), click(10, 1));
+        assert!(matches!(app.sort, Some(sort) if sort.column == Column::Title && !sort.descending));
+        assert_eq!(listed(&app), vec![2, 3, 1]);
+
+        let (app, _) = update(app, click(12, 1));
+        assert!(matches!(app.sort, Some(sort) if sort.column == Column::Title && sort.descending));
+        assert_eq!(listed(&app), vec![1, 3, 2]);
+
+        let (app, _) = update(app, click(60, 1));
+        assert!(matches!(app.sort, Some(sort) if sort.column == Column::Url));
+        let (app, _) = update(app, click(90, 1));
+        assert!(matches!(app.sort, Some(sort) if sort.column == Column::Tags));
+    }
+
+    #[test]
+    fn selects_and_opens_the_clicked_row() {
+        // the rows start under the header
+        let (app, commands) = update(shown_library(), click(5, 3));
+        assert!(commands.is_empty());
+        assert_eq!(app.selected_bookmark().map(|bookmark| bookmark.id), Some(2));
+
+        let (app, commands) = update(app, click(5, 3));
+        assert!(matches!(
+            commands.as_slice(),
+            [Command::OpenUrl(url), Command::MarkOpened(2), ..] if url.as_str() == "https://gobyexample.com/"
+        ));
+
+        // there's nothing below the last one
+        let (app, _) = update(app, click(5, 8));
+        assert_eq!(app.selected_bookmark().map(|bookmark| bookmark.id), Some(2));
+    }
+
+    #[test]
+    fn scrolls_the_list_under_the_pointer() {
+        let (app, _) = update(shown_library(), Message::ScrollDown { column: 5, row: 4 });
+        assert_eq!(app.bookmarks_items.state.selected(), Some(0));
+        let (app, _) = feed(
+            app,
+            [
+                Message::ScrollDown { column: 5, row: 4 },
+                Message::ScrollDown { column: 5, row: 4 },
+            ],
+        );
+        assert_eq!(app.bookmarks_items.state.selected(), Some(2));
+
+        // outside of the lists nothing moves
+        let (app, _) = update(app, Message::ScrollUp { column: 5, row: 40 });
+        assert_eq!(app.bookmarks_items.state.selected(), Some(2));
+        let (app, _) = update(app, Message::ScrollUp { column: 5, row: 4 });
+        assert_eq!(app.bookmarks_items.state.selected(), Some(1));
+    }
+
+    fn ctrl(c: char) -> Message {
+        Message::from_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL))
+    }
+
+    #[test]
+    fn runs_the_palette_entry_like_its_key() {
+        let selected = feed(library(), [key(KeyCode::Down), key(KeyCode::Down)]).0;
+        let (pressed, by_key) = update(selected, key(KeyCode::Char('u')));
+
+        let mut messages = vec![key(KeyCode::Down), key(KeyCode::Down), ctrl('p')];
+        messages.extend(typed("mark as unread"));
+        messages.push(key(KeyCode::Enter));
+        let (chosen, by_palette) = feed(library(), messages);
+
+        assert!(chosen.palette.is_none());
+        assert!(matches!(chosen.mode, Mode::Scrolling));
+        assert!(matches!(
+            by_key.as_slice(),
+            [Command::SetReadingStatus { .. }]
+        ));
+        assert_eq!(format!("{by_palette:?}"), format!("{by_key:?}"));
+        assert_eq!(
+            chosen.selected_bookmark().map(|bookmark| bookmark.id),
+            pressed.selected_bookmark().map(|bookmark| bookmark.id)
+        );
+    }
+
+    #[test]
+    fn types_nothing_into_the_search_from_the_palette() {
+        let searching = || {
+            let mut messages = vec![key(KeyCode::Char('`'))];
+            messages.extend(typed("go"));
+            feed(library(), messages).0
+        };
+        let (app, _) = update(searching(), ctrl('p'));
+        let count = app.palette.as_ref().unwrap().items.items.len();
+
+        for index in 0..count {
+            let mut messages = vec![ctrl('p')];
+            messages.extend((0..index).map(|_| key(KeyCode::Down)));
+            messages.push(key(KeyCode::Enter));
+            let (app, _) = feed(searching(), messages);
+
+            if let Mode::Search(params) = &app.mode {
+                assert_eq!(params.title.value(), "go", "entry {index}");
+            }
+        }
+    }
+
+    #[test]
+    fn searches_the_refreshed_library_by_the_recalled_search() {
+        let (app, _) = update(
+            library(),
+            Message::SearchHistoryLoaded(vec![SearchHistoryEntry {
+                title_query: "rust".to_string(),
+                link_query: String::new(),
+                tags: vec![Tag::new(1, "rust".to_string())],
+            }]),
+        );
+        // the tags differ, so the library is refreshed before the query runs
+        let (app, commands) = feed(app, [key(KeyCode::Char('`')), key(KeyCode::Up)]);
+        assert!(
+            matches!(commands.as_slice(), [Command::Refresh { tags_filter, .. }] if tags_filter.len() == 1)
+        );
+
+        let (app, _) = update(app, refreshed(library().bookmarks));
+        let mut results = listed(&app);
+        results.sort();
+        assert_eq!(results, vec![1, 3]);
+    }
+
+    fn search_params(app: &App) -> &SearchingParams {
+        match &app.mode {
+            Mode::Search(params) => params,
+            _ => panic!("not searching"),
+        }
+    }
+
+    #[test]
+    fn saves_the_typed_search_with_the_filter() {
+        let (app, commands) = update(library(), ctrl('s'));
+        assert!(commands.is_empty());
+        assert_eq!(app.notifications.log.items.len(), 1);
+
+        let mut app = library();
+        app.tags_filter = vec![Tag::new(1, "rust".to_string())];
+        app.sort_by(Column::Title);
+        let mut messages = vec![key(KeyCode::Char('`'))];
+        messages.extend(typed("book"));
+        messages.push(ctrl('s'));
+        let (_, commands) = feed(app, messages);
+
+        match commands.as_slice() {
+            [Command::SaveSearch(params)] => {
+                assert_eq!(params.name, "\"book\" #rust");
+                assert_eq!(params.title_query, "book");
+                assert_eq!(params.tag_ids, vec![1]);
+                assert_eq!(params.sort_column, Some(Column::Title));
+                assert!(!params.sort_descending);
+            }
+            commands => panic!("unexpected commands {commands:?}"),
+        }
+    }
+
+    #[test]
+    fn runs_the_saved_search() {
+        let rust = Tag::new(1, "rust".to_string());
+        let (app, _) = update(
+            library(),
+            Message::SavedSearchesLoaded(vec![SavedSearch {
+                id: 7,
+                name: "Rust".to_string(),
+                title_query: "rust".to_string(),
+                link_query: String::new(),
+                tags: vec![rust],
+                sort_column: Some(Column::Title),
+                sort_descending: true,
+            }]),
+        );
+
+        let (app, commands) = feed(app, [ctrl('l'), key(KeyCode::Down), key(KeyCode::Enter)]);
+        assert!(matches!(
+            commands.as_slice(),
+            [Command::Refresh { tags_filter, .. }] if tags_filter.iter().map(|tag| tag.id).eq([1])
+        ));
+        assert_eq!(search_params(&app).title.value(), "rust");
+
+        // the query runs over the library filtered by the tags
+        let (app, _) = update(app, refreshed(library().bookmarks));
+        assert_eq!(listed(&app), vec![1, 3]);
+    }
+
+    #[test]
+    fn recalls_the_search_from_the_history() {
+        let history = vec![
+            SearchHistoryEntry {
+                title_query: "go".to_string(),
+                ..Default::default()
+            },
+            SearchHistoryEntry {
+                title_query: "rust".to_string(),
+                ..Default::default()
+            },
+            SearchHistoryEntry {
+                link_query: "rust-lang".to_string(),
+                ..Default::default()
+            },
+        ];
+        let (app, _) = update(library(), Message::SearchHistoryLoaded(history));
+
+        // the latest match first, then the older one
+        let mut messages = vec![key(KeyCode::Char('`')), ctrl('r')];
+        messages.extend(typed("rust"));
+        let (app, _) = feed(app, messages);
+        let matched = |app: &App| search_params(app).reverse_search.as_ref().unwrap().matched;
+        assert_eq!(matched(&app), Some(2));
+        let (app, _) = update(app, ctrl('r'));
+        assert_eq!(matched(&app), Some(1));
+
+        let (app, _) = update(app, key(KeyCode::Enter));
+        let params = search_params(&app);
+        assert!(params.reverse_search.is_none());
+        assert_eq!(params.title.value(), "rust");
+        assert_eq!(params.link.value(), "");
+        let mut results = listed(&app);
+        results.sort();
+        assert_eq!(results, vec![1, 3]);
+
+        // giving up leaves the inputs be
+        let (app, _) = feed(app, [ctrl('r'), key(KeyCode::Char('g')), key(KeyCode::Esc)]);
+        let params = search_params(&app);
+        assert!(params.reverse_search.is_none());
+        assert_eq!(params.title.value(), "rust");
+    }
+
+    fn copied(commands: &[Command]) -> (&str, &str) {
+        match commands {
+            [Command::Copy { text, what }] => (text, what),
+            commands => panic!("unexpected commands {commands:?}"),
+        }
+    }
+
+    #[test]
+    fn copies_the_selected_bookmark() {
+        let mut app = library();
+        app.bookmarks.push(bookmark(
+            4,
+            "Arrays [and] slices",
+            "https://en.wikipedia.org/wiki/Slice_(programming)",
+        ));
+        app.set_library(app.bookmarks.clone(), app.tags.clone(), vec![], vec![]);
+        let index = listed(&app).iter().position(|id| *id == 4).unwrap();
+        app.bookmarks_items.state.select(Some(index));
+
+        let (app, commands) = update(app, key(KeyCode::Char('y')));
+        assert_eq!(
+            copied(&commands),
+            (
+                "https://en.wikipedia.org/wiki/Slice_(programming)",
+                "the link"
+            )
+        );
+        let (app, commands) = update(app, key(KeyCode::Char('t')));
+        assert_eq!(copied(&commands), ("Arrays [and] slices", "the title"));
+        let (_, commands) = update(app, key(KeyCode::Char('Y')));
+        assert_eq!(
+            copied(&commands),
+            (
+                "[Arrays \\[and\\] slices](https://en.wikipedia.org/wiki/Slice_%28programming%29)",
+                "the Markdown link"
+            )
+        );
+    }
+
+    #[test]
+    fn copies_the_marked_bookmarks_a_line_each() {
+        let mut app = library();
+        app.toggle_mark(1);
+        app.toggle_mark(3);
+
+        let (_, commands) = update(app, key(KeyCode::Char('Y')));
+        assert_eq!(
+            copied(&commands),
+            (
+                "[The Rust book](https://doc.rust-lang.org/book/)\n\
+                 [Rust by example](https://doc.rust-lang.org/rust-by-example/)",
+                "2 Markdown links"
+            )
+        );
+
+        // nothing to copy without a selection
+        let (_, commands) = update(library(), key(KeyCode::Char('y')));
+        assert!(commands.is_empty());
+    }
+
+    #[test]
+    fn pastes_the_first_line_into_the_link() {
+        let (app, commands) = update(library(), Message::Paste);
+        assert!(commands.is_empty());
+
+        let (app, _) = update(app, key(KeyCode::F(3)));
+        let (app, commands) = update(app, Message::Paste);
+        assert!(matches!(commands.as_slice(), [Command::Paste]));
+
+        let (app, commands) = update(
+            app,
+            Message::Pasted("  example.com/page \nthe rest of the note\n".to_string()),
+        );
+        let Mode::Create(params) = &app.mode else {
+            panic!("not creating");
+        };
+        assert_eq!(params.link.value(), "https://example.com/page");
+        assert!(params.validation.is_valid());
+        assert!(matches!(
+            commands.as_slice(),
+            [Command::FetchMetadata { url, .. }] if url.as_str() == "https://example.com/page"
+        ));
+    }
+
+    fn synced(id: u64) -> Message {
+        Message::TaskFinished {
+            id,
+            outcome: Box::new(Message::Synced {
+                count: 2,
+                failures: vec![],
+            }),
+        }
+    }
+
+    #[test]
+    fn syncs_in_the_background() {
+        let (app, commands) = update(library(), key(KeyCode::F(5)));
+        let [Command::Sync { task_id }] = commands.as_slice() else {
+            panic!("unexpected commands {commands:?}");
+        };
+        let task_id = *task_id;
+        assert!(app.tasks.is_busy());
+
+        // the app goes on while the task reports back
+        let (app, _) = update(
+            app,
+            Message::TaskProgress {
+                id: task_id,
+                done: 1,
+                total: 3,
+            },
+        );
+        assert_eq!(app.tasks.running[0].progress, Some((1, 3)));
+        let (app, _) = update(app, key(KeyCode::Down));
+        assert_eq!(app.bookmarks_items.state.selected(), Some(0));
+
+        let (app, commands) = update(app, synced(task_id));
+        assert!(!app.tasks.is_busy());
+        assert!(matches!(commands.as_slice(), [Command::Refresh { .. }]));
+        assert_eq!(
+            app.notifications.log.items.last().unwrap().text,
+            "Synced 2 bookmarks"
+        );
+    }
+
+    #[test]
+    fn escape_cancels_the_running_tasks() {
+        let (app, commands) = update(library(), Message::Sync);
+        let [Command::Sync { task_id }] = commands.as_slice() else {
+            panic!("unexpected commands {commands:?}");
+        };
+        let task_id = *task_id;
+
+        let (app, commands) = update(app, Message::Escape);
+        assert!(!app.is_should_quit());
+        assert!(!app.tasks.is_busy());
+        assert!(matches!(commands.as_slice(), [Command::Cancel(ids)] if *ids == [task_id]));
+
+        // whatever the cancelled task still sends is dropped
+        let (app, commands) = update(app, synced(task_id));
+        assert!(commands.is_empty());
+        assert_eq!(app.notifications.log.items.len(), 1);
+    }
+
+    #[test]
+    fn reports_the_errors_instead_of_quitting() {
+        let mut messages = vec![key(KeyCode::F(3))];
+        messages.extend(typed("not a link"));
+        messages.push(key(KeyCode::Enter));
+        let (app, commands) = feed(library(), messages);
+        assert!(commands.is_empty());
+        assert!(matches!(app.mode, Mode::Create(_)));
+        let warning = app.notifications.log.items.last().unwrap();
+        assert_eq!(warning.level, Level::Warning);
+        assert_eq!(warning.text, "Fix the link before saving the bookmark");
+
+        let (app, commands) = update(app, Message::Sync);
+        let [Command::Sync { task_id }] = commands.as_slice() else {
+            panic!("unexpected commands {commands:?}");
+        };
+        let (app, _) = update(
+            app,
+            Message::TaskFinished {
+                id: *task_id,
+                outcome: Box::new(Message::Failed("connection refused".to_string())),
+            },
+        );
+        assert!(!app.is_should_quit());
+        let error = app.notifications.log.items.last().unwrap();
+        assert_eq!(error.level, Level::Error);
+        // the error names the task it comes from
+        assert_eq!(error.text, "Syncing bookmarks: connection refused");
+    }
+
+    #[test]
+    fn pins_and_opens_the_pinned_bookmarks() {
+        let (app, _) = update(library(), key(KeyCode::Down));
+        let id = app.selected_bookmark().unwrap().id;
+        let (app, commands) = update(app, key(KeyCode::Char('p')));
+        assert!(matches!(
+            commands.as_slice(),
+            [Command::SetPinned { ids, pinned: true }] if *ids == [id]
+        ));
+
+        let library = library().bookmarks;
+        let (app, _) = update(
+            app,
+            Message::Refreshed {
+                pinned: vec![library[2].clone(), library[0].clone()],
+                bookmarks: library,
+                tags: vec![],
+                packs: vec![],
+                collections: vec![],
+                archived: Default::default(),
+            },
+        );
+        // the pinned ones are unpinned
+        let (app, commands) = update(app, key(KeyCode::Char('p')));
+        assert!(matches!(
+            commands.as_slice(),
+            [Command::SetPinned { ids, pinned: false }] if *ids == [id]
+        ));
+
+        // the numbers open them from anywhere, even while typing
+        let alt = |digit| Message::from_key(KeyEvent::new(KeyCode::Char(digit), KeyModifiers::ALT));
+        let (app, _) = update(app, key(KeyCode::Char('`')));
+        let (app, commands) = update(app, alt('2'));
+        assert!(matches!(
+            commands.first(),
+            Some(Command::OpenUrl(url)) if url == "https://doc.rust-lang.org/book/"
+        ));
+        assert_eq!(search_params(&app).title.value(), "");
+
+        let (app, commands) = update(app, alt('3'));
+        assert!(commands.is_empty());
+        assert_eq!(
+            app.notifications.log.items.last().unwrap().text,
+            "There's no pinned bookmark number 3"
+        );
+    }
+
+    #[test]
+    fn creates_and_edits_the_packs() {
+        let mut app = library();
+        app.packs = vec![Pack {
+            id: 1,
+            name: "Web".to_string(),
+            color: 350,
+        }];
+        let (app, _) = update(app, key(KeyCode::F(9)));
+
+        let mut messages = vec![key(KeyCode::Char('n'))];
+        messages.extend(typed("Languages"));
+        messages.push(key(KeyCode::Enter));
+        let (app, commands) = feed(app, messages);
+        // the new pack gets a hue of its own
+        assert!(matches!(
+            commands.as_slice(),
+            [Command::CreatePack(NewPackParams { name, color: 137 })] if name == "Languages"
+        ));
+        let (app, _) = update(
+            app,
+            Message::PackSaved(Pack {
+                id: 2,
+                name: "Languages".to_string(),
+                color: 137,
+            }),
+        );
+
+        let mut messages = vec![key(KeyCode::Down), key(KeyCode::Char('e'))];
+        messages.extend(typed(" dev"));
+        messages.push(key(KeyCode::Enter));
+        let (app, commands) = feed(app, messages);
+        assert!(matches!(
+            commands.as_slice(),
+            [Command::UpdatePack(Pack { id: 1, name, color: 350 })] if name == "Web dev"
+        ));
+
+        let (app, _) = update(
+            app,
+            Message::PackSaved(Pack {
+                id: 1,
+                name: "Web dev".to_string(),
+                color: 350,
+            }),
+        );
+
+        // the color goes around the wheel
+        let (_, commands) = update(app, key(KeyCode::Char('c')));
+        assert!(matches!(
+            commands.as_slice(),
+            [Command::UpdatePack(Pack {
+                id: 1,
+                color: 20,
+                ..
+            })]
+        ));
+    }
 }
@@ -0,0 +1,185 @@
+use std::collections::HashSet;
+
+use url::{Host, Url};
+
+use crate::notifications::Level;
+
+const DEFAULT_SCHEME: &str = "https://";
+
+/// Outcome of checking the link typed into the creation popup.
+#[derive(Debug, Default, Clone)]
+pub struct LinkValidation {
+    /// The link that is going to be saved, if it can be parsed at all.
+    pub url: Option<Url>,
+    pub notes: Vec<(Level, String)>,
+}
+
+impl LinkValidation {
+    pub fn is_valid(&self) -> bool {
+        self.url.is_some() && !self.notes.iter().any(|(level, _)| *level == Level::Error)
+    }
+}
+
+/// Form of the URL that is used to find the same page bookmarked twice:
+/// the scheme, `www.`, the fragment and the trailing slash don't matter.
+pub fn canonical_url(url: &Url) -> String {
+    let host = url.host_str().unwrap_or_default();
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    let port = url
+        .port()
+        .map(|port| format!(":{port}"))
+        .unwrap_or_default();
+    let path = url.path().trim_end_matches('/');
+    let query = url
+        .query()
+        .map(|query| format!("?{query}"))
+        .unwrap_or_default();
+
+    format!("{host}{port}{path}{query}")
+}
+
+pub fn validate_link(input: &str, known_urls: &HashSet<String>) -> LinkValidation {
+    let input = input.trim();
+    let mut validation = LinkValidation::default();
+
+    if input.is_empty() {
+        validation
+            .notes
+            .push((Level::Error, "Type the link to the page".to_string()));
+        return validation;
+    }
+    if input.contains(char::is_whitespace) {
+        validation
+            .notes
+            .push((Level::Error, "The link cannot contain spaces".to_string()));
+        return validation;
+    }
+
+    let link = if input.contains("://") {
+        input.to_string()
+    } else {
+        validation
+            .notes
+            .push((Level::Info, format!("{DEFAULT_SCHEME} will be added")));
+        format!("{DEFAULT_SCHEME}{input}")
+    };
+
+    let url = match Url::parse(&link) {
+        Ok(url) => url,
+        Err(error) => {
+            validation
+                .notes
+                .push((Level::Error, format!("Not a valid link: {error}")));
+            return validation;
+        }
+    };
+
+    if !matches!(url.scheme(), "http" | "https") {
+        validation
+            .notes
+            .push((Level::Warning, format!("Unusual scheme '{}'", url.scheme())));
+    }
+
+    match url.host() {
+        None if matches!(url.scheme(), "http" | "https") => {
+            validation
+                .notes
+                .push((Level::Error, "The link has no host".to_string()));
+        }
+        Some(Host::Domain(domain)) if !domain.contains('.') && domain != "localhost" => {
+            validation.notes.push((
+                Level::Warning,
+                format!("'{domain}' doesn't look like a full domain name"),
+            ));
+        }
+        _ => {}
+    }
+
+    if known_urls.contains(&canonical_url(&url)) {
+        validation.notes.push((
+            Level::Warning,
+            "This page is already bookmarked".to_string(),
+        ));
+    }
+
+    validation.url = Some(url);
+    validation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canonical(url: &str) -> String {
+        canonical_url(&Url::parse(url).unwrap())
+    }
+
+    fn levels(validation: &LinkValidation) -> Vec<Level> {
+        validation.notes.iter().map(|(level, _)| *level).collect()
+    }
+
+    #[test]
+    fn finds_the_same_page_by_its_canonical_url() {
+        let page = canonical("https://example.com/docs");
+        assert_eq!(page, "example.com/docs");
+        for same in [
+            "http://example.com/docs",
+            "https://www.example.com/docs",
+            "https://example.com/docs/",
+            "https://example.com/docs#install",
+            "http://www.example.com/docs/#install",
+        ] {
+            assert_eq!(canonical(same), page, "{same}");
+        }
+
+        for other in [
+            "https://example.com/docs?page=2",
+            "https://example.com:8080/docs",
+            "https://docs.example.com/docs",
+        ] {
+            assert_ne!(canonical(other), page, "{other}");
+        }
+    }
+
+    #[test]
+    fn adds_the_scheme() {
+        let validation = validate_link(" example.com/docs ", &HashSet::new());
+        assert!(validation.is_valid());
+        assert_eq!(validation.url.unwrap().as_str(), "https://example.com/docs");
+        assert_eq!(validation.notes.len(), 1);
+
+        let validation = validate_link("http://example.com/", &HashSet::new());
+        assert_eq!(validation.url.unwrap().as_str(), "http://example.com/");
+        assert!(validation.notes.is_empty());
+    }
+
+    #[test]
+    fn warns_about_a_host_without_a_dot() {
+        let validation = validate_link("intranet/wiki", &HashSet::new());
+        assert!(validation.is_valid());
+        assert!(levels(&validation).contains(&Level::Warning));
+
+        let validation = validate_link("http://localhost:8080/", &HashSet::new());
+        assert!(validation.notes.is_empty());
+    }
+
+    #[test]
+    fn refuses_what_isnt_a_link() {
+        for input in ["", "   ", "example.com/two words", "https://"] {
+            let validation = validate_link(input, &HashSet::new());
+            assert!(!validation.is_valid(), "{input:?}");
+            assert!(levels(&validation).contains(&Level::Error), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn warns_about_a_bookmarked_page() {
+        let known = HashSet::from([canonical("https://example.com/docs")]);
+        let validation = validate_link("www.example.com/docs/#top", &known);
+        assert!(validation.is_valid());
+        assert!(validation
+            .notes
+            .iter()
+            .any(|(_, note)| note == "This page is already bookmarked"));
+    }
+}